@@ -1,6 +1,6 @@
 use super::S3;
 use crate::{
-    helper::into_string,
+    helper::{escape::encode_copy_source, into_string},
     validation::file::{check_dir, check_fullpath},
     Result,
 };
@@ -10,7 +10,7 @@ impl S3 {
         check_fullpath(fullpath).map_err(into_string)?;
         check_fullpath(dest_fullpath).map_err(into_string)?;
 
-        let src = format!("{}/{fullpath}", self.bucket_name);
+        let src = encode_copy_source(&self.bucket_name, fullpath);
 
         self.client
             .copy_object()
@@ -30,7 +30,7 @@ impl S3 {
         let objs = self.get_all(dir).await?;
 
         for obj in objs {
-            let src = format!("{}/{obj}", self.bucket_name);
+            let src = encode_copy_source(&self.bucket_name, &obj);
             let dest = format!("{dest_dir}{}", obj.split_at(dir.len()).1);
             self.client
                 .copy_object()
@@ -9,12 +9,9 @@ use crate::{
 };
 
 impl S3 {
-    pub async fn create_file(&self, fullpath: &str, data: Vec<u8>) -> Result<()> {
+    pub async fn create_file(&self, fullpath: &str, data: Vec<u8>, mime: &str) -> Result<()> {
         check_fullpath(fullpath).map_err(into_string)?;
 
-        let mime = mime_guess::from_path(fullpath)
-            .first_or_octet_stream()
-            .to_string();
         let body = ByteStream::from(data);
 
         self.client
@@ -22,7 +19,7 @@ impl S3 {
             .bucket(&self.bucket_name)
             .key(fullpath)
             .body(body)
-            .content_type(&mime)
+            .content_type(mime)
             .send()
             .await?;
 
@@ -40,4 +40,23 @@ impl S3 {
 
         Ok(())
     }
+
+    // Deletes the objects by their exact keys, as they come from the listing
+    pub async fn delete_keys(&self, keys: &[String]) -> Result<()> {
+        // A single request can only take 1000 keys
+        for chunk in keys.chunks(1000) {
+            let objects = chunk
+                .iter()
+                .map(|k| ObjectIdentifier::builder().key(k).build())
+                .collect::<Vec<_>>();
+            let delete = Delete::builder().set_objects(Some(objects)).build();
+            self.client
+                .delete_objects()
+                .bucket(&self.bucket_name)
+                .delete(delete)
+                .send()
+                .await?;
+        }
+        Ok(())
+    }
 }
@@ -20,6 +20,57 @@ impl S3 {
         Ok(contents)
     }
 
+    // Every object under the prefix with the time it was last written, in milliseconds
+    // Unlike get_all, it goes through every page of the listing
+    pub async fn get_all_with_time(&self, prefix: &str) -> Result<Vec<(String, i64)>> {
+        let mut objects = vec![];
+        let mut continuation_token = None;
+        loop {
+            let res = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket_name)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await?;
+            objects.extend(res.contents().unwrap_or_default().iter().filter_map(|o| {
+                let modified = o.last_modified().map(|t| t.secs() * 1000)?;
+                Some((o.key.clone()?, modified))
+            }));
+            match res.next_continuation_token() {
+                Some(token) if res.is_truncated() => continuation_token = Some(token.to_string()),
+                _ => return Ok(objects),
+            }
+        }
+    }
+
+    // Adds up the sizes of every object under the prefix, through every page of the listing
+    pub async fn get_size_by_prefix(&self, prefix: &str) -> Result<i64> {
+        let mut size = 0;
+        let mut continuation_token = None;
+        loop {
+            let res = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket_name)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await?;
+            size += res
+                .contents()
+                .unwrap_or_default()
+                .iter()
+                .map(|o| o.size)
+                .sum::<i64>();
+            match res.next_continuation_token() {
+                Some(token) if res.is_truncated() => continuation_token = Some(token.to_string()),
+                _ => return Ok(size),
+            }
+        }
+    }
+
     pub async fn get_data_by_key(&self, fullpath: &str) -> Result<ByteStream> {
         check_fullpath(fullpath).map_err(into_string)?;
         let req = self
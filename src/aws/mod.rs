@@ -5,14 +5,9 @@ pub mod get;
 pub mod rename;
 pub mod transfer;
 
-use aws_sdk_s3::{config, Client, Credentials, Region};
+use aws_sdk_s3::{config, Client, Credentials, Endpoint, Region};
 
-use crate::Result;
-
-const S3_KEY_ID: &str = "S3_KEY_ID";
-const S3_KEY_SECRET: &str = "S3_KEY_SECRET";
-const REGION: &str = "REGION";
-const BUCKET_NAME: &str = "BUCKET_NAME";
+use crate::{config::StorageConfig, helper::into_string, Result};
 
 #[derive(Debug, Clone)]
 pub struct S3 {
@@ -21,27 +16,39 @@ pub struct S3 {
 }
 
 impl S3 {
-    pub fn init() -> Result<Self> {
+    pub fn init(config: &StorageConfig) -> Result<Self> {
         Ok(Self {
-            client: Self::get_aws_client()?,
-            bucket_name: std::env::var(BUCKET_NAME)?,
+            client: Self::get_aws_client(config)?,
+            bucket_name: config.bucket.clone(),
         })
     }
 
-    fn get_aws_client() -> Result<Client> {
-        // Get the id secret from env
-        let key_id = std::env::var(S3_KEY_ID)?;
-        let key_secret = std::env::var(S3_KEY_SECRET)?;
-        let region_env = std::env::var(REGION)?;
-
+    fn get_aws_client(config: &StorageConfig) -> Result<Client> {
         // Build the aws cred
-        let cred = Credentials::new(key_id, key_secret, None, None, "get-from-env");
+        let cred = Credentials::new(
+            &config.key_id,
+            &config.key_secret,
+            None,
+            None,
+            "get-from-config",
+        );
 
         // Build the aws config
-        let region = Region::new(region_env);
-        let conf_builder = config::Builder::new()
+        let region = Region::new(config.region.clone());
+        let mut conf_builder = config::Builder::new()
             .region(region)
             .credentials_provider(cred);
+
+        // The S3-compatible servers, like MinIO
+        // This sdk always puts the bucket in the path, the immutable endpoint also stops it from
+        // adding prefixes to the host, which these servers usually do not understand
+        if let Some(endpoint) = &config.endpoint {
+            let uri = endpoint.parse().map_err(into_string)?;
+            conf_builder = conf_builder.endpoint_resolver(match config.force_path_style {
+                true => Endpoint::immutable(uri),
+                false => Endpoint::mutable(uri),
+            });
+        }
         let conf = conf_builder.build();
 
         // Build the aws client
@@ -0,0 +1,125 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use super::search_entry::ResourceKind;
+
+// One entry gives one user a role on one file or folder
+// A role on a folder also covers everything inside of it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AclEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    // The id of the file or folder being shared
+    pub resource: ObjectId,
+
+    pub kind: ResourceKind,
+
+    // The owner of the shared file or folder
+    pub owner: ObjectId,
+
+    // The user that the file or folder is shared with
+    pub grantee: ObjectId,
+
+    pub role: Role,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// The order matters, an editor can do everything that a viewer can
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    #[serde(rename = "viewer")]
+    Viewer,
+    #[serde(rename = "editor")]
+    Editor,
+}
+
+// What the logged in user (or the guest) can do with the requested file or folder
+// It is put in the depot by the file and folder middlewares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Access {
+    Public,
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl From<AclEntry> for Document {
+    fn from(a: AclEntry) -> Self {
+        let kind = a.kind_to_str();
+        let role = a.role_to_str();
+        doc! {
+            "resource": a.resource,
+            "kind": kind,
+            "owner": a.owner,
+            "grantee": a.grantee,
+            "role": role,
+            "createdAt": a.created_at,
+            "updatedAt": a.updated_at,
+        }
+    }
+}
+
+impl From<Role> for Access {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Viewer => Access::Viewer,
+            Role::Editor => Access::Editor,
+        }
+    }
+}
+
+impl AclEntry {
+    pub fn new(
+        resource: ObjectId,
+        kind: ResourceKind,
+        owner: ObjectId,
+        grantee: ObjectId,
+        role: Role,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            resource,
+            kind,
+            owner,
+            grantee,
+            role,
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn kind_to_str(&self) -> &str {
+        match self.kind {
+            ResourceKind::File => "file",
+            ResourceKind::Folder => "folder",
+        }
+    }
+
+    pub fn role_to_str(&self) -> &str {
+        self.role.as_str()
+    }
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+        }
+    }
+}
+
+impl Access {
+    pub fn can_edit(&self) -> bool {
+        *self >= Access::Editor
+    }
+
+    pub fn is_owner(&self) -> bool {
+        *self == Access::Owner
+    }
+}
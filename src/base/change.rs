@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::response::change::ChangeResponse;
+
+use super::{
+    event::{Event, EventKind},
+    file::File,
+    folder::Folder,
+    search_entry::ResourceKind,
+};
+
+// What a sync client has to do with the file or folder
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ChangeAction {
+    #[serde(rename = "created")]
+    Created,
+    #[serde(rename = "updated")]
+    Updated,
+    #[serde(rename = "moved")]
+    Moved,
+    // A tombstone, the file or folder does not exist anymore
+    #[serde(rename = "deleted")]
+    Deleted,
+}
+
+impl ChangeAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChangeAction::Created => "created",
+            ChangeAction::Updated => "updated",
+            ChangeAction::Moved => "moved",
+            ChangeAction::Deleted => "deleted",
+        }
+    }
+}
+
+impl From<EventKind> for ChangeAction {
+    fn from(kind: EventKind) -> Self {
+        match kind {
+            EventKind::Created => ChangeAction::Created,
+            // A restored version is new content, same as an upload
+            EventKind::Updated | EventKind::VersionRestored => ChangeAction::Updated,
+            EventKind::Moved => ChangeAction::Moved,
+            EventKind::Deleted => ChangeAction::Deleted,
+        }
+    }
+}
+
+// One entry of the change log that the sync clients follow
+// Unlike the events, it is stored, so the clients can catch up on what happened while they were away
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    // Given out in order by the change log, the cursors are made of it
+    pub seq: i64,
+
+    pub action: ChangeAction,
+    pub kind: ResourceKind,
+    pub resource: ObjectId,
+    pub owner: ObjectId,
+    pub team: Option<ObjectId>,
+    pub position: String,
+    pub fullpath: String,
+
+    // Only for a move
+    pub previous_fullpath: Option<String>,
+
+    pub created_at: i64,
+}
+
+impl From<Change> for Document {
+    fn from(c: Change) -> Self {
+        let kind = match c.kind {
+            ResourceKind::File => "file",
+            ResourceKind::Folder => "folder",
+        };
+        doc! {
+            "seq": c.seq,
+            "action": c.action.as_str(),
+            "kind": kind,
+            "resource": c.resource,
+            "owner": c.owner,
+            "team": c.team,
+            "position": c.position,
+            "fullpath": c.fullpath,
+            "previousFullpath": c.previous_fullpath,
+            "createdAt": c.created_at,
+        }
+    }
+}
+
+impl Change {
+    // The seq is filled in by the change log when it is written
+    fn new(
+        action: ChangeAction,
+        kind: ResourceKind,
+        resource: ObjectId,
+        owner: ObjectId,
+        team: Option<ObjectId>,
+        position: &str,
+        fullpath: &str,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            seq: 0,
+            action,
+            kind,
+            resource,
+            owner,
+            team,
+            position: position.to_string(),
+            fullpath: fullpath.to_string(),
+            previous_fullpath: None,
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn from_event(event: &Event) -> Self {
+        let mut change = Self::new(
+            event.kind.into(),
+            event.resource_kind,
+            event.resource,
+            event.owner,
+            event.team,
+            &event.position,
+            &event.fullpath,
+        );
+        change.previous_fullpath = event.previous_fullpath.clone();
+        change
+    }
+
+    pub fn from_file(action: ChangeAction, file: &File) -> Self {
+        Self::new(
+            action,
+            ResourceKind::File,
+            file.id,
+            file.owner,
+            file.team,
+            &file.position,
+            &file.fullpath,
+        )
+    }
+
+    pub fn from_folder(action: ChangeAction, folder: &Folder) -> Self {
+        Self::new(
+            action,
+            ResourceKind::Folder,
+            folder.id,
+            folder.owner,
+            folder.team,
+            &folder.position,
+            &folder.fullpath,
+        )
+    }
+
+    fn moved_from(mut self, previous_fullpath: Option<&String>) -> Option<Self> {
+        let previous_fullpath = previous_fullpath.filter(|p| **p != self.fullpath)?;
+        self.previous_fullpath = Some(previous_fullpath.clone());
+        Some(self)
+    }
+
+    // Everything inside of a folder that was moved in one go, matched by id from before and after the move
+    // What did not change its fullpath is left out
+    pub fn moved_tree(
+        old_folders: &[Folder],
+        new_folders: &[Folder],
+        old_files: &[File],
+        new_files: &[File],
+    ) -> Vec<Self> {
+        let old_folder_paths = old_folders
+            .iter()
+            .map(|f| (f.id, f.fullpath.clone()))
+            .collect::<HashMap<_, _>>();
+        let old_file_paths = old_files
+            .iter()
+            .map(|f| (f.id, f.fullpath.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let folders = new_folders.iter().filter_map(|f| {
+            Self::from_folder(ChangeAction::Moved, f).moved_from(old_folder_paths.get(&f.id))
+        });
+        let files = new_files.iter().filter_map(|f| {
+            Self::from_file(ChangeAction::Moved, f).moved_from(old_file_paths.get(&f.id))
+        });
+        folders.chain(files).collect()
+    }
+
+    pub fn into_response(self) -> ChangeResponse {
+        ChangeResponse::from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{file::Visibility as FileVisibility, folder::Visibility, user::User};
+
+    fn user() -> User {
+        User::new(
+            ObjectId::new(),
+            "alice",
+            "alice@example.com",
+            "Passw0rd!",
+            "",
+            None,
+        )
+        .unwrap()
+    }
+
+    fn moved_folder(folder: &Folder, position: &str) -> Folder {
+        let mut folder = folder.clone();
+        folder.position = position.to_string();
+        folder.fullpath = format!("{position}{}/", folder.folder_name);
+        folder
+    }
+
+    fn moved_file(file: &File, position: &str) -> File {
+        let mut file = file.clone();
+        file.position = position.to_string();
+        file.fullpath = format!("{position}{}", file.full_filename);
+        file
+    }
+
+    // docs/ is moved into archive/, with a folder and two files inside of it
+    #[test]
+    fn gives_every_child_of_a_moved_folder_its_own_move() {
+        let user = user();
+        let inner = Folder::new(
+            ObjectId::new(),
+            &user,
+            None,
+            "a",
+            Visibility::Inherit,
+            "docs/",
+            None,
+        )
+        .unwrap();
+        let deep = File::new(
+            ObjectId::new(),
+            &user,
+            None,
+            "x.txt",
+            FileVisibility::Inherit,
+            "docs/a/",
+            None,
+        )
+        .unwrap();
+        let top = File::new(
+            ObjectId::new(),
+            &user,
+            None,
+            "y.txt",
+            FileVisibility::Inherit,
+            "docs/",
+            None,
+        )
+        .unwrap();
+
+        let changes = Change::moved_tree(
+            std::slice::from_ref(&inner),
+            &[moved_folder(&inner, "alice/archive/docs/")],
+            &[deep.clone(), top.clone()],
+            &[
+                moved_file(&top, "alice/archive/docs/"),
+                moved_file(&deep, "alice/archive/docs/a/"),
+            ],
+        );
+
+        let moves = changes
+            .iter()
+            .map(|c| {
+                assert_eq!(c.action, ChangeAction::Moved);
+                (
+                    c.resource,
+                    c.previous_fullpath.as_deref().unwrap(),
+                    c.fullpath.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            moves,
+            [
+                (inner.id, "alice/docs/a/", "alice/archive/docs/a/"),
+                (top.id, "alice/docs/y.txt", "alice/archive/docs/y.txt"),
+                (deep.id, "alice/docs/a/x.txt", "alice/archive/docs/a/x.txt"),
+            ]
+        );
+        assert_eq!(changes[0].kind, ResourceKind::Folder);
+        assert_eq!(changes[1].kind, ResourceKind::File);
+    }
+
+    // What kept its path, or was not there before the move, did not move
+    #[test]
+    fn leaves_out_what_did_not_move() {
+        let user = user();
+        let kept = File::new(
+            ObjectId::new(),
+            &user,
+            None,
+            "kept.txt",
+            FileVisibility::Inherit,
+            "docs/",
+            None,
+        )
+        .unwrap();
+        let created = File::new(
+            ObjectId::new(),
+            &user,
+            None,
+            "new.txt",
+            FileVisibility::Inherit,
+            "archive/docs/",
+            None,
+        )
+        .unwrap();
+
+        let changes = Change::moved_tree(
+            &[],
+            &[],
+            std::slice::from_ref(&kept),
+            &[kept.clone(), created],
+        );
+
+        assert!(changes.is_empty(), "{changes:?}");
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+
+use crate::response::event::EventResponse;
+
+use super::{file::File, folder::Folder, search_entry::ResourceKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Created,
+    Updated,
+    Moved,
+    Deleted,
+    VersionRestored,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Updated => "updated",
+            EventKind::Moved => "moved",
+            EventKind::Deleted => "deleted",
+            EventKind::VersionRestored => "version_restored",
+        }
+    }
+}
+
+// Something that happened to a file or a folder, published by the services on the event bus
+// It only lives in memory, so the clients that were not connected never get it
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub kind: EventKind,
+    pub resource_kind: ResourceKind,
+    pub resource: ObjectId,
+    pub owner: ObjectId,
+    pub team: Option<ObjectId>,
+    pub position: String,
+    pub fullpath: String,
+
+    // Where a moved file or folder was before
+    pub previous_position: Option<String>,
+    pub previous_fullpath: Option<String>,
+
+    // The users it was shared with directly
+    // Only kept for the deletions, since the shares are gone by the time the clients are checked
+    pub grantees: Vec<ObjectId>,
+
+    pub created_at: i64,
+}
+
+impl Event {
+    pub fn from_file(kind: EventKind, file: &File) -> Self {
+        Self {
+            kind,
+            resource_kind: ResourceKind::File,
+            resource: file.id,
+            owner: file.owner,
+            team: file.team,
+            position: file.position.clone(),
+            fullpath: file.fullpath.clone(),
+            previous_position: None,
+            previous_fullpath: None,
+            grantees: vec![],
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn from_folder(kind: EventKind, folder: &Folder) -> Self {
+        Self {
+            kind,
+            resource_kind: ResourceKind::Folder,
+            resource: folder.id,
+            owner: folder.owner,
+            team: folder.team,
+            position: folder.position.clone(),
+            fullpath: folder.fullpath.clone(),
+            previous_position: None,
+            previous_fullpath: None,
+            grantees: vec![],
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    // A changed fullpath turns the update into a move
+    pub fn moved_from(mut self, position: &str, fullpath: &str) -> Self {
+        if self.fullpath != fullpath {
+            self.kind = EventKind::Moved;
+            self.previous_position = Some(position.to_string());
+            self.previous_fullpath = Some(fullpath.to_string());
+        }
+        self
+    }
+
+    pub fn with_grantees(mut self, grantees: Vec<ObjectId>) -> Self {
+        self.grantees = grantees;
+        self
+    }
+
+    pub fn resource_kind_to_str(&self) -> &str {
+        match self.resource_kind {
+            ResourceKind::File => "file",
+            ResourceKind::Folder => "folder",
+        }
+    }
+
+    // Like file.created or folder.moved, only files have versions so a restore is version.restored
+    pub fn name(&self) -> String {
+        match self.kind {
+            EventKind::VersionRestored => "version.restored".to_string(),
+            kind => format!("{}.{}", self.resource_kind_to_str(), kind.as_str()),
+        }
+    }
+
+    // The dirs that decide who can see the event, a moved resource is seen from both sides
+    pub fn dirs(&self) -> Vec<&str> {
+        let mut dirs = vec![self.position.as_str()];
+        dirs.extend(self.previous_position.as_deref());
+        dirs
+    }
+
+    pub fn into_response(self) -> EventResponse {
+        EventResponse::from(self)
+    }
+}
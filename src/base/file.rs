@@ -1,12 +1,25 @@
-use crate::{helper::into_string, response::file::FileResponse, Result};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    helper::{into_string, position::normalize_path},
+    response::file::FileResponse,
+    Result,
+};
 use chrono::Utc;
 use mongodb::bson::{doc, oid::ObjectId, Document};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
-use crate::validation::file::{check_dir, check_filename, check_full_filename, check_fullpath};
+use crate::validation::file::{
+    check_dir, check_extension, check_filename, check_full_filename, check_fullpath,
+};
 
-use super::user::User;
+use super::{
+    folder::{is_dir_public, Visibility as FolderVisibility},
+    team::{root_name, Team},
+    user::User,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -14,12 +27,23 @@ pub struct File {
     #[serde(rename = "_id")]
     pub id: ObjectId,
 
+    // The user who created the file
     pub owner: ObjectId,
 
+    // Files in the folders of a team belong to the team, not to the user who created them
+    #[serde(default)]
+    pub team: Option<ObjectId>,
+
     #[validate(custom = "check_filename")]
     pub filename: String,
 
-    pub extension: Extension,
+    // The extension can be anything, or even empty for files without an extension
+    #[validate(custom = "check_extension")]
+    pub extension: String,
+
+    // Detected from the name, and from the content when there is one
+    #[serde(default = "default_mime_type")]
+    pub mime_type: String,
 
     pub visibility: Visibility,
 
@@ -32,41 +56,90 @@ pub struct File {
     #[validate(custom = "check_fullpath")]
     pub fullpath: String,
 
+    // Tags and metadata are only changed through their own endpoints,
+    // which is why they are not part of the document used for updates
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+
+    // Who uploaded the file through a drop link, it is empty when the owner uploaded it
+    // Like the tags, it is set once and never part of the document used for updates
+    #[serde(default)]
+    pub uploaded_by: Option<Uploader>,
+
+    // Set while the owner is locked, it is only changed along with the status of the owner
+    #[serde(default)]
+    pub hidden: bool,
+
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
-pub enum Extension {
-    #[serde(rename = "jpg")]
-    Jpg,
-    #[serde(rename = "jpeg")]
-    Jpeg,
-    #[serde(rename = "png")]
-    Png,
-    #[serde(rename = "txt")]
-    Txt,
-    #[serde(rename = "mp3")]
-    Mp3,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
+// Inherit follows the closest folder above that is either public or private
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum Visibility {
     #[serde(rename = "public")]
     Public,
     #[serde(rename = "private")]
     Private,
+    #[serde(rename = "inherit")]
+    Inherit,
+}
+
+// A visitor of a drop link has to leave at least a name or an email
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Uploader {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+fn default_mime_type() -> String {
+    "application/octet-stream".to_string()
+}
+
+// Splits a full filename into the name and the extension, at the last dot
+// archive.tar.gz gives archive.tar and gz
+// Names without a dot, or with only a leading dot like .env, have no extension
+// Neither do names like "Meeting notes v1.final draft", since an extension is short and has no spaces
+pub fn split_full_filename(full_filename: &str) -> (&str, &str) {
+    match full_filename.rsplit_once('.') {
+        Some((filename, extension))
+            if !filename.is_empty()
+                && (1..=16).contains(&extension.len())
+                && extension.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            (filename, extension)
+        }
+        _ => (full_filename, ""),
+    }
+}
+
+// The name of the nth copy of a file, with the number in front of the extension
+// report.pdf gives report (2).pdf, and the first one keeps its own name
+pub fn numbered_full_filename(full_filename: &str, number: usize) -> String {
+    if number <= 1 {
+        return full_filename.to_string();
+    }
+    match split_full_filename(full_filename) {
+        (filename, "") => format!("{filename} ({number})"),
+        (filename, extension) => format!("{filename} ({number}).{extension}"),
+    }
 }
 
 impl From<File> for Document {
     fn from(f: File) -> Self {
-        let extension = f.extension_to_str();
+        let extension = f.extension_to_str().to_string();
         let visibility = f.visibility_to_str();
 
         doc! {
             "owner": f.owner,
+            "team": f.team,
             "filename": f.filename.clone(),
             "extension": extension,
+            "mimeType": f.mime_type.clone(),
             "visibility": visibility,
             "fullFilename": f.full_filename,
             "position": f.position,
@@ -86,37 +159,39 @@ impl File {
     pub fn new(
         id: ObjectId,
         owner: &User,
+        team: Option<&Team>,
         full_filename: &str,
         visibility: Visibility,
         position: &str,
         created_at: Option<i64>,
     ) -> Result<Self> {
+        let full_filename = &normalize_path(full_filename);
+        let position = &normalize_path(position);
         check_full_filename(full_filename).map_err(into_string)?;
         check_dir(position).map_err(into_string)?;
-        let (filename, extension) = full_filename
-            .split_once('.')
-            .ok_or("Cannot extract filename and extension from the full filename")?;
-
-        let extension = match extension {
-            "jpeg" => Extension::Jpeg,
-            "jpg" => Extension::Jpg,
-            "png" => Extension::Png,
-            "txt" => Extension::Txt,
-            "mp3" => Extension::Mp3,
-            _ => return Err("Invalid extension".into()),
-        };
+        let (filename, extension) = split_full_filename(full_filename);
 
-        let position = &format!("{}/{}", owner.username, position);
+        let mime_type = mime_guess::from_path(full_filename)
+            .first_or_octet_stream()
+            .to_string();
+
+        let position = &format!("{}/{}", root_name(owner, team), position);
 
         let file = File {
             id,
             owner: owner.id,
+            team: team.map(|t| t.id),
             filename: filename.to_string(),
-            extension,
+            extension: extension.to_string(),
+            mime_type,
             visibility,
             full_filename: full_filename.to_string(),
             position: position.to_string(),
             fullpath: format!("{position}{full_filename}"),
+            tags: vec![],
+            metadata: BTreeMap::new(),
+            uploaded_by: None,
+            hidden: false,
             created_at: created_at.unwrap_or_else(|| Utc::now().timestamp_millis()),
             updated_at: Utc::now().timestamp_millis(),
         };
@@ -125,19 +200,48 @@ impl File {
     }
 
     pub fn extension_to_str(&self) -> &str {
-        match self.extension {
-            Extension::Jpeg => "jpeg",
-            Extension::Jpg => "jpg",
-            Extension::Png => "png",
-            Extension::Txt => "txt",
-            Extension::Mp3 => "mp3",
+        &self.extension
+    }
+
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.extension.eq_ignore_ascii_case(extension)
+    }
+
+    // The key of the current data of the file on the storage
+    // It is the id of the file, plus the extension if the file has one
+    pub fn internal_path(&self) -> String {
+        match self.extension.is_empty() {
+            true => self.id.to_string(),
+            false => format!("{}.{}", self.id, self.extension),
+        }
+    }
+
+    // The folder on the storage that holds all of the versions of the file
+    pub fn internal_version_folder(&self) -> String {
+        format!("{}/", self.id)
+    }
+
+    // The key of a specific version of the file on the storage
+    pub fn internal_version_path(&self, version: i64) -> String {
+        match self.extension.is_empty() {
+            true => format!("{}/{version}", self.id),
+            false => format!("{}/{version}.{}", self.id, self.extension),
         }
     }
 
     pub fn visibility_to_str(&self) -> &str {
+        self.visibility.as_str()
+    }
+
+    pub fn is_public(&self, dir_visibilities: &HashMap<String, FolderVisibility>) -> bool {
+        // Nothing of a locked user is public, whatever the visibility says
+        if self.hidden {
+            return false;
+        }
         match self.visibility {
-            Visibility::Public => "public",
-            Visibility::Private => "private",
+            Visibility::Public => true,
+            Visibility::Private => false,
+            Visibility::Inherit => is_dir_public(&self.position, dir_visibilities),
         }
     }
 
@@ -145,3 +249,40 @@ impl File {
         FileResponse::try_from(self)
     }
 }
+
+impl Visibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+            Visibility::Inherit => "inherit",
+        }
+    }
+}
+
+impl From<FolderVisibility> for Visibility {
+    fn from(v: FolderVisibility) -> Self {
+        match v {
+            FolderVisibility::Public => Visibility::Public,
+            FolderVisibility::Private => Visibility::Private,
+            FolderVisibility::Inherit => Visibility::Inherit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_the_copies_before_the_extension() {
+        assert_eq!(numbered_full_filename("report.pdf", 1), "report.pdf");
+        assert_eq!(numbered_full_filename("report.pdf", 2), "report (2).pdf");
+        assert_eq!(
+            numbered_full_filename("archive.tar.gz", 3),
+            "archive.tar (3).gz"
+        );
+        assert_eq!(numbered_full_filename("README", 2), "README (2)");
+        assert_eq!(numbered_full_filename(".env", 2), ".env (2)");
+    }
+}
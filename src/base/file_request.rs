@@ -0,0 +1,94 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::{helper::hash::random_token, response::file_request::FileRequestResponse, Result};
+
+use super::share_link::LINK_TOKEN_LENGTH;
+
+// A drop link lets everyone who knows the token upload files into a folder,
+// without being able to see what is inside of it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRequest {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    pub token: String,
+
+    // The folder that receives the uploads
+    pub folder: ObjectId,
+
+    // The user who created the request
+    pub creator: ObjectId,
+
+    // In bytes, for every uploaded file
+    pub max_file_size: Option<i64>,
+
+    // How many files can be uploaded through the link in total
+    pub max_files: Option<i64>,
+
+    // Only ever changed with $inc, so that concurrent uploads cannot go over max_files
+    pub upload_count: i64,
+
+    // In milliseconds, a request without expiry lasts until it is deleted
+    pub expires_at: Option<i64>,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<FileRequest> for Document {
+    fn from(r: FileRequest) -> Self {
+        doc! {
+            "token": r.token,
+            "folder": r.folder,
+            "creator": r.creator,
+            "maxFileSize": r.max_file_size,
+            "maxFiles": r.max_files,
+            "uploadCount": r.upload_count,
+            "expiresAt": r.expires_at,
+            "createdAt": r.created_at,
+            "updatedAt": r.updated_at,
+        }
+    }
+}
+
+impl FileRequest {
+    pub fn new(
+        folder: ObjectId,
+        creator: ObjectId,
+        max_file_size: Option<i64>,
+        max_files: Option<i64>,
+        expires_at: Option<i64>,
+    ) -> Result<Self> {
+        if expires_at.is_some_and(|e| e <= Utc::now().timestamp_millis()) {
+            return Err("The expiry time must be in the future".into());
+        }
+        if max_file_size.is_some_and(|s| s <= 0) || max_files.is_some_and(|f| f <= 0) {
+            return Err("The limits must be greater than 0".into());
+        }
+
+        Ok(Self {
+            id: ObjectId::new(),
+            token: random_token(LINK_TOKEN_LENGTH),
+            folder,
+            creator,
+            max_file_size,
+            max_files,
+            upload_count: 0,
+            expires_at,
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|e| e <= Utc::now().timestamp_millis())
+    }
+
+    pub fn into_response(self) -> Result<FileRequestResponse> {
+        FileRequestResponse::try_from(self)
+    }
+}
@@ -1,15 +1,23 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::Utc;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::{doc, Document};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::helper::into_string;
+use crate::helper::{
+    into_string,
+    position::{get_ancestor_dirs, normalize_path},
+};
 use crate::response::folder::FolderResponse;
 use crate::validation::file::{check_dir, check_folder_name};
 use crate::Result;
 
-use super::user::User;
+use super::{
+    team::{root_name, Team},
+    user::User,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -17,28 +25,53 @@ pub struct Folder {
     #[serde(rename = "_id")]
     pub id: ObjectId,
 
+    // The user who created the folder
     pub owner: ObjectId,
 
+    // Folders in the tree of a team belong to the team, not to the user who created them
+    #[serde(default)]
+    pub team: Option<ObjectId>,
+
     #[validate(custom = "check_folder_name")]
     pub folder_name: String,
 
     pub visibility: Visibility,
 
+    // The visibility given to the files and folders created directly inside this folder,
+    // when they do not ask for one
+    #[serde(default)]
+    pub child_visibility: Option<Visibility>,
+
+    // Set while the owner is locked, it is only changed along with the status of the owner
+    #[serde(default)]
+    pub hidden: bool,
+
     #[validate(custom = "check_dir")]
     pub position: String,
     #[validate(custom = "check_dir")]
     pub fullpath: String,
 
+    // Tags and metadata are only changed through their own endpoints,
+    // which is why they are not part of the document used for updates
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+// Inherit follows the closest folder above that is either public or private
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 pub enum Visibility {
     #[serde(rename = "public")]
     Public,
     #[serde(rename = "private")]
     Private,
+    #[serde(rename = "inherit")]
+    Inherit,
 }
 
 impl From<Folder> for Document {
@@ -47,7 +80,9 @@ impl From<Folder> for Document {
 
         doc! {
             "visibility": visibility,
+            "childVisibility": f.child_visibility.map(Visibility::as_str),
             "owner": f.owner,
+            "team": f.team,
             "folderName": f.folder_name,
             "position": f.position,
             "fullpath": f.fullpath,
@@ -61,23 +96,31 @@ impl Folder {
     pub fn new(
         id: ObjectId,
         owner: &User,
+        team: Option<&Team>,
         folder_name: &str,
         visibility: Visibility,
         position: &str,
         created_at: Option<i64>,
     ) -> Result<Self> {
+        let folder_name = &normalize_path(folder_name);
+        let position = &normalize_path(position);
         check_folder_name(folder_name).map_err(into_string)?;
         check_dir(position).map_err(into_string)?;
 
-        let position = format!("{}/{position}", owner.username);
+        let position = format!("{}/{position}", root_name(owner, team));
         let fullpath = format!("{position}{folder_name}/");
         let folder = Folder {
             id,
             owner: owner.id,
+            team: team.map(|t| t.id),
             visibility,
+            child_visibility: None,
+            hidden: false,
             folder_name: folder_name.to_string(),
             position,
             fullpath,
+            tags: vec![],
+            metadata: BTreeMap::new(),
             created_at: created_at.unwrap_or_else(|| Utc::now().timestamp_millis()),
             updated_at: Utc::now().timestamp_millis(),
         };
@@ -94,10 +137,36 @@ impl Folder {
         let root = Self {
             id: folder_id,
             owner: owner.id,
+            team: None,
             folder_name: owner.username.clone(),
             visibility: Visibility::Private,
+            child_visibility: None,
+            hidden: false,
             position: format!("{}/", owner.username),
             fullpath: format!("{}/", owner.username),
+            tags: vec![],
+            metadata: BTreeMap::new(),
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        root.validate()?;
+        Ok(root)
+    }
+
+    // Every team has its own root folder, @name/, created along with the team
+    pub fn new_team_root(owner: &User, team: &Team) -> Result<Self> {
+        let root = Self {
+            id: ObjectId::new(),
+            owner: owner.id,
+            team: Some(team.id),
+            folder_name: team.root_name(),
+            visibility: Visibility::Private,
+            child_visibility: None,
+            hidden: false,
+            position: format!("{}/", team.root_name()),
+            fullpath: format!("{}/", team.root_name()),
+            tags: vec![],
+            metadata: BTreeMap::new(),
             created_at: Utc::now().timestamp_millis(),
             updated_at: Utc::now().timestamp_millis(),
         };
@@ -106,9 +175,143 @@ impl Folder {
     }
 
     pub fn visibility_to_str(&self) -> &str {
+        self.visibility.as_str()
+    }
+
+    // Only meaningful for a folder whose own visibility is inherit
+    pub fn is_public(&self, dir_visibilities: &HashMap<String, Visibility>) -> bool {
+        // Nothing of a locked user is public, whatever the visibility says
+        if self.hidden {
+            return false;
+        }
         match self.visibility {
+            Visibility::Public => true,
+            Visibility::Private => false,
+            Visibility::Inherit => is_dir_public(&self.position, dir_visibilities),
+        }
+    }
+}
+
+impl Visibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
             Visibility::Public => "public",
             Visibility::Private => "private",
+            Visibility::Inherit => "inherit",
         }
     }
 }
+
+// Follows the inherited visibility up from the dir to the closest folder that is public or private
+// The dir_visibilities map the fullpath of the folders above the dir to their own visibility
+// The root folders are private, so is a dir where nothing above it decides
+pub fn is_dir_public(dir: &str, dir_visibilities: &HashMap<String, Visibility>) -> bool {
+    get_ancestor_dirs(dir)
+        .iter()
+        .rev()
+        .find_map(|d| match dir_visibilities.get(d) {
+            Some(Visibility::Public) => Some(true),
+            Some(Visibility::Private) => Some(false),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helper::position::get_ancestor_dirs;
+
+    fn visibilities(folders: &[(&str, Visibility)]) -> HashMap<String, Visibility> {
+        folders
+            .iter()
+            .map(|(fullpath, visibility)| (fullpath.to_string(), *visibility))
+            .collect()
+    }
+
+    #[test]
+    fn lists_every_dir_from_the_root_down() {
+        assert_eq!(
+            get_ancestor_dirs("alice/a/b/"),
+            ["alice/", "alice/a/", "alice/a/b/"]
+        );
+        assert!(get_ancestor_dirs("").is_empty());
+    }
+
+    // alice/ is private, like every root, docs/ decides, and a/ and b/ inherit from it
+    #[test]
+    fn follows_inherit_up_to_the_closest_folder_that_decides() {
+        let public = visibilities(&[
+            ("alice/", Visibility::Private),
+            ("alice/docs/", Visibility::Public),
+            ("alice/docs/a/", Visibility::Inherit),
+            ("alice/docs/a/b/", Visibility::Inherit),
+        ]);
+        assert!(is_dir_public("alice/docs/a/b/", &public));
+
+        let private = visibilities(&[
+            ("alice/", Visibility::Public),
+            ("alice/docs/", Visibility::Private),
+            ("alice/docs/a/", Visibility::Inherit),
+            ("alice/docs/a/b/", Visibility::Inherit),
+        ]);
+        assert!(!is_dir_public("alice/docs/a/b/", &private));
+
+        // The closest one wins over the ones further up
+        let nested = visibilities(&[
+            ("alice/", Visibility::Private),
+            ("alice/docs/", Visibility::Private),
+            ("alice/docs/a/", Visibility::Public),
+        ]);
+        assert!(is_dir_public("alice/docs/a/", &nested));
+        assert!(!is_dir_public("alice/docs/", &nested));
+    }
+
+    // When nothing decides, not even the root, it is private
+    #[test]
+    fn treats_a_root_that_inherits_as_private() {
+        let inherit = visibilities(&[
+            ("alice/", Visibility::Inherit),
+            ("alice/docs/", Visibility::Inherit),
+        ]);
+        assert!(!is_dir_public("alice/docs/", &inherit));
+        assert!(!is_dir_public("alice/docs/", &HashMap::new()));
+    }
+
+    #[test]
+    fn resolves_the_visibility_of_an_inherit_folder() {
+        let user = User::new(
+            ObjectId::new(),
+            "alice",
+            "alice@example.com",
+            "Passw0rd!",
+            "",
+            None,
+        )
+        .unwrap();
+        let dir_visibilities = visibilities(&[
+            ("alice/", Visibility::Private),
+            ("alice/docs/", Visibility::Public),
+        ]);
+
+        let mut folder = Folder::new(
+            ObjectId::new(),
+            &user,
+            None,
+            "a",
+            Visibility::Inherit,
+            "docs/",
+            None,
+        )
+        .unwrap();
+        assert!(folder.is_public(&dir_visibilities));
+
+        folder.visibility = Visibility::Private;
+        assert!(!folder.is_public(&dir_visibilities));
+
+        // A locked user has nothing public
+        folder.visibility = Visibility::Public;
+        folder.hidden = true;
+        assert!(!folder.is_public(&dir_visibilities));
+    }
+}
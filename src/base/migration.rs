@@ -0,0 +1,23 @@
+use mongodb::bson::{doc, Document};
+use serde::{Deserialize, Serialize};
+
+// A schema migration that has been applied, kept so that it never runs twice
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Migration {
+    #[serde(rename = "_id")]
+    pub name: String,
+    pub applied_at: i64,
+    // How many documents it changed
+    pub modified: u64,
+}
+
+impl From<Migration> for Document {
+    fn from(m: Migration) -> Self {
+        doc! {
+            "_id": m.name,
+            "appliedAt": m.applied_at,
+            "modified": m.modified as i64,
+        }
+    }
+}
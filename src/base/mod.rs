@@ -1,4 +1,17 @@
+pub mod acl;
+pub mod change;
+pub mod event;
 pub mod file;
+pub mod file_request;
 pub mod file_version;
 pub mod folder;
+pub mod migration;
+pub mod oidc_login;
+pub mod reconciliation;
+pub mod search_entry;
+pub mod share_link;
+pub mod team;
 pub mod user;
+pub mod user_token;
+pub mod webhook;
+pub mod webhook_delivery;
@@ -0,0 +1,42 @@
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::helper::hash::random_token;
+
+// How long the user has to finish the login at the provider, in milliseconds
+pub const OIDC_LOGIN_LIFETIME: i64 = 10 * 60 * 1000;
+
+// A login that has been sent to the provider, and waits for it to come back
+// The state ties the callback to this login, and it can only be used once
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcLogin {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    pub state: String,
+
+    pub provider: String,
+
+    // Must come back inside the ID token, so that a token cannot be replayed into another login
+    pub nonce: String,
+
+    // The PKCE secret, only its hash was sent to the provider
+    pub code_verifier: String,
+
+    pub created_at: i64,
+}
+
+impl OidcLogin {
+    pub fn new(provider: &str) -> Self {
+        Self {
+            id: ObjectId::new(),
+            state: random_token(32),
+            provider: provider.to_string(),
+            nonce: random_token(32),
+            code_verifier: random_token(64),
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use mongodb::bson::oid::ObjectId;
+
+// What does not line up between the storage and the database
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    // Objects on the storage that no file or version points to
+    pub orphan_objects: Vec<String>,
+
+    // Files without their current content on the storage
+    // The empty uploads never have one, so they show up here as well
+    pub missing_contents: Vec<ObjectId>,
+
+    // Versions without their content on the storage, or whose file does not exist anymore
+    pub broken_versions: Vec<ObjectId>,
+
+    // Files whose owner does not exist anymore
+    pub ownerless_files: Vec<ObjectId>,
+}
+
+impl Reconciliation {
+    pub fn is_clean(&self) -> bool {
+        self.orphan_objects.is_empty()
+            && self.missing_contents.is_empty()
+            && self.broken_versions.is_empty()
+            && self.ownerless_files.is_empty()
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use super::{file::File, folder::Folder};
+
+// The search index keeps one entry for every file and folder
+// Only the text that can be searched is stored here, the real data stays in the File and Folder collections
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    // The id of the file or folder that this entry points to
+    pub resource: ObjectId,
+
+    pub kind: ResourceKind,
+
+    pub owner: ObjectId,
+
+    // The team whose tree the file or folder is in, for the members to find it
+    #[serde(default)]
+    pub team: Option<ObjectId>,
+
+    // The visibility is only used to narrow down the search early,
+    // the actual file or folder is still checked before returning the result
+    pub visibility: String,
+
+    pub name: String,
+
+    // Only text files have their content indexed
+    pub content: String,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ResourceKind {
+    #[serde(rename = "file")]
+    File,
+    #[serde(rename = "folder")]
+    Folder,
+}
+
+// A search entry with its relevance score, returned from the text search
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub entry: SearchEntry,
+    pub score: f64,
+}
+
+impl From<SearchEntry> for Document {
+    fn from(s: SearchEntry) -> Self {
+        let kind = s.kind_to_str();
+        doc! {
+            "resource": s.resource,
+            "kind": kind,
+            "owner": s.owner,
+            "team": s.team,
+            "visibility": s.visibility,
+            "name": s.name,
+            "content": s.content,
+            "createdAt": s.created_at,
+            "updatedAt": s.updated_at,
+        }
+    }
+}
+
+impl SearchEntry {
+    pub fn from_file(file: &File, content: String) -> Self {
+        Self {
+            id: ObjectId::new(),
+            resource: file.id,
+            kind: ResourceKind::File,
+            owner: file.owner,
+            team: file.team,
+            visibility: file.visibility_to_str().to_string(),
+            name: file.full_filename.clone(),
+            content,
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn from_folder(folder: &Folder) -> Self {
+        Self {
+            id: ObjectId::new(),
+            resource: folder.id,
+            kind: ResourceKind::Folder,
+            owner: folder.owner,
+            team: folder.team,
+            visibility: folder.visibility_to_str().to_string(),
+            name: folder.folder_name.clone(),
+            content: String::new(),
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn kind_to_str(&self) -> &str {
+        match self.kind {
+            ResourceKind::File => "file",
+            ResourceKind::Folder => "folder",
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    helper::hash::{hash_password, random_token, verify_password},
+    response::share_link::ShareLinkResponse,
+    Result,
+};
+
+pub const LINK_TOKEN_LENGTH: usize = 32;
+
+// A link gives everyone who knows the token access to a folder and everything inside of it,
+// without having to log in
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    pub token: String,
+
+    // The folder that the link exposes
+    pub folder: ObjectId,
+
+    // The user who created the link
+    pub creator: ObjectId,
+
+    // The argon2 hash of the password, never the password itself
+    pub password_hash: Option<String>,
+
+    // Whether the visitors can upload files into the folder
+    pub allow_upload: bool,
+
+    // In milliseconds, a link without expiry lasts until it is deleted
+    pub expires_at: Option<i64>,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<ShareLink> for Document {
+    fn from(l: ShareLink) -> Self {
+        doc! {
+            "token": l.token,
+            "folder": l.folder,
+            "creator": l.creator,
+            "passwordHash": l.password_hash,
+            "allowUpload": l.allow_upload,
+            "expiresAt": l.expires_at,
+            "createdAt": l.created_at,
+            "updatedAt": l.updated_at,
+        }
+    }
+}
+
+impl ShareLink {
+    pub fn new(
+        folder: ObjectId,
+        creator: ObjectId,
+        password: Option<&str>,
+        allow_upload: bool,
+        expires_at: Option<i64>,
+    ) -> Result<Self> {
+        if expires_at.is_some_and(|e| e <= Utc::now().timestamp_millis()) {
+            return Err("The expiry time must be in the future".into());
+        }
+
+        Ok(Self {
+            id: ObjectId::new(),
+            token: random_token(LINK_TOKEN_LENGTH),
+            folder,
+            creator,
+            password_hash: password.map(hash_password).transpose()?,
+            allow_upload,
+            expires_at,
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|e| e <= Utc::now().timestamp_millis())
+    }
+
+    // A link without a password lets everyone in
+    pub fn check_password(&self, password: Option<&str>) -> Result<()> {
+        match (&self.password_hash, password) {
+            (None, _) => Ok(()),
+            (Some(hash), Some(password)) if verify_password(password, hash) => Ok(()),
+            (Some(_), Some(_)) => Err(Error::Unauthorized("The password is incorrect".into())),
+            (Some(_), None) => Err(Error::Unauthorized(
+                "This link is protected by a password".into(),
+            )),
+        }
+    }
+
+    pub fn into_response(self) -> Result<ShareLinkResponse> {
+        ShareLinkResponse::try_from(self)
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::validation::team::check_team_name;
+use crate::{response::team::TeamResponse, Result};
+
+use super::user::User;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    // The root folder of the team is @name/, so it never clashes with the root of a user
+    #[validate(custom = "check_team_name")]
+    pub name: String,
+
+    pub members: Vec<TeamMember>,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMember {
+    pub user: ObjectId,
+    pub role: TeamRole,
+    pub joined_at: i64,
+}
+
+// The order matters, an owner can do everything that an admin can, and so on
+// There is exactly one owner in a team
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TeamRole {
+    #[serde(rename = "member")]
+    Member,
+    #[serde(rename = "admin")]
+    Admin,
+    #[serde(rename = "owner")]
+    Owner,
+}
+
+impl From<Team> for Document {
+    fn from(t: Team) -> Self {
+        let members = t
+            .members
+            .iter()
+            .map(|m| m.clone().into())
+            .collect::<Vec<Document>>();
+        doc! {
+            "name": t.name,
+            "members": members,
+            "createdAt": t.created_at,
+            "updatedAt": t.updated_at,
+        }
+    }
+}
+
+impl From<TeamMember> for Document {
+    fn from(m: TeamMember) -> Self {
+        doc! {
+            "user": m.user,
+            "role": m.role.as_str(),
+            "joinedAt": m.joined_at,
+        }
+    }
+}
+
+impl Team {
+    // The user creating the team becomes its owner
+    pub fn new(id: ObjectId, name: &str, owner: &User) -> Result<Self> {
+        let team = Self {
+            id,
+            name: name.to_string(),
+            members: vec![TeamMember::new(owner.id, TeamRole::Owner)],
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        team.validate()?;
+        Ok(team)
+    }
+
+    pub fn root_name(&self) -> String {
+        format!("@{}", self.name)
+    }
+
+    pub fn role_of(&self, user: &ObjectId) -> Option<TeamRole> {
+        self.members
+            .iter()
+            .find(|m| m.user == *user)
+            .map(|m| m.role)
+    }
+
+    pub fn owner(&self) -> Result<&ObjectId> {
+        let owner = self
+            .members
+            .iter()
+            .find(|m| m.role == TeamRole::Owner)
+            .ok_or("The team does not have an owner")?;
+        Ok(&owner.user)
+    }
+
+    pub fn into_response(self) -> Result<TeamResponse> {
+        TeamResponse::try_from(self)
+    }
+}
+
+impl TeamMember {
+    pub fn new(user: ObjectId, role: TeamRole) -> Self {
+        Self {
+            user,
+            role,
+            joined_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+impl TeamRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TeamRole::Member => "member",
+            TeamRole::Admin => "admin",
+            TeamRole::Owner => "owner",
+        }
+    }
+}
+
+// The first folder of every path, which is the username, or @name for a team
+pub fn root_name(owner: &User, team: Option<&Team>) -> String {
+    match team {
+        Some(team) => team.root_name(),
+        None => owner.username.clone(),
+    }
+}
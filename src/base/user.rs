@@ -5,7 +5,11 @@ use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::validation::user::{check_password, check_username};
-use crate::{response::user::UserResponse, Result};
+use crate::{
+    error::Error,
+    response::user::{UsageResponse, UserResponse},
+    Result,
+};
 
 #[derive(Debug, Deserialize, Serialize, Validate, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -19,15 +23,140 @@ pub struct User {
     #[validate(email(message = "Email must be in correct form"))]
     pub email: String,
 
+    // Only set through a verification token, and cleared again when the email changes
+    #[serde(default)]
+    pub email_verified: bool,
+
     #[validate(custom = "check_password")]
     pub password: String,
 
     pub refresh_token: String,
 
+    // The role and the suspension are only changed through the admin endpoints,
+    // which is why they are not part of the document used for updates
+    #[serde(default)]
+    pub role: Role,
+
+    // A locked user cannot log in, and the sessions they had are rejected
+    #[serde(default)]
+    pub status: Status,
+
+    // When the account is purged, only set while the deletion is pending
+    #[serde(default)]
+    pub deletion_scheduled_at: Option<i64>,
+
+    // Only changed through the two-factor endpoints, like the status
+    #[serde(default)]
+    pub two_factor: Option<TwoFactor>,
+
+    // The accounts at the identity providers that can log in as this user
+    #[serde(default)]
+    pub identities: Vec<Identity>,
+
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Role {
+    #[default]
+    #[serde(rename = "user")]
+    User,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+// Suspended is set by the admins, pending deletion by the user deleting their account
+// Both lock the account and hide its public content, but only the user can cancel a deletion
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Status {
+    #[default]
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "suspended")]
+    Suspended,
+    #[serde(rename = "pendingDeletion")]
+    PendingDeletion,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Active => "active",
+            Status::Suspended => "suspended",
+            Status::PendingDeletion => "pendingDeletion",
+        }
+    }
+}
+
+// The secret is stored as soon as the user enrolls,
+// but it is only asked for on login after the user has verified a first code
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactor {
+    pub secret: String,
+    pub enabled: bool,
+
+    // Hashed, each of them can be used once in place of a code
+    pub recovery_codes: Vec<String>,
+
+    // The time step of the last accepted code, so that a code cannot be used twice
+    pub last_step: i64,
+}
+
+impl From<TwoFactor> for Document {
+    fn from(t: TwoFactor) -> Self {
+        doc! {
+            "secret": t.secret,
+            "enabled": t.enabled,
+            "recoveryCodes": t.recovery_codes,
+            "lastStep": t.last_step,
+        }
+    }
+}
+
+// The subject is the id of the user at the provider, which never changes unlike the email
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    pub provider: String,
+    pub subject: String,
+}
+
+impl From<Identity> for Document {
+    fn from(i: Identity) -> Self {
+        doc! {
+            "provider": i.provider,
+            "subject": i.subject,
+        }
+    }
+}
+
+// How much a user stores, the bytes include every version of their files
+#[derive(Debug, Clone, Default)]
+pub struct Usage {
+    pub user: ObjectId,
+    pub files: u64,
+    pub folders: u64,
+    pub versions: u64,
+    pub bytes: i64,
+}
+
+impl Usage {
+    pub fn into_response(self) -> UsageResponse {
+        UsageResponse::from(self)
+    }
+}
+
 impl From<User> for Document {
     fn from(u: User) -> Self {
         doc! {
@@ -55,8 +184,14 @@ impl User {
             id,
             username: username.to_string(),
             email: email.to_string(),
+            email_verified: false,
             password: password.to_string(),
             refresh_token: refresh_token.to_string(),
+            role: Role::User,
+            status: Status::Active,
+            deletion_scheduled_at: None,
+            two_factor: None,
+            identities: vec![],
             created_at: created_at.unwrap_or_else(|| Utc::now().timestamp_millis()),
             updated_at: Utc::now().timestamp_millis(),
         };
@@ -64,6 +199,29 @@ impl User {
         Ok(user)
     }
 
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+
+    pub fn has_two_factor(&self) -> bool {
+        self.two_factor.as_ref().is_some_and(|t| t.enabled)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.status != Status::Active
+    }
+
+    // The reason shown to a locked user when they try to use the account
+    pub fn check_active(&self) -> Result<()> {
+        match self.status {
+            Status::Active => Ok(()),
+            Status::Suspended => Err(Error::Permissions("This account has been suspended".into())),
+            Status::PendingDeletion => Err(Error::Permissions(
+                "This account is scheduled for deletion. Restore it to use it again".into(),
+            )),
+        }
+    }
+
     pub fn into_response(self) -> Result<UserResponse> {
         UserResponse::try_from(self)
     }
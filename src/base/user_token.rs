@@ -0,0 +1,87 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::helper::hash::{hash_token, random_token};
+
+pub const USER_TOKEN_LENGTH: usize = 32;
+
+// A single use token mailed to the user, only its hash is stored
+// It is deleted as soon as it is used
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserToken {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    pub user: ObjectId,
+
+    pub kind: TokenKind,
+
+    pub token_hash: String,
+
+    // The address that the token was sent to,
+    // a verification token stops working once the user changes their email again
+    pub email: String,
+
+    // In milliseconds
+    pub expires_at: i64,
+
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum TokenKind {
+    #[serde(rename = "verifyEmail")]
+    VerifyEmail,
+    #[serde(rename = "resetPassword")]
+    ResetPassword,
+}
+
+impl TokenKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenKind::VerifyEmail => "verifyEmail",
+            TokenKind::ResetPassword => "resetPassword",
+        }
+    }
+
+    // A reset token can take over the account, so it is kept short
+    pub fn lifetime_millis(self) -> i64 {
+        match self {
+            TokenKind::VerifyEmail => 24 * 60 * 60 * 1000,
+            TokenKind::ResetPassword => 60 * 60 * 1000,
+        }
+    }
+}
+
+impl From<UserToken> for Document {
+    fn from(t: UserToken) -> Self {
+        doc! {
+            "user": t.user,
+            "kind": t.kind.as_str(),
+            "tokenHash": t.token_hash,
+            "email": t.email,
+            "expiresAt": t.expires_at,
+            "createdAt": t.created_at,
+        }
+    }
+}
+
+impl UserToken {
+    // Returns the token along with the plain value, which only goes into the mail
+    pub fn new(user: ObjectId, kind: TokenKind, email: &str) -> (Self, String) {
+        let token = random_token(USER_TOKEN_LENGTH);
+        let now = Utc::now().timestamp_millis();
+        let user_token = Self {
+            id: ObjectId::new(),
+            user,
+            kind,
+            token_hash: hash_token(&token),
+            email: email.to_string(),
+            expires_at: now + kind.lifetime_millis(),
+            created_at: now,
+        };
+        (user_token, token)
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::{helper::hash::random_token, response::webhook::WebhookResponse, Result};
+
+pub const WEBHOOK_SECRET_LENGTH: usize = 32;
+
+// The events that can be subscribed to, they are the names of the events on the event bus
+pub const WEBHOOK_EVENTS: [&str; 9] = [
+    "file.created",
+    "file.updated",
+    "file.moved",
+    "file.deleted",
+    "version.restored",
+    "folder.created",
+    "folder.updated",
+    "folder.moved",
+    "folder.deleted",
+];
+
+// A URL that gets a signed POST every time one of its events happens
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    // The user who registered the webhook, it only hears about what they can see
+    pub owner: ObjectId,
+
+    pub url: String,
+
+    // Signs the deliveries, so it is kept as it is instead of hashed
+    pub secret: String,
+
+    pub events: Vec<String>,
+
+    // Only the admins can create one, it hears about everything that happens on the server
+    pub global: bool,
+
+    // A disabled webhook keeps its deliveries but does not get new ones
+    pub active: bool,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<Webhook> for Document {
+    fn from(w: Webhook) -> Self {
+        doc! {
+            "owner": w.owner,
+            "url": w.url,
+            "secret": w.secret,
+            "events": w.events,
+            "global": w.global,
+            "active": w.active,
+            "createdAt": w.created_at,
+            "updatedAt": w.updated_at,
+        }
+    }
+}
+
+impl Webhook {
+    pub fn new(owner: ObjectId, url: &str, events: Vec<String>, global: bool) -> Self {
+        Self {
+            id: ObjectId::new(),
+            owner,
+            url: url.to_string(),
+            secret: format!("whsec_{}", random_token(WEBHOOK_SECRET_LENGTH)),
+            events,
+            global,
+            active: true,
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn is_subscribed_to(&self, event: &str) -> bool {
+        self.active && self.events.iter().any(|e| e == event)
+    }
+
+    // The secret is only shown once, right after the webhook is created
+    pub fn into_response(self) -> Result<WebhookResponse> {
+        WebhookResponse::try_from(self)
+    }
+
+    pub fn into_response_with_secret(self) -> Result<WebhookResponse> {
+        let secret = self.secret.clone();
+        let mut response = WebhookResponse::try_from(self)?;
+        response.secret = Some(secret);
+        Ok(response)
+    }
+}
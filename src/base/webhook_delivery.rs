@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::{response::webhook::WebhookDeliveryResponse, Result};
+
+// The first retry waits this long, and every retry after it twice as long as the one before
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+// Only the start of the body of the receiver is kept, to see what went wrong
+pub const MAX_RESPONSE_BODY: usize = 1024;
+
+// One event sent to one webhook, along with every try to send it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    pub webhook: ObjectId,
+
+    // The same for every webhook that got the event, and kept on a redelivery,
+    // so that the receivers can tell the duplicates apart
+    pub event_id: ObjectId,
+
+    // Like file.created
+    pub event: String,
+
+    // The exact body that is signed and sent
+    pub payload: String,
+
+    pub status: DeliveryStatus,
+
+    pub attempts: Vec<DeliveryAttempt>,
+
+    // In milliseconds, only set while the delivery is pending
+    pub next_attempt_at: Option<i64>,
+
+    // The delivery that this one was sent again from
+    pub redelivery_of: Option<ObjectId>,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "succeeded")]
+    Succeeded,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Succeeded => "succeeded",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    pub attempted_at: i64,
+    // In milliseconds
+    pub duration: i64,
+    // None when the receiver could not be reached at all
+    pub status_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<DeliveryAttempt> for Document {
+    fn from(a: DeliveryAttempt) -> Self {
+        doc! {
+            "attemptedAt": a.attempted_at,
+            "duration": a.duration,
+            "statusCode": a.status_code,
+            "responseBody": a.response_body,
+            "error": a.error,
+        }
+    }
+}
+
+impl DeliveryAttempt {
+    // A try that did not get as far as the receiver
+    pub fn failed(error: &str) -> Self {
+        Self {
+            attempted_at: Utc::now().timestamp_millis(),
+            duration: 0,
+            status_code: None,
+            response_body: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    // Only a 2xx answer counts, the redirects are not followed
+    pub fn is_success(&self) -> bool {
+        self.status_code.is_some_and(|c| (200..300).contains(&c))
+    }
+}
+
+impl From<WebhookDelivery> for Document {
+    fn from(d: WebhookDelivery) -> Self {
+        let attempts = d
+            .attempts
+            .into_iter()
+            .map(|a| a.into())
+            .collect::<Vec<Document>>();
+        doc! {
+            "webhook": d.webhook,
+            "eventId": d.event_id,
+            "event": d.event,
+            "payload": d.payload,
+            "status": d.status.as_str(),
+            "attempts": attempts,
+            "nextAttemptAt": d.next_attempt_at,
+            "redeliveryOf": d.redelivery_of,
+            "createdAt": d.created_at,
+            "updatedAt": d.updated_at,
+        }
+    }
+}
+
+impl WebhookDelivery {
+    pub fn new(webhook: ObjectId, event_id: ObjectId, event: &str, payload: String) -> Self {
+        Self {
+            id: ObjectId::new(),
+            webhook,
+            event_id,
+            event: event.to_string(),
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: vec![],
+            next_attempt_at: Some(Utc::now().timestamp_millis()),
+            redelivery_of: None,
+            created_at: Utc::now().timestamp_millis(),
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    // A fresh delivery of the same payload, with its own tries
+    pub fn redeliver(&self) -> Self {
+        let mut delivery = Self::new(
+            self.webhook,
+            self.event_id,
+            &self.event,
+            self.payload.clone(),
+        );
+        delivery.redelivery_of = Some(self.id);
+        delivery
+    }
+
+    // Where the delivery stands after one more try, the attempt is already in the list
+    // Returns the status and when to try again
+    pub fn after_attempt(&self, max_attempts: usize) -> (DeliveryStatus, Option<i64>) {
+        let tries = self.attempts.len();
+        match self.attempts.last() {
+            Some(attempt) if attempt.is_success() => (DeliveryStatus::Succeeded, None),
+            _ if tries >= max_attempts => (DeliveryStatus::Failed, None),
+            _ => {
+                let delay = FIRST_RETRY_DELAY
+                    .saturating_mul(2u32.saturating_pow(tries.saturating_sub(1) as u32))
+                    .min(MAX_RETRY_DELAY);
+                let next_attempt_at = Utc::now().timestamp_millis() + delay.as_millis() as i64;
+                (DeliveryStatus::Pending, Some(next_attempt_at))
+            }
+        }
+    }
+
+    pub fn into_response(self) -> Result<WebhookDeliveryResponse> {
+        WebhookDeliveryResponse::try_from(self)
+    }
+}
@@ -0,0 +1,288 @@
+// The maintenance tasks of the server, run against the same database and storage without serving anything
+// It reads the same config file and environment as the server
+
+use std::process::ExitCode;
+
+use chrono::Utc;
+use clap::{Parser, Subcommand};
+use dotenv::dotenv;
+use final_project::{
+    aws::S3,
+    base::user::Role,
+    config::Config,
+    db::{
+        self, acl_db::AclDB, change_db::ChangeDB, file_db::FileDB, file_request_db::FileRequestDB,
+        file_version_db::FileVersionDB, folder_db::FolderDB, migration_db::MigrationDB, mongo::DB,
+        search_db::SearchDB, share_link_db::ShareLinkDB, team_db::TeamDB, user_db::UserDB,
+        user_token_db::UserTokenDB, webhook_db::WebhookDB, webhook_delivery_db::WebhookDeliveryDB,
+    },
+    helper::{event_bus::EventBus, log},
+    request::{admin::password::ResetPasswordRequest, user::create::CreateUserRequest},
+    service::{maintenance_service::MaintenanceService, user_service::UserService},
+    Result,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "fmb-admin",
+    about = "Maintenance tasks for the file manager server"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a user, the password is asked for
+    CreateUser {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// Give the user the admin role
+        #[arg(long)]
+        admin: bool,
+    },
+    /// Set a new password for a user, which also ends their sessions
+    ResetPassword { username: String },
+    /// Compare the storage with the database and report what does not line up
+    Reconcile {
+        /// Delete the orphan objects and the broken versions
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Create the indexes of every collection, the existing ones are left as they are
+    RebuildIndexes,
+    /// Write the search entry of every file and folder again, like after a restore of the database
+    ReindexSearch,
+    /// Apply the schema migrations that have not been applied yet
+    Migrate {
+        /// Only list the pending migrations
+        #[arg(long)]
+        list: bool,
+    },
+    /// Delete the older versions of the files
+    PruneVersions {
+        /// How many of the newest versions of each file are kept
+        #[arg(long, default_value_t = 10)]
+        keep: usize,
+        /// Only delete the versions older than this
+        #[arg(long)]
+        older_than_days: Option<i64>,
+    },
+    /// Purge the accounts whose deletion grace period is over
+    ///
+    /// The files and folders have no trash, they are deleted right away,
+    /// the deleted accounts are the only thing waiting to be purged
+    EmptyTrash,
+    /// Print how much each user stores
+    Usage {
+        /// Only this user
+        username: Option<String>,
+    },
+}
+
+// Asks for a password twice, without showing it
+fn prompt_new_password() -> Result<(String, String)> {
+    let password = rpassword::prompt_password("Password: ")?;
+    let confirm_password = rpassword::prompt_password("Confirm password: ")?;
+    Ok((password, confirm_password))
+}
+
+struct Services {
+    db: DB,
+    user_service: UserService,
+    maintenance_service: MaintenanceService,
+}
+
+async fn init_services(config: &Config) -> Result<Services> {
+    let db = DB::init(&config.database).await?;
+    let s3 = S3::init(&config.storage)?;
+    let user_db = UserDB::init(&db);
+    let file_db = FileDB::init(&db);
+    let folder_db = FolderDB::init(&db);
+    let file_version_db = FileVersionDB::init(&db);
+    let search_db = SearchDB::init(&db);
+
+    // Nothing listens to the events here, the change log still gets them
+    let user_service = UserService::init(
+        &user_db,
+        &file_db,
+        &folder_db,
+        &file_version_db,
+        &search_db,
+        &AclDB::init(&db),
+        &TeamDB::init(&db),
+        &ShareLinkDB::init(&db),
+        &FileRequestDB::init(&db),
+        &UserTokenDB::init(&db),
+        &WebhookDB::init(&db),
+        &WebhookDeliveryDB::init(&db),
+        &ChangeDB::init(&db),
+        &s3,
+        &EventBus::default(),
+        config.accounts.deletion_grace_days,
+    );
+    let maintenance_service = MaintenanceService::init(
+        &file_db,
+        &folder_db,
+        &file_version_db,
+        &user_db,
+        &search_db,
+        &MigrationDB::init(&db),
+        &s3,
+    );
+
+    Ok(Services {
+        db,
+        user_service,
+        maintenance_service,
+    })
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    dotenv().ok();
+    // The services log the problems that they skip over, the outcome of the task is printed
+    log::init_logging();
+    let config = Config::load()?;
+    let Services {
+        db,
+        user_service,
+        maintenance_service,
+    } = init_services(&config).await?;
+
+    match cli.command {
+        Command::CreateUser {
+            username,
+            email,
+            admin,
+        } => {
+            let (password, confirm_password) = prompt_new_password()?;
+            let mut user = CreateUserRequest {
+                username,
+                email,
+                password,
+                confirm_password,
+            }
+            .into_user()?;
+
+            // Made by an admin on the server itself, there is nobody to send the verification to
+            user.email_verified = true;
+            if admin {
+                user.role = Role::Admin;
+            }
+
+            let user = user_service.create_user(user).await?;
+            println!("Created the {} {}", user.role.as_str(), user.username);
+        }
+        Command::ResetPassword { username } => {
+            let user = user_service.get_user_by_username(&username).await?;
+            let (new_password, confirm_password) = prompt_new_password()?;
+            let password = ResetPasswordRequest {
+                new_password,
+                confirm_password,
+            }
+            .into_password()?;
+            user_service
+                .reset_password_by_id(&user.id, &password)
+                .await?;
+            println!("Reset the password of {username}, their sessions have ended");
+        }
+        Command::Reconcile { fix } => {
+            let reconciliation = maintenance_service.reconcile(fix).await?;
+            for key in &reconciliation.orphan_objects {
+                println!("Orphan object      {key}");
+            }
+            for id in &reconciliation.missing_contents {
+                println!("Missing content    file {id}");
+            }
+            for id in &reconciliation.broken_versions {
+                println!("Broken version     {id}");
+            }
+            for id in &reconciliation.ownerless_files {
+                println!("Ownerless file     {id}");
+            }
+            match (reconciliation.is_clean(), fix) {
+                (true, _) => println!("The storage and the database line up"),
+                (false, true) => {
+                    println!("Deleted the orphan objects and the broken versions, the files are left as they are")
+                }
+                (false, false) => println!(
+                    "Run again with --fix to delete the orphan objects and the broken versions"
+                ),
+            }
+        }
+        Command::RebuildIndexes => {
+            db::create_indexes(&db).await?;
+            println!("Created the indexes");
+        }
+        Command::ReindexSearch => {
+            let indexed = maintenance_service.reindex_search().await?;
+            println!("Indexed {indexed} files and folders");
+        }
+        Command::Migrate { list: true } => {
+            let pending = maintenance_service.get_pending_migrations().await?;
+            if pending.is_empty() {
+                println!("There are no pending migrations");
+            }
+            for name in pending {
+                println!("{name}");
+            }
+        }
+        Command::Migrate { list: false } => {
+            let applied = maintenance_service.run_migrations().await?;
+            if applied.is_empty() {
+                println!("There are no pending migrations");
+            }
+            for migration in applied {
+                println!(
+                    "Applied {}, {} documents changed",
+                    migration.name, migration.modified
+                );
+            }
+        }
+        Command::PruneVersions {
+            keep,
+            older_than_days,
+        } => {
+            let before = older_than_days
+                .map(|days| Utc::now().timestamp_millis() - days * 24 * 60 * 60 * 1000);
+            let pruned = maintenance_service.prune_versions(keep, before).await?;
+            println!("Deleted {pruned} versions");
+        }
+        Command::EmptyTrash => {
+            let purged = user_service.purge_due_users().await?;
+            println!("Purged {purged} accounts");
+        }
+        Command::Usage { username } => {
+            let users = match username {
+                Some(username) => vec![user_service.get_user_by_username(&username).await?],
+                None => user_service.get_users().await?,
+            };
+            println!(
+                "{:<24} {:>8} {:>8} {:>8} {:>14}",
+                "USER", "FILES", "FOLDERS", "VERSIONS", "BYTES"
+            );
+            for user in users {
+                let usage = user_service.get_usage_by_id(&user.id).await?;
+                println!(
+                    "{:<24} {:>8} {:>8} {:>8} {:>14}",
+                    user.username, usage.files, usage.folders, usage.versions, usage.bytes
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
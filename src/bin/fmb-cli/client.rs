@@ -0,0 +1,292 @@
+use std::path::Path;
+
+use reqwest::{
+    header::{COOKIE, SET_COOKIE},
+    multipart::{Form, Part},
+    RequestBuilder, Response,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tokio::{fs::File, io::AsyncWriteExt};
+
+use crate::{
+    remote::{RemoteFile, RemoteFolder, ShareLink, Tree},
+    Result,
+};
+
+// Added to the files while they are being downloaded
+pub const PARTIAL_EXTENSION: &str = ".fmb-partial";
+
+// How a login ends, either with a session or with a second step
+pub enum Login {
+    Session { refresh_token: String },
+    TwoFactor { challenge_token: String },
+}
+
+// Talks to the REST routes of the server
+// The server only knows the cookies, so the tokens are sent the way a browser would
+pub struct Client {
+    http: reqwest::Client,
+    server: String,
+    access_token: String,
+    refresh_token: String,
+}
+
+// Finds the value of a cookie in the Set-Cookie headers of a response
+// The last one wins, since the server might remove a cookie before setting it again
+fn get_cookie(res: &Response, name: &str) -> Option<String> {
+    res.headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .filter_map(|h| h.split(';').next()?.split_once('='))
+        .filter(|(n, _)| n.trim() == name)
+        .map(|(_, v)| v.trim().to_string())
+        .next_back()
+        .filter(|v| !v.is_empty())
+}
+
+// Every answer of the server is wrapped in the same shape,
+// the errors carry their message in the error field
+async fn into_data<T: DeserializeOwned>(res: Response) -> Result<T> {
+    let status = res.status();
+    let body = res.json::<Value>().await.unwrap_or_default();
+    if !status.is_success() {
+        let error = body["error"].as_str().unwrap_or_default();
+        return Err(match error.is_empty() {
+            true => format!("The server answered with {status}").into(),
+            false => error.into(),
+        });
+    }
+    Ok(serde_json::from_value(body["data"].clone())?)
+}
+
+impl Client {
+    pub fn new(server: &str) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder().build()?,
+            server: server.trim_end_matches('/').to_string(),
+            access_token: String::new(),
+            refresh_token: String::new(),
+        })
+    }
+
+    // Gets a fresh access token for the refresh token, an expired session fails right here
+    pub async fn connect(server: &str, refresh_token: &str) -> Result<Self> {
+        let mut client = Self::new(server)?;
+        client.refresh_token = refresh_token.to_string();
+
+        let res = client.send(client.post("/user/refresh")).await?;
+        let access_token = get_cookie(&res, "accessToken");
+        into_data::<Value>(res).await?;
+        client.access_token = access_token.ok_or("The server did not give back an access token")?;
+        Ok(client)
+    }
+
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.server)
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        self.http.get(self.url(path))
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.http.post(self.url(path))
+    }
+
+    fn put(&self, path: &str) -> RequestBuilder {
+        self.http.put(self.url(path))
+    }
+
+    fn delete(&self, path: &str) -> RequestBuilder {
+        self.http.delete(self.url(path))
+    }
+
+    async fn send(&self, req: RequestBuilder) -> Result<Response> {
+        let req = match self.refresh_token.is_empty() {
+            true => req,
+            false => req.header(
+                COOKIE,
+                format!(
+                    "accessToken={}; refreshToken={}",
+                    self.access_token, self.refresh_token
+                ),
+            ),
+        };
+        Ok(req.send().await?)
+    }
+
+    async fn send_for<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
+        into_data(self.send(req).await?).await
+    }
+
+    fn session_from(res: &Response) -> Result<Login> {
+        let refresh_token =
+            get_cookie(res, "refreshToken").ok_or("The server did not give back a session")?;
+        Ok(Login::Session { refresh_token })
+    }
+
+    pub async fn login(&self, username: &str, password: &str) -> Result<Login> {
+        let res = self
+            .send(
+                self.post("/user/login")
+                    .json(&json!({"username": username, "password": password})),
+            )
+            .await?;
+        let login = Self::session_from(&res);
+        let data = into_data::<Value>(res).await?;
+
+        // With two-factor authentication, there are no cookies yet, only a challenge
+        match data["challengeToken"].as_str() {
+            Some(challenge_token) => Ok(Login::TwoFactor {
+                challenge_token: challenge_token.to_string(),
+            }),
+            None => login,
+        }
+    }
+
+    pub async fn login_two_factor(&self, challenge_token: &str, code: &str) -> Result<Login> {
+        let res = self
+            .send(
+                self.post("/user/login/2fa")
+                    .json(&json!({"challengeToken": challenge_token, "code": code})),
+            )
+            .await?;
+        let login = Self::session_from(&res);
+        into_data::<Value>(res).await?;
+        login
+    }
+
+    pub async fn logout(&self) -> Result<()> {
+        self.send_for::<Value>(self.post("/user/logout")).await?;
+        Ok(())
+    }
+
+    pub async fn get_tree(&self) -> Result<Tree> {
+        let tree = self.send_for::<Tree>(self.get("/user/profile")).await?;
+        Ok(tree.personal())
+    }
+
+    pub async fn get_file(&self, id: &str) -> Result<RemoteFile> {
+        self.send_for(self.get(&format!("/file/{id}"))).await
+    }
+
+    // The whole file is sent in one go, the same way the server reads it
+    async fn file_part(local: &Path) -> Result<Part> {
+        let name = local
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or("The local file does not have a valid name")?
+            .to_string();
+        let bytes = tokio::fs::read(local).await?;
+        Ok(Part::bytes(bytes).file_name(name))
+    }
+
+    // The position is relative to the root of the user, like docs/
+    pub async fn create_file(&self, position: &str, local: &Path) -> Result<RemoteFile> {
+        let form = Form::new()
+            .text("position", position.to_string())
+            .part("file", Self::file_part(local).await?);
+        self.send_for(self.post("/file/create").multipart(form))
+            .await
+    }
+
+    // A new content makes a new version, a new position moves the file
+    pub async fn update_file(
+        &self,
+        file: &RemoteFile,
+        position: Option<&str>,
+        local: Option<&Path>,
+    ) -> Result<RemoteFile> {
+        let mut form = Form::new().text("visibility", file.visibility.clone());
+        if let Some(position) = position {
+            form = form.text("position", position.to_string());
+        }
+        if let Some(local) = local {
+            form = form.part("file", Self::file_part(local).await?);
+        }
+        self.send_for(
+            self.put(&format!("/file/update/{}", file.id))
+                .multipart(form),
+        )
+        .await
+    }
+
+    pub async fn delete_file(&self, id: &str) -> Result<()> {
+        self.send_for::<Value>(self.delete(&format!("/file/delete/{id}")))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_folder(&self, position: &str, folder_name: &str) -> Result<RemoteFolder> {
+        self.send_for(
+            self.post("/folder/create")
+                .json(&json!({"folderName": folder_name, "position": position})),
+        )
+        .await
+    }
+
+    pub async fn update_folder(
+        &self,
+        id: &str,
+        position: &str,
+        folder_name: &str,
+    ) -> Result<RemoteFolder> {
+        self.send_for(
+            self.put(&format!("/folder/update/{id}"))
+                .json(&json!({"folderName": folder_name, "position": position})),
+        )
+        .await
+    }
+
+    pub async fn restore_version(&self, id: &str, version: i64) -> Result<RemoteFile> {
+        self.send_for(self.put(&format!("/file/{id}/versions/restore/{version}")))
+            .await
+    }
+
+    pub async fn create_link(
+        &self,
+        folder_id: &str,
+        password: Option<&str>,
+        allow_upload: bool,
+        expires_at: Option<i64>,
+    ) -> Result<ShareLink> {
+        self.send_for(
+            self.post(&format!("/folder/{folder_id}/links"))
+                .json(&json!({
+                    "password": password,
+                    "allowUpload": allow_upload,
+                    "expiresAt": expires_at,
+                })),
+        )
+        .await
+    }
+
+    // Writes the content of the file, or of one of its versions, to the local path
+    pub async fn download(&self, id: &str, version: Option<i64>, local: &Path) -> Result<()> {
+        let path = match version {
+            Some(version) => format!("/content/{id}/versions/{version}"),
+            None => format!("/content/{id}"),
+        };
+        let mut res = self.send(self.get(&path)).await?;
+        if !res.status().is_success() {
+            return into_data::<Value>(res).await.map(|_| ());
+        }
+
+        // Written next to the target first, so that a failed download does not leave half a file
+        let mut partial = local.as_os_str().to_owned();
+        partial.push(PARTIAL_EXTENSION);
+        let mut file = File::create(&partial).await?;
+        while let Some(chunk) = res.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        tokio::fs::rename(&partial, local).await?;
+        Ok(())
+    }
+}
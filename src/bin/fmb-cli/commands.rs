@@ -0,0 +1,275 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{TimeZone, Utc};
+use walkdir::WalkDir;
+
+use crate::{
+    client::Client,
+    remote::{dir_path, split_path, RemoteFile, RemoteFolder, Tree},
+    Result,
+};
+
+fn format_time(millis: i64) -> String {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn local_name(local: &Path) -> Result<String> {
+    let name = local
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("{} does not have a valid name", local.display()))?;
+    Ok(name.to_string())
+}
+
+pub async fn list(client: &Client, dir: &str) -> Result<()> {
+    let tree = client.get_tree().await?;
+    let dir = dir_path(dir);
+    if !tree.has_dir(&dir) {
+        return Err(format!("There is no folder at {dir}").into());
+    }
+
+    let position = tree.absolute(&dir);
+    for folder in tree.folders.iter().filter(|f| f.position == position) {
+        println!(
+            "{:<16}  {:>24}  {}/",
+            format_time(folder.updated_at),
+            folder.id,
+            folder.folder_name
+        );
+    }
+    for file in tree.files.iter().filter(|f| f.position == position) {
+        println!(
+            "{:<16}  {:>24}  {}",
+            format_time(file.updated_at),
+            file.id,
+            file.full_filename
+        );
+    }
+    Ok(())
+}
+
+// Creates the folders of the dir that do not exist yet, from the top down
+// The new ones are added to the tree, so that they are not created twice
+pub async fn ensure_dir(client: &Client, tree: &mut Tree, dir: &str) -> Result<()> {
+    let mut position = String::new();
+    for name in dir.split('/').filter(|n| !n.is_empty()) {
+        let current = format!("{position}{name}/");
+        if !tree.has_dir(&current) {
+            let folder = client.create_folder(&position, name).await?;
+            tree.folders.push(folder);
+        }
+        position = current;
+    }
+    Ok(())
+}
+
+// An existing file gets a new version instead of a copy
+pub async fn put_file(
+    client: &Client,
+    tree: &mut Tree,
+    dir: &str,
+    local: &Path,
+) -> Result<RemoteFile> {
+    ensure_dir(client, tree, dir).await?;
+    let path = format!("{dir}{}", local_name(local)?);
+    let file = match tree.find_file(&path) {
+        Some(file) => client.update_file(file, None, Some(local)).await?,
+        None => client.create_file(dir, local).await?,
+    };
+
+    tree.files.retain(|f| f.id != file.id);
+    tree.files.push(file.clone());
+    Ok(file)
+}
+
+pub async fn upload(client: &Client, local: &Path, dir: &str) -> Result<()> {
+    let mut tree = client.get_tree().await?;
+    let dir = dir_path(dir);
+
+    if local.is_file() {
+        let file = put_file(client, &mut tree, &dir, local).await?;
+        println!("Uploaded {}", tree.relative(&file.fullpath));
+        return Ok(());
+    }
+
+    // A directory goes in as a folder of the same name, with everything inside of it
+    let base = format!("{dir}{}/", local_name(local)?);
+    ensure_dir(client, &mut tree, &base).await?;
+    for entry in WalkDir::new(local).min_depth(1).sort_by_file_name() {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(local)?;
+        let relative = relative
+            .to_str()
+            .ok_or_else(|| format!("{} does not have a valid name", relative.display()))?
+            .replace('\\', "/");
+
+        if entry.file_type().is_dir() {
+            ensure_dir(client, &mut tree, &format!("{base}{relative}/")).await?;
+        } else if entry.file_type().is_file() {
+            let (sub_dir, _) = split_path(&relative);
+            let file =
+                put_file(client, &mut tree, &format!("{base}{sub_dir}"), entry.path()).await?;
+            println!("Uploaded {}", tree.relative(&file.fullpath));
+        }
+    }
+    Ok(())
+}
+
+// Where a remote file or folder lands, inside of the output when it is a directory
+fn target_path(output: Option<&Path>, name: &str) -> PathBuf {
+    match output {
+        Some(output) if output.is_dir() => output.join(name),
+        Some(output) => output.to_path_buf(),
+        None => PathBuf::from(name),
+    }
+}
+
+pub async fn download(
+    client: &Client,
+    path: &str,
+    version: Option<i64>,
+    output: Option<&Path>,
+) -> Result<()> {
+    let tree = client.get_tree().await?;
+
+    if let Some(file) = tree.find_file(path) {
+        let target = target_path(output, &file.full_filename);
+        client.download(&file.id, version, &target).await?;
+        println!("Downloaded {}", target.display());
+        return Ok(());
+    }
+
+    let folder = tree
+        .find_folder(path)
+        .ok_or_else(|| format!("There is no file or folder at {path}"))?;
+    if version.is_some() {
+        return Err("Only the files have versions".into());
+    }
+
+    download_folder(
+        client,
+        &tree,
+        folder,
+        &target_path(output, &folder.folder_name),
+    )
+    .await
+}
+
+pub async fn download_folder(
+    client: &Client,
+    tree: &Tree,
+    folder: &RemoteFolder,
+    target: &Path,
+) -> Result<()> {
+    let dir = tree.relative(&folder.fullpath);
+
+    // The empty folders are kept as well
+    tokio::fs::create_dir_all(target).await?;
+    for sub_folder in tree.folders_under(dir) {
+        let relative = &sub_folder.fullpath[folder.fullpath.len()..];
+        tokio::fs::create_dir_all(target.join(relative)).await?;
+    }
+
+    for file in tree.files_under(dir) {
+        let local = target.join(&file.fullpath[folder.fullpath.len()..]);
+        client.download(&file.id, None, &local).await?;
+        println!("Downloaded {}", local.display());
+    }
+    Ok(())
+}
+
+// The destination is a folder to move into when it ends with a slash or is an existing folder,
+// otherwise the last part of it is the new name, which only the folders can take
+pub async fn move_path(client: &Client, source: &str, destination: &str) -> Result<()> {
+    let tree = client.get_tree().await?;
+
+    let (dir, name) = match destination.ends_with('/') || tree.find_folder(destination).is_some() {
+        true => (dir_path(destination), None),
+        false => {
+            let (dir, name) = split_path(destination);
+            (dir, Some(name))
+        }
+    };
+    if !tree.has_dir(&dir) {
+        return Err(format!("There is no folder at {dir}").into());
+    }
+
+    if let Some(file) = tree.find_file(source) {
+        if name.as_ref().is_some_and(|n| *n != file.full_filename) {
+            return Err("The files cannot be renamed, only moved into another folder".into());
+        }
+        let file = client.update_file(file, Some(&dir), None).await?;
+        println!("Moved to {}", tree.relative(&file.fullpath));
+        return Ok(());
+    }
+
+    let folder = tree
+        .find_folder(source)
+        .ok_or_else(|| format!("There is no file or folder at {source}"))?;
+    let name = name.unwrap_or_else(|| folder.folder_name.clone());
+    let folder = client.update_folder(&folder.id, &dir, &name).await?;
+    println!("Moved to {}", tree.relative(&folder.fullpath));
+    Ok(())
+}
+
+fn find_file<'a>(tree: &'a Tree, path: &str) -> Result<&'a RemoteFile> {
+    let file = tree
+        .find_file(path)
+        .ok_or_else(|| format!("There is no file at {path}"))?;
+    Ok(file)
+}
+
+pub async fn versions(client: &Client, path: &str) -> Result<()> {
+    let tree = client.get_tree().await?;
+    let file = client.get_file(&find_file(&tree, path)?.id).await?;
+    if file.versions.is_empty() {
+        println!("{} does not have older versions", path);
+    }
+    for version in file.versions {
+        println!("{version}");
+    }
+    Ok(())
+}
+
+pub async fn restore(client: &Client, path: &str, version: i64) -> Result<()> {
+    let tree = client.get_tree().await?;
+    let file = client
+        .restore_version(&find_file(&tree, path)?.id, version)
+        .await?;
+    println!(
+        "Restored version {version} of {}",
+        tree.relative(&file.fullpath)
+    );
+    Ok(())
+}
+
+pub async fn share(
+    client: &Client,
+    path: &str,
+    password: Option<&str>,
+    allow_upload: bool,
+    expires_in_days: Option<i64>,
+) -> Result<()> {
+    let tree = client.get_tree().await?;
+    if tree.find_file(path).is_some() {
+        return Err("Only the folders can be shared with a link, share the folder above it".into());
+    }
+    let folder = tree
+        .find_folder(path)
+        .ok_or_else(|| format!("There is no folder at {path}"))?;
+
+    let expires_at =
+        expires_in_days.map(|days| Utc::now().timestamp_millis() + days * 24 * 60 * 60 * 1000);
+    let link = client
+        .create_link(&folder.id, password, allow_upload, expires_at)
+        .await?;
+
+    println!("{}/link/{}", client.server(), link.token);
+    if let Some(expires_at) = link.expires_at {
+        println!("Expires at {}", format_time(expires_at));
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+// A command line client for the file manager, it only talks to the REST routes of a running server
+// The remote paths are relative to the root of the logged in user, like docs/report.pdf
+
+use std::{
+    io::{stdin, stdout, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+use client::{Client, Login};
+use session::Session;
+use sync::Prefer;
+
+mod client;
+mod commands;
+mod remote;
+mod session;
+mod sync;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Parser)]
+#[command(
+    name = "fmb-cli",
+    about = "Upload, download, sync and share the files of a file manager server"
+)]
+struct Cli {
+    /// The address of the server, like https://files.example.com
+    #[arg(long, global = true, env = "FMB_SERVER")]
+    server: Option<String>,
+
+    /// The refresh token of a session, used instead of the saved login
+    #[arg(long, global = true, env = "FMB_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log in and save the session for the next commands
+    Login {
+        #[arg(long)]
+        username: Option<String>,
+    },
+    /// End the saved session
+    Logout,
+    /// List a folder
+    Ls {
+        #[arg(default_value = "")]
+        dir: String,
+    },
+    /// Upload a file, or a directory with everything inside of it
+    Upload {
+        local: PathBuf,
+        /// The folder to upload into
+        #[arg(default_value = "")]
+        dir: String,
+    },
+    /// Download a file, a version of a file, or a folder with everything inside of it
+    Download {
+        path: String,
+        #[arg(long)]
+        version: Option<i64>,
+        /// Where to write it, inside of it when it is a directory
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Move a file or a folder into another folder, a folder can be renamed as well
+    Mv { source: String, destination: String },
+    /// List the older versions of a file
+    Versions { path: String },
+    /// Bring back an older version of a file
+    Restore { path: String, version: i64 },
+    /// Create a share link for a folder
+    Share {
+        path: String,
+        #[arg(long)]
+        password: Option<String>,
+        #[arg(long)]
+        allow_upload: bool,
+        #[arg(long)]
+        expires_in_days: Option<i64>,
+    },
+    /// Mirror a local directory with a folder, both ways
+    Sync {
+        local: PathBuf,
+        #[arg(default_value = "")]
+        dir: String,
+        /// Which side wins the conflicts, they are left alone otherwise
+        #[arg(long, value_enum)]
+        prefer: Option<Prefer>,
+        /// Only print what would be done
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+fn prompt(label: &str) -> Result<String> {
+    print!("{label}: ");
+    stdout().flush()?;
+    let mut line = String::new();
+    stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+async fn login(server: Option<String>, username: Option<String>) -> Result<()> {
+    let server = match server {
+        Some(server) => server,
+        None => prompt("Server")?,
+    };
+    let username = match username {
+        Some(username) => username,
+        None => prompt("Username")?,
+    };
+    let password = rpassword::prompt_password("Password: ")?;
+
+    let client = Client::new(&server)?;
+    let refresh_token = match client.login(&username, &password).await? {
+        Login::Session { refresh_token } => refresh_token,
+        Login::TwoFactor { challenge_token } => {
+            let code = prompt("Two-factor code")?;
+            match client.login_two_factor(&challenge_token, &code).await? {
+                Login::Session { refresh_token } => refresh_token,
+                Login::TwoFactor { .. } => return Err("The login did not finish".into()),
+            }
+        }
+    };
+
+    Session {
+        server: client.server().to_string(),
+        username: username.clone(),
+        refresh_token,
+    }
+    .save()?;
+    println!("Logged in as {username}");
+    Ok(())
+}
+
+// A token on the command line wins over the saved session, the server has to be known either way
+async fn connect(server: Option<String>, token: Option<String>) -> Result<Client> {
+    let session = Session::load()?;
+    let server = server
+        .or_else(|| session.as_ref().map(|s| s.server.clone()))
+        .ok_or("There is no server, pass --server or log in first")?;
+    let token = token
+        .or_else(|| session.map(|s| s.refresh_token))
+        .ok_or("There is no session, log in or pass --token first")?;
+    Client::connect(&server, &token).await
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let command = match cli.command {
+        Command::Login { username } => return login(cli.server, username).await,
+        Command::Logout => {
+            let client = connect(cli.server, cli.token).await?;
+            client.logout().await?;
+            return Session::remove();
+        }
+        command => command,
+    };
+
+    let client = connect(cli.server, cli.token).await?;
+    match command {
+        Command::Ls { dir } => commands::list(&client, &dir).await,
+        Command::Upload { local, dir } => commands::upload(&client, &local, &dir).await,
+        Command::Download {
+            path,
+            version,
+            output,
+        } => commands::download(&client, &path, version, output.as_deref()).await,
+        Command::Mv {
+            source,
+            destination,
+        } => commands::move_path(&client, &source, &destination).await,
+        Command::Versions { path } => commands::versions(&client, &path).await,
+        Command::Restore { path, version } => commands::restore(&client, &path, version).await,
+        Command::Share {
+            path,
+            password,
+            allow_upload,
+            expires_in_days,
+        } => {
+            commands::share(
+                &client,
+                &path,
+                password.as_deref(),
+                allow_upload,
+                expires_in_days,
+            )
+            .await
+        }
+        Command::Sync {
+            local,
+            dir,
+            prefer,
+            dry_run,
+        } => sync::sync(&client, &local, &dir, prefer, dry_run).await,
+        Command::Login { .. } | Command::Logout => Ok(()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Deserialize;
+
+// The parts of the responses of the server that the client uses
+// The paths coming from the server start with the root of the user, like alice/docs/
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFile {
+    pub id: String,
+    pub team: Option<String>,
+    pub full_filename: String,
+    pub visibility: String,
+    pub position: String,
+    pub fullpath: String,
+    pub updated_at: i64,
+    // Only the single file responses have them, newest first
+    #[serde(default)]
+    pub versions: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFolder {
+    pub id: String,
+    pub team: Option<String>,
+    pub folder_name: String,
+    pub position: String,
+    pub fullpath: String,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    pub token: String,
+    pub expires_at: Option<i64>,
+}
+
+// The logged in user along with everything in the personal tree
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tree {
+    pub username: String,
+    pub files: Vec<RemoteFile>,
+    pub folders: Vec<RemoteFolder>,
+}
+
+// docs, /docs/ and docs/ all give docs/, the root is an empty string
+pub fn dir_path(path: &str) -> String {
+    match path.trim_matches('/') {
+        "" => String::new(),
+        path => format!("{path}/"),
+    }
+}
+
+// The folder a path is in, and the name of the last part of it
+// docs/report.pdf gives docs/ and report.pdf
+pub fn split_path(path: &str) -> (String, String) {
+    let path = path.trim_matches('/');
+    match path.rsplit_once('/') {
+        Some((dir, name)) => (format!("{dir}/"), name.to_string()),
+        None => (String::new(), path.to_string()),
+    }
+}
+
+impl Tree {
+    // The team files and the root folder itself are left out,
+    // the client only deals with the personal tree
+    pub fn personal(mut self) -> Self {
+        let root = self.root();
+        self.files.retain(|f| f.team.is_none());
+        self.folders
+            .retain(|f| f.team.is_none() && f.fullpath != root);
+        self
+    }
+
+    pub fn root(&self) -> String {
+        format!("{}/", self.username)
+    }
+
+    // From a path that the user typed, like docs/report.pdf, to the fullpath on the server
+    pub fn absolute(&self, path: &str) -> String {
+        format!("{}{}", self.root(), path.trim_start_matches('/'))
+    }
+
+    // The other way around, what is shown to the user and sent back as a position
+    pub fn relative<'a>(&self, fullpath: &'a str) -> &'a str {
+        fullpath.strip_prefix(&self.root()).unwrap_or(fullpath)
+    }
+
+    pub fn find_file(&self, path: &str) -> Option<&RemoteFile> {
+        let fullpath = self.absolute(path.trim_end_matches('/'));
+        self.files.iter().find(|f| f.fullpath == fullpath)
+    }
+
+    pub fn find_folder(&self, path: &str) -> Option<&RemoteFolder> {
+        let fullpath = self.absolute(&dir_path(path));
+        self.folders.iter().find(|f| f.fullpath == fullpath)
+    }
+
+    // The root always exists, even though it is not in the list
+    pub fn has_dir(&self, dir: &str) -> bool {
+        dir.is_empty() || self.find_folder(dir).is_some()
+    }
+
+    // Every file below the dir, however deep
+    pub fn files_under(&self, dir: &str) -> Vec<&RemoteFile> {
+        let prefix = self.absolute(&dir_path(dir));
+        self.files
+            .iter()
+            .filter(|f| f.fullpath.starts_with(&prefix))
+            .collect()
+    }
+
+    // Every folder below the dir, parents before their children
+    pub fn folders_under(&self, dir: &str) -> Vec<&RemoteFolder> {
+        let prefix = self.absolute(&dir_path(dir));
+        let mut folders = self
+            .folders
+            .iter()
+            .filter(|f| f.fullpath.starts_with(&prefix) && f.fullpath != prefix)
+            .collect::<Vec<_>>();
+        folders.sort_by(|a, b| a.fullpath.cmp(&b.fullpath));
+        folders
+    }
+}
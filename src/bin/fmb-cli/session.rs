@@ -0,0 +1,68 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+// What is kept between two runs, after a login
+// The refresh token is all that is needed to get a new access token for each run
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub server: String,
+    pub username: String,
+    pub refresh_token: String,
+}
+
+impl Session {
+    // ~/.config/fmb/session.json on linux
+    fn path() -> Result<PathBuf> {
+        let dir = dirs::config_dir().ok_or("Cannot find the config directory of the user")?;
+        Ok(dir.join("fmb").join("session.json"))
+    }
+
+    pub fn load() -> Result<Option<Self>> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let session = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(Some(session))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // The token is as good as the password, only the user gets to read it,
+        // from the moment the file exists and before anything is written to it
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+
+        // The mode only counts for a new file, a file from before is narrowed down as well
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn remove() -> Result<()> {
+        let path = Self::path()?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{copy, BufReader},
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use data_encoding::HEXLOWER;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+use walkdir::WalkDir;
+
+use crate::{
+    client::{Client, PARTIAL_EXTENSION},
+    commands::{ensure_dir, put_file},
+    remote::{dir_path, split_path, RemoteFile},
+    Result,
+};
+
+// Kept at the top of the local directory, it is never synced itself
+const STATE_FILE: &str = ".fmb-sync.json";
+
+// Which side wins when a file changed on both of them since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Prefer {
+    Local,
+    Remote,
+}
+
+// How a file looked on both sides right after it was last synced
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedFile {
+    id: String,
+    updated_at: i64,
+    hash: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncState {
+    // The remote folder that the directory mirrors
+    remote: String,
+    // By the path relative to the directory, like notes/todo.txt
+    files: BTreeMap<String, SyncedFile>,
+}
+
+impl SyncState {
+    fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(STATE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        fs::write(dir.join(STATE_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+enum Action<'a> {
+    // Nothing changed since the last sync
+    Skip,
+    Upload,
+    Download(&'a RemoteFile),
+    DeleteRemote(&'a RemoteFile),
+    DeleteLocal,
+    // The same content showed up on both sides
+    Record(&'a RemoteFile, String),
+    Forget,
+    Conflict(&'static str),
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    copy(&mut BufReader::new(fs::File::open(path)?), &mut hasher)?;
+    Ok(HEXLOWER.encode(&hasher.finalize()))
+}
+
+// Every file in the directory by its relative path, in the same form as the server stores them
+fn local_files(dir: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(dir).min_depth(1) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir)?;
+        let relative = relative
+            .to_str()
+            .ok_or_else(|| format!("{} does not have a valid name", relative.display()))?
+            .replace('\\', "/")
+            .nfc()
+            .collect::<String>();
+        if relative == STATE_FILE || relative.ends_with(PARTIAL_EXTENSION) {
+            continue;
+        }
+        files.insert(relative, entry.into_path());
+    }
+    Ok(files)
+}
+
+// The remote content has to be fetched to tell whether both sides made the same change
+async fn remote_hash(client: &Client, file: &RemoteFile) -> Result<String> {
+    let temp = std::env::temp_dir().join(format!("fmb-{}", file.id));
+    client.download(&file.id, None, &temp).await?;
+    let hash = hash_file(&temp);
+    fs::remove_file(&temp)?;
+    hash
+}
+
+async fn decide<'a>(
+    client: &Client,
+    local_hash: Option<&String>,
+    remote: Option<&'a RemoteFile>,
+    known: Option<&SyncedFile>,
+    prefer: Option<Prefer>,
+) -> Result<Action<'a>> {
+    // A side changed when it does not look like it did after the last sync,
+    // by the hash of the content locally and by the updatedAt on the server
+    let local_changed = local_hash != known.map(|k| &k.hash);
+    let remote_changed = remote.map(|r| r.updated_at) != known.map(|k| k.updated_at);
+
+    let from_local = || match remote {
+        Some(remote) if local_hash.is_none() => Action::DeleteRemote(remote),
+        _ => Action::Upload,
+    };
+    let from_remote = || match remote {
+        Some(remote) => Action::Download(remote),
+        None => Action::DeleteLocal,
+    };
+
+    let action = match (local_changed, remote_changed) {
+        (false, false) => Action::Skip,
+        (true, false) => from_local(),
+        (false, true) => from_remote(),
+        (true, true) => match (local_hash, remote) {
+            (None, None) => Action::Forget,
+            (Some(local_hash), Some(remote))
+                if *local_hash == remote_hash(client, remote).await? =>
+            {
+                Action::Record(remote, local_hash.clone())
+            }
+            _ => match prefer {
+                Some(Prefer::Local) => from_local(),
+                Some(Prefer::Remote) => from_remote(),
+                None => match (local_hash, remote) {
+                    (Some(_), Some(_)) => Action::Conflict("changed on both sides"),
+                    (None, _) => Action::Conflict("deleted locally but changed remotely"),
+                    (_, None) => Action::Conflict("changed locally but deleted remotely"),
+                },
+            },
+        },
+    };
+    Ok(action)
+}
+
+// Mirrors the local directory and the remote folder both ways
+// What changed on one side only is carried over to the other one, deletions included
+// What changed on both sides is a conflict, which is left alone unless a side is preferred
+pub async fn sync(
+    client: &Client,
+    local: &Path,
+    remote: &str,
+    prefer: Option<Prefer>,
+    dry_run: bool,
+) -> Result<()> {
+    let remote = dir_path(remote);
+    if !local.is_dir() {
+        return Err(format!("{} is not a directory", local.display()).into());
+    }
+
+    let mut state = match SyncState::load(local)? {
+        Some(state) if state.remote != remote => {
+            return Err(format!(
+                "{} is already synced with {}",
+                local.display(),
+                state.remote
+            )
+            .into())
+        }
+        Some(state) => state,
+        None => SyncState {
+            remote: remote.clone(),
+            ..Default::default()
+        },
+    };
+
+    let mut tree = client.get_tree().await?;
+    if !dry_run {
+        ensure_dir(client, &mut tree, &remote).await?;
+    }
+
+    let local_files = local_files(local)?;
+    let prefix = tree.absolute(&remote);
+    let remote_files = tree
+        .files_under(&remote)
+        .into_iter()
+        .map(|f| (f.fullpath[prefix.len()..].to_string(), f.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    let paths = local_files
+        .keys()
+        .chain(remote_files.keys())
+        .chain(state.files.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let mut conflicts = 0;
+    for path in paths {
+        let local_path = local_files
+            .get(&path)
+            .cloned()
+            .unwrap_or_else(|| local.join(&path));
+        let local_hash = match local_files.get(&path) {
+            Some(local_path) => Some(hash_file(local_path)?),
+            None => None,
+        };
+        let remote_file = remote_files.get(&path);
+        let known = state.files.get(&path);
+
+        let action = decide(client, local_hash.as_ref(), remote_file, known, prefer).await?;
+        let synced = match action {
+            Action::Skip => continue,
+            Action::Conflict(reason) => {
+                conflicts += 1;
+                println!("Conflict  {path}: {reason}");
+                continue;
+            }
+            Action::Forget => None,
+            Action::Record(remote_file, hash) => Some(SyncedFile {
+                id: remote_file.id.clone(),
+                updated_at: remote_file.updated_at,
+                hash,
+            }),
+            Action::Upload => {
+                println!("Upload    {path}");
+                if dry_run {
+                    continue;
+                }
+                let (dir, _) = split_path(&path);
+                let file =
+                    put_file(client, &mut tree, &format!("{remote}{dir}"), &local_path).await?;
+                Some(SyncedFile {
+                    id: file.id,
+                    updated_at: file.updated_at,
+                    hash: local_hash.ok_or("The uploaded file has no hash")?,
+                })
+            }
+            Action::Download(remote_file) => {
+                println!("Download  {path}");
+                if dry_run {
+                    continue;
+                }
+                if let Some(dir) = local_path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                client.download(&remote_file.id, None, &local_path).await?;
+                Some(SyncedFile {
+                    id: remote_file.id.clone(),
+                    updated_at: remote_file.updated_at,
+                    hash: hash_file(&local_path)?,
+                })
+            }
+            Action::DeleteRemote(remote_file) => {
+                println!("Delete    {path} (remote)");
+                if dry_run {
+                    continue;
+                }
+                client.delete_file(&remote_file.id).await?;
+                None
+            }
+            Action::DeleteLocal => {
+                println!("Delete    {path} (local)");
+                if dry_run {
+                    continue;
+                }
+                fs::remove_file(&local_path)?;
+                None
+            }
+        };
+
+        if dry_run {
+            continue;
+        }
+        match synced {
+            Some(synced) => state.files.insert(path, synced),
+            None => state.files.remove(&path),
+        };
+
+        // Saved after every file, so that an interrupted sync does not redo what it already did
+        state.save(local)?;
+    }
+
+    if !dry_run {
+        state.save(local)?;
+    }
+
+    match conflicts {
+        0 => Ok(()),
+        _ => Err(format!(
+            "{conflicts} conflicts were left alone, run again with --prefer local or --prefer remote to settle them"
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,632 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::{
+    helper::{into_string, jwt::JwtType},
+    Result,
+};
+
+const CONFIG_FILE: &str = "CONFIG_FILE";
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+// Every setting of the server, read once at the start
+// The values come from the TOML file (config.toml, or the path in CONFIG_FILE),
+// and the environment variables override them, so the .env setups keep working
+// See config.example.toml for all the keys
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub storage: StorageConfig,
+    pub jwt: JwtConfig,
+    pub accounts: AccountsConfig,
+    pub uploads: UploadsConfig,
+    pub mail: MailConfig,
+    pub webhooks: WebhooksConfig,
+    // The identity providers for single sign-on, by name
+    pub oidc: HashMap<String, OidcProviderConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    // In bytes, for the whole request
+    pub max_upload_size: u64,
+    // Where the frontend is, the links in the mails point there
+    pub app_url: String,
+    // Behind a reverse proxy, the header that it puts the address of the client in,
+    // like X-Forwarded-For or Forwarded, otherwise every client looks like the proxy
+    pub trusted_proxy_header: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8000,
+            max_upload_size: 1024 * 1024 * 100,
+            app_url: "http://localhost:3000".to_string(),
+            trusted_proxy_header: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn address(&self) -> Result<SocketAddr> {
+        let ip: IpAddr = self.host.parse().map_err(into_string)?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub uri: String,
+    pub name: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            uri: String::default(),
+            name: "final-db".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub bucket: String,
+    pub region: String,
+    pub key_id: String,
+    pub key_secret: String,
+    // For the S3-compatible servers like MinIO, like http://localhost:9000
+    pub endpoint: Option<String>,
+    pub force_path_style: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JwtConfig {
+    pub access: TokenConfig,
+    pub refresh: TokenConfig,
+    pub challenge: TokenConfig,
+}
+
+impl JwtConfig {
+    pub fn get(&self, token_type: JwtType) -> &TokenConfig {
+        match token_type {
+            JwtType::Access => &self.access,
+            JwtType::Refresh => &self.refresh,
+            JwtType::Challenge => &self.challenge,
+        }
+    }
+
+    fn get_mut(&mut self, token_type: JwtType) -> &mut TokenConfig {
+        match token_type {
+            JwtType::Access => &mut self.access,
+            JwtType::Refresh => &mut self.refresh,
+            JwtType::Challenge => &mut self.challenge,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenConfig {
+    // In seconds, every kind of token has its own default
+    pub lifetime: Option<i64>,
+    // kid:algorithm:source, the first one signs the new tokens
+    pub keys: Vec<String>,
+    // The single HS512 secret from before the keys
+    pub secret: Option<String>,
+}
+
+impl TokenConfig {
+    // The secrets and the key files, without the kid and the algorithm
+    fn key_sources(&self) -> impl Iterator<Item = &str> {
+        self.keys
+            .iter()
+            .filter_map(|spec| spec.trim().splitn(3, ':').nth(2))
+            .chain(self.secret.as_deref())
+    }
+
+    pub fn lifetime(&self, token_type: JwtType) -> i64 {
+        self.lifetime.unwrap_or(match token_type {
+            JwtType::Access => 30 * 60,
+            JwtType::Refresh => 2 * 60 * 60,
+            JwtType::Challenge => 5 * 60,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountsConfig {
+    // Deleted accounts are kept for this long, so that the users can change their mind
+    pub deletion_grace_days: i64,
+}
+
+impl Default for AccountsConfig {
+    fn default() -> Self {
+        Self {
+            deletion_grace_days: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UploadsConfig {
+    // Without an allow list, everything that is not denied is allowed
+    pub allowed_extensions: Option<Vec<String>>,
+    pub denied_extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhooksConfig {
+    // The webhooks cannot point inside the network of the server unless this is on,
+    // which is only meant for the development and the tests
+    pub allow_private_hosts: bool,
+    // How many times a delivery is tried before it is given up on
+    pub max_attempts: usize,
+    // In seconds, for every try
+    pub timeout: u64,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            allow_private_hosts: false,
+            max_attempts: 8,
+            timeout: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MailerKind {
+    // Writes the mails to files, for development
+    #[default]
+    Outbox,
+    Smtp,
+}
+
+impl FromStr for MailerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "outbox" => Ok(MailerKind::Outbox),
+            "smtp" => Ok(MailerKind::Smtp),
+            _ => Err(format!("{s} is not outbox or smtp")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub mailer: MailerKind,
+    pub from: Option<String>,
+    pub outbox_dir: String,
+    pub smtp_host: Option<String>,
+    // The submission port when it is not set
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            mailer: MailerKind::default(),
+            from: None,
+            outbox_dir: "outbox".to_string(),
+            smtp_host: None,
+            smtp_port: None,
+            smtp_username: None,
+            smtp_password: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OidcProviderConfig {
+    pub issuer: String,
+    pub client_id: String,
+    // Public clients rely on PKCE alone and have no secret
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: Option<String>,
+}
+
+fn parse_env<T: FromStr>(key: &str, value: &str) -> Result<T>
+where
+    T::Err: Display,
+{
+    Ok(value
+        .trim()
+        .parse()
+        .map_err(|e| format!("The environment variable {key} is not valid: {e}"))?)
+}
+
+// Sets the field when the variable is there
+fn env_value<T: FromStr>(key: &str, field: &mut T) -> Result<()>
+where
+    T::Err: Display,
+{
+    if let Ok(value) = std::env::var(key) {
+        *field = parse_env(key, &value)?;
+    }
+    Ok(())
+}
+
+fn env_option<T: FromStr>(key: &str, field: &mut Option<T>) -> Result<()>
+where
+    T::Err: Display,
+{
+    if let Ok(value) = std::env::var(key) {
+        *field = Some(parse_env(key, &value)?);
+    }
+    Ok(())
+}
+
+// Comma separated, like DENIED_EXTENSIONS=exe,bat
+fn env_list(key: &str) -> Option<Vec<String>> {
+    std::env::var(key).ok().map(|list| {
+        list.split(',')
+            .map(|item| item.trim().to_string())
+            .collect()
+    })
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        // The file is optional unless it was asked for, everything can come from the environment
+        let (path, required) = match std::env::var(CONFIG_FILE) {
+            Ok(path) => (path, true),
+            Err(_) => (DEFAULT_CONFIG_FILE.to_string(), false),
+        };
+
+        let mut config = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| format!("Cannot read the config file {path}: {e}"))?,
+            Err(e) if required || e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Cannot open the config file {path}: {e}").into())
+            }
+            Err(_) => Config::default(),
+        };
+
+        config.apply_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // The names are the ones that were read before the config file existed
+    fn apply_env(&mut self) -> Result<()> {
+        env_value("HOST", &mut self.server.host)?;
+        env_value("PORT", &mut self.server.port)?;
+        env_value("MAX_UPLOAD_SIZE", &mut self.server.max_upload_size)?;
+        env_value("APP_URL", &mut self.server.app_url)?;
+        env_option(
+            "TRUSTED_PROXY_HEADER",
+            &mut self.server.trusted_proxy_header,
+        )?;
+
+        env_value("MONGODB_URI", &mut self.database.uri)?;
+        env_value("DATABASE_NAME", &mut self.database.name)?;
+
+        env_value("BUCKET_NAME", &mut self.storage.bucket)?;
+        env_value("REGION", &mut self.storage.region)?;
+        env_value("S3_KEY_ID", &mut self.storage.key_id)?;
+        env_value("S3_KEY_SECRET", &mut self.storage.key_secret)?;
+        env_option("S3_ENDPOINT", &mut self.storage.endpoint)?;
+        env_value("S3_FORCE_PATH_STYLE", &mut self.storage.force_path_style)?;
+
+        for token_type in [JwtType::Access, JwtType::Refresh, JwtType::Challenge] {
+            let name = token_type.env_name();
+            let token = self.jwt.get_mut(token_type);
+            env_option(name, &mut token.secret)?;
+            env_option(&format!("{name}_LIFETIME"), &mut token.lifetime)?;
+            if let Some(keys) = env_list(&format!("{name}_KEYS")) {
+                token.keys = keys;
+            }
+        }
+
+        env_value(
+            "DELETION_GRACE_DAYS",
+            &mut self.accounts.deletion_grace_days,
+        )?;
+
+        if let Some(allowed) = env_list("ALLOWED_EXTENSIONS") {
+            // An empty variable turns the allow list off
+            self.uploads.allowed_extensions =
+                Some(allowed).filter(|list| list.iter().any(|e| !e.is_empty()));
+        }
+        if let Some(denied) = env_list("DENIED_EXTENSIONS") {
+            self.uploads.denied_extensions = denied;
+        }
+
+        env_value("MAILER", &mut self.mail.mailer)?;
+        env_option("MAIL_FROM", &mut self.mail.from)?;
+        env_value("OUTBOX_DIR", &mut self.mail.outbox_dir)?;
+        env_option("SMTP_HOST", &mut self.mail.smtp_host)?;
+        env_option("SMTP_PORT", &mut self.mail.smtp_port)?;
+        env_option("SMTP_USERNAME", &mut self.mail.smtp_username)?;
+        env_option("SMTP_PASSWORD", &mut self.mail.smtp_password)?;
+
+        env_value(
+            "WEBHOOK_ALLOW_PRIVATE_HOSTS",
+            &mut self.webhooks.allow_private_hosts,
+        )?;
+        env_value("WEBHOOK_MAX_ATTEMPTS", &mut self.webhooks.max_attempts)?;
+        env_value("WEBHOOK_TIMEOUT", &mut self.webhooks.timeout)?;
+
+        // OIDC_PROVIDERS=company,google, then OIDC_COMPANY_ISSUER and so on for each of them
+        for name in env_list("OIDC_PROVIDERS").unwrap_or_default() {
+            if name.is_empty() {
+                continue;
+            }
+            let prefix = format!("OIDC_{}", name.to_uppercase());
+            let provider = self.oidc.entry(name).or_default();
+            env_value(&format!("{prefix}_ISSUER"), &mut provider.issuer)?;
+            env_value(&format!("{prefix}_CLIENT_ID"), &mut provider.client_id)?;
+            env_option(
+                &format!("{prefix}_CLIENT_SECRET"),
+                &mut provider.client_secret,
+            )?;
+            env_value(
+                &format!("{prefix}_REDIRECT_URI"),
+                &mut provider.redirect_uri,
+            )?;
+            env_option(&format!("{prefix}_SCOPES"), &mut provider.scopes)?;
+        }
+
+        Ok(())
+    }
+
+    // Every problem is reported at once, instead of one per restart
+    fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let mut require = |value: &str, name: &str| {
+            if value.trim().is_empty() {
+                problems.push(format!("{name} is missing"));
+            }
+        };
+
+        require(&self.database.uri, "database.uri (MONGODB_URI)");
+        require(&self.database.name, "database.name");
+        require(&self.storage.bucket, "storage.bucket (BUCKET_NAME)");
+        require(&self.storage.region, "storage.region (REGION)");
+        require(&self.storage.key_id, "storage.key_id (S3_KEY_ID)");
+        require(
+            &self.storage.key_secret,
+            "storage.key_secret (S3_KEY_SECRET)",
+        );
+
+        for (name, provider) in &self.oidc {
+            require(&provider.issuer, &format!("oidc.{name}.issuer"));
+            require(&provider.client_id, &format!("oidc.{name}.client_id"));
+            require(&provider.redirect_uri, &format!("oidc.{name}.redirect_uri"));
+        }
+
+        if self.mail.mailer == MailerKind::Smtp {
+            for (value, name) in [
+                (&self.mail.smtp_host, "mail.smtp_host (SMTP_HOST)"),
+                (
+                    &self.mail.smtp_username,
+                    "mail.smtp_username (SMTP_USERNAME)",
+                ),
+                (
+                    &self.mail.smtp_password,
+                    "mail.smtp_password (SMTP_PASSWORD)",
+                ),
+                (&self.mail.from, "mail.from (MAIL_FROM)"),
+            ] {
+                require(value.as_deref().unwrap_or_default(), name);
+            }
+        }
+
+        if self.server.host.parse::<IpAddr>().is_err() {
+            problems.push(format!(
+                "server.host {} is not an ip address",
+                self.server.host
+            ));
+        }
+        if self.server.port == 0 {
+            problems.push("server.port cannot be 0".to_string());
+        }
+        if self.webhooks.max_attempts == 0 {
+            problems.push("webhooks.max_attempts cannot be 0".to_string());
+        }
+        if self.webhooks.timeout == 0 {
+            problems.push("webhooks.timeout cannot be 0".to_string());
+        }
+        if self.server.max_upload_size == 0 {
+            problems.push("server.max_upload_size cannot be 0".to_string());
+        }
+        if Url::parse(&self.server.app_url).is_err() {
+            problems.push(format!(
+                "server.app_url {} is not a url",
+                self.server.app_url
+            ));
+        }
+        if let Some(endpoint) = &self.storage.endpoint {
+            if Url::parse(endpoint).is_err() {
+                problems.push(format!("storage.endpoint {endpoint} is not a url"));
+            }
+        }
+        if self.accounts.deletion_grace_days < 0 {
+            problems.push("accounts.deletion_grace_days cannot be negative".to_string());
+        }
+
+        // A key shared between two kinds of tokens would let one pass as the other
+        let mut key_owners = HashMap::new();
+        for token_type in [JwtType::Access, JwtType::Refresh, JwtType::Challenge] {
+            let name = token_type.env_name();
+            let token = self.jwt.get(token_type);
+            for source in token.key_sources() {
+                match key_owners.insert(source, name) {
+                    Some(other) if other != name => problems.push(format!(
+                        "The {other} and {name} tokens share a key, every kind of token needs its own"
+                    )),
+                    _ => {}
+                }
+            }
+            if token.keys.is_empty() && token.secret.is_none() {
+                problems.push(format!(
+                    "The {name} token has no keys ({name}_KEYS) or secret ({name})"
+                ));
+            }
+            if token.lifetime(token_type) <= 0 {
+                problems.push(format!("The lifetime of the {name} token must be positive"));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("The config is not valid:\n{}", problems.join("\n")).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [server]
+        port = 8080
+
+        [database]
+        uri = "mongodb://localhost:27017"
+        name = "file-manager"
+
+        [storage]
+        bucket = "files"
+        region = "eu-west-1"
+        key_id = "id"
+        key_secret = "secret"
+
+        [jwt.access]
+        keys = ["a1:HS512:access-secret"]
+        [jwt.refresh]
+        secret = "refresh-secret"
+        [jwt.challenge]
+        secret = "challenge-secret"
+
+        [accounts]
+        deletion_grace_days = 14
+
+        [webhooks]
+        max_attempts = 5
+        timeout = 20
+    "#;
+
+    fn config() -> Config {
+        toml::from_str(CONFIG).unwrap()
+    }
+
+    fn problems(config: &Config) -> String {
+        config.validate().unwrap_err().to_string()
+    }
+
+    // The only test that touches the environment, the variables are not read anywhere else in the tests
+    #[test]
+    fn overrides_the_file_with_the_environment() {
+        let mut config = config();
+        std::env::remove_var("WEBHOOK_TIMEOUT");
+        std::env::set_var("WEBHOOK_MAX_ATTEMPTS", "3");
+        std::env::set_var("DELETION_GRACE_DAYS", "0");
+        std::env::set_var("JWT_ACCESS_KEYS", "a2:HS256:new, a1:HS512:access-secret");
+        std::env::set_var("DENIED_EXTENSIONS", "exe,bat");
+
+        let applied = config.apply_env();
+        for key in [
+            "WEBHOOK_MAX_ATTEMPTS",
+            "DELETION_GRACE_DAYS",
+            "JWT_ACCESS_KEYS",
+            "DENIED_EXTENSIONS",
+        ] {
+            std::env::remove_var(key);
+        }
+        applied.unwrap();
+
+        assert_eq!(config.webhooks.max_attempts, 3);
+        assert_eq!(config.accounts.deletion_grace_days, 0);
+        assert_eq!(
+            config.jwt.access.keys,
+            ["a2:HS256:new", "a1:HS512:access-secret"]
+        );
+        assert_eq!(config.uploads.denied_extensions, ["exe", "bat"]);
+        // What the environment does not set stays as in the file
+        assert_eq!(config.webhooks.timeout, 20);
+
+        std::env::set_var("WEBHOOK_TIMEOUT", "soon");
+        let applied = config.apply_env();
+        std::env::remove_var("WEBHOOK_TIMEOUT");
+        assert!(applied.unwrap_err().to_string().contains("WEBHOOK_TIMEOUT"));
+    }
+
+    #[test]
+    fn accepts_a_complete_config() {
+        config().validate().unwrap();
+    }
+
+    #[test]
+    fn reports_every_problem_at_once() {
+        let mut config = config();
+        config.database.uri = " ".to_string();
+        config.server.port = 0;
+        config.server.app_url = "not a url".to_string();
+        config.webhooks.max_attempts = 0;
+        config.accounts.deletion_grace_days = -1;
+        config.jwt.challenge.secret = None;
+
+        let problems = problems(&config);
+        for problem in [
+            "database.uri (MONGODB_URI) is missing",
+            "server.port cannot be 0",
+            "server.app_url not a url is not a url",
+            "webhooks.max_attempts cannot be 0",
+            "accounts.deletion_grace_days cannot be negative",
+            "The JWT_CHALLENGE token has no keys",
+        ] {
+            assert!(problems.contains(problem), "{problem} in {problems}");
+        }
+    }
+
+    #[test]
+    fn refuses_a_key_shared_between_two_kinds_of_tokens() {
+        let mut config = config();
+        config.jwt.challenge.secret = Some("access-secret".to_string());
+        assert!(problems(&config).contains("The JWT_ACCESS and JWT_CHALLENGE tokens share a key"));
+
+        let mut config = self::config();
+        config.jwt.refresh.keys = vec!["r1:HS512:challenge-secret".to_string()];
+        assert!(problems(&config).contains("The JWT_REFRESH and JWT_CHALLENGE tokens share a key"));
+    }
+
+    #[test]
+    fn asks_for_the_smtp_settings_with_the_smtp_mailer() {
+        let mut config = config();
+        config.mail.mailer = MailerKind::Smtp;
+        assert!(problems(&config).contains("mail.smtp_host (SMTP_HOST) is missing"));
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::acl::AclEntry;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct AclDB {
+    collection: Collection<AclEntry>,
+}
+
+impl AclDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("Acl"),
+        }
+    }
+
+    // A user can only have one role on a file or folder
+    pub async fn create_indexes(&self) -> Result<()> {
+        let share_index = IndexModel::builder()
+            .keys(doc! {"resource": 1, "grantee": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        let grantee_index = IndexModel::builder().keys(doc! {"grantee": 1}).build();
+
+        self.collection
+            .create_indexes([share_index, grantee_index], None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_entries_by(&self, doc: Document) -> Result<Vec<AclEntry>> {
+        let entries = self.collection.find(doc, None).await?.try_collect().await?;
+        Ok(entries)
+    }
+
+    pub async fn get_entries_by_resource(&self, resource: &ObjectId) -> Result<Vec<AclEntry>> {
+        self.get_entries_by(doc! {"resource": resource}).await
+    }
+
+    pub async fn get_entries_by_resources(&self, resources: &[ObjectId]) -> Result<Vec<AclEntry>> {
+        self.get_entries_by(doc! {"resource": {"$in": resources}})
+            .await
+    }
+
+    pub async fn get_entries_by_grantee(&self, grantee: &ObjectId) -> Result<Vec<AclEntry>> {
+        self.get_entries_by(doc! {"grantee": grantee}).await
+    }
+
+    pub async fn get_entries_by_owner(&self, owner: &ObjectId) -> Result<Vec<AclEntry>> {
+        self.get_entries_by(doc! {"owner": owner}).await
+    }
+
+    // Used for checking a file or folder together with all of the folders above it
+    pub async fn get_entries_by_grantee_resources(
+        &self,
+        grantee: &ObjectId,
+        resources: &[ObjectId],
+    ) -> Result<Vec<AclEntry>> {
+        self.get_entries_by(doc! {"grantee": grantee, "resource": {"$in": resources}})
+            .await
+    }
+
+    // Sharing again with the same user only changes the role
+    pub async fn upsert_entry(&self, entry: AclEntry) -> Result<AclEntry> {
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let filter = doc! {"resource": entry.resource, "grantee": entry.grantee};
+        let role = entry.role_to_str().to_string();
+        let created_at = entry.created_at;
+
+        let mut entry_doc: Document = entry.into();
+        entry_doc.remove("role");
+        entry_doc.remove("createdAt");
+        entry_doc.remove("updatedAt");
+        entry_doc.insert("createdAt", created_at);
+
+        let entry = self
+            .collection
+            .find_one_and_update(
+                filter,
+                doc! {
+                    "$set": {"role": role, "updatedAt": Utc::now().timestamp_millis()},
+                    "$setOnInsert": entry_doc
+                },
+                options,
+            )
+            .await?
+            .ok_or("Cannot share the resource")?;
+        Ok(entry)
+    }
+
+    pub async fn delete_entry_by_resource_grantee(
+        &self,
+        resource: &ObjectId,
+        grantee: &ObjectId,
+    ) -> Result<AclEntry> {
+        let entry = self
+            .collection
+            .find_one_and_delete(doc! {"resource": resource, "grantee": grantee}, None)
+            .await?
+            .ok_or("This resource is not shared with the provided user")?;
+        Ok(entry)
+    }
+
+    pub async fn delete_entries_by_resources(&self, resources: &[ObjectId]) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"resource": {"$in": resources}}, None)
+            .await?;
+        Ok(())
+    }
+
+    // Removes what the user shared, and what was shared with the user
+    pub async fn delete_entries_by_user(&self, user: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"$or": [{"owner": user}, {"grantee": user}]}, None)
+            .await?;
+        Ok(())
+    }
+}
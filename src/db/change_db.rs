@@ -0,0 +1,125 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::change::Change;
+use crate::helper::into_string;
+use crate::Result;
+
+use super::mongo::DB;
+
+// The counter document that hands out the seq of the changes
+const CHANGE_COUNTER: &str = "changes";
+
+#[derive(Debug, Clone)]
+pub struct ChangeDB {
+    collection: Collection<Change>,
+    counters: Collection<Document>,
+}
+
+impl ChangeDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("Change"),
+            counters: db.get_collection("Counter"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let seq_index = IndexModel::builder()
+            .keys(doc! {"seq": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        // The personal tree of a user is read by owner, the tree of a team by team
+        let owner_index = IndexModel::builder()
+            .keys(doc! {"owner": 1, "team": 1, "seq": 1})
+            .build();
+        let team_index = IndexModel::builder()
+            .keys(doc! {"team": 1, "seq": 1})
+            .build();
+
+        self.collection
+            .create_indexes([seq_index, owner_index, team_index], None)
+            .await?;
+        Ok(())
+    }
+
+    // Reserves the next count numbers at once, returns the last of them
+    async fn reserve_seqs(&self, count: i64) -> Result<i64> {
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let counter = self
+            .counters
+            .find_one_and_update(
+                doc! {"_id": CHANGE_COUNTER},
+                doc! {"$inc": {"seq": count}},
+                options,
+            )
+            .await?
+            .ok_or("Cannot reserve the seq of the changes")?;
+        let seq = counter.get_i64("seq").map_err(into_string)?;
+        Ok(seq)
+    }
+
+    // The changes are numbered in the order they are given
+    // The time is taken right before the numbers, which is what the readers rely on to skip
+    // the changes that might still have a smaller number being written
+    pub async fn append_changes(&self, mut changes: Vec<Change>) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp_millis();
+        for change in changes.iter_mut() {
+            change.created_at = now;
+        }
+        let last_seq = self.reserve_seqs(changes.len() as i64).await?;
+        let first_seq = last_seq - changes.len() as i64 + 1;
+        for (seq, change) in (first_seq..).zip(changes.iter_mut()) {
+            change.seq = seq;
+        }
+
+        self.collection.insert_many(changes, None).await?;
+        Ok(())
+    }
+
+    // 0 when nothing has been written yet
+    pub async fn get_latest_seq(&self) -> Result<i64> {
+        let counter = self
+            .counters
+            .find_one(doc! {"_id": CHANGE_COUNTER}, None)
+            .await?;
+        Ok(counter
+            .and_then(|c| c.get_i64("seq").ok())
+            .unwrap_or_default())
+    }
+
+    // The changes matching the filter that come after the seq and were written until the time, oldest first
+    pub async fn get_changes_after(
+        &self,
+        mut filter: Document,
+        seq: i64,
+        until: i64,
+        limit: i64,
+    ) -> Result<Vec<Change>> {
+        filter.insert("seq", doc! {"$gt": seq});
+        filter.insert("createdAt", doc! {"$lte": until});
+        let options = FindOptions::builder()
+            .sort(doc! {"seq": 1})
+            .limit(limit)
+            .build();
+        let changes = self
+            .collection
+            .find(filter, options)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(changes)
+    }
+}
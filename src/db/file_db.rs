@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::bson::oid::ObjectId;
@@ -5,7 +7,9 @@ use mongodb::bson::{doc, Regex};
 use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
 use mongodb::{bson::Document, Collection};
 
-use crate::base::file::File;
+use crate::base::file::{File, Visibility};
+use crate::error::Error;
+use crate::helper::escape::escape_regex;
 use crate::Result;
 
 use super::mongo::DB;
@@ -41,11 +45,9 @@ impl FileDB {
     }
 
     async fn get_file_by(&self, doc: Document) -> Result<File> {
-        let file = self
-            .collection
-            .find_one(doc, None)
-            .await?
-            .ok_or("Cannot find the file with the provided information")?;
+        let file = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the file with the provided information".into())
+        })?;
         Ok(file)
     }
 
@@ -73,7 +75,7 @@ impl FileDB {
 
     pub async fn get_files_by_prefix_position(&self, prefix: &str) -> Result<Vec<File>> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.get_files_by(doc! {
@@ -84,7 +86,7 @@ impl FileDB {
 
     pub async fn get_files_by_prefix_exact_position(&self, prefix: &str) -> Result<Vec<File>> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}$"),
+            pattern: format!("^{}$", escape_regex(prefix)),
             options: String::new(),
         };
         self.get_files_by(doc! {
@@ -95,7 +97,7 @@ impl FileDB {
 
     pub async fn get_files_by_prefix_fullpath(&self, prefix: &str) -> Result<Vec<File>> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.get_files_by(doc! {
@@ -106,7 +108,7 @@ impl FileDB {
 
     pub async fn get_public_files_by_prefix_position(&self, prefix: &str) -> Result<Vec<File>> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.get_files_by(doc! {
@@ -178,6 +180,90 @@ impl FileDB {
         Ok(file)
     }
 
+    async fn update_file_with(&self, id: &ObjectId, update: Document) -> Result<File> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let file = self
+            .collection
+            .find_one_and_update(doc! {"_id": id}, update, options)
+            .await?
+            .ok_or("Cannot update the file")?;
+        Ok(file)
+    }
+
+    pub async fn add_tags(&self, id: &ObjectId, tags: &[String]) -> Result<File> {
+        self.update_file_with(
+            id,
+            doc! {
+                "$addToSet": {"tags": {"$each": tags}},
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+        )
+        .await
+    }
+
+    pub async fn remove_tags(&self, id: &ObjectId, tags: &[String]) -> Result<File> {
+        self.update_file_with(
+            id,
+            doc! {
+                "$pull": {"tags": {"$in": tags}},
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+        )
+        .await
+    }
+
+    // Only the provided keys are changed, the other metadata entries are kept as they are
+    pub async fn update_metadata(
+        &self,
+        id: &ObjectId,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<File> {
+        let mut set = doc! {"updatedAt": Utc::now().timestamp_millis()};
+        for (key, value) in metadata {
+            set.insert(format!("metadata.{key}"), value);
+        }
+        self.update_file_with(id, doc! {"$set": set}).await
+    }
+
+    pub async fn delete_metadata(&self, id: &ObjectId, key: &str) -> Result<File> {
+        let mut unset = Document::new();
+        unset.insert(format!("metadata.{key}"), "");
+        self.update_file_with(
+            id,
+            doc! {
+                "$unset": unset,
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+        )
+        .await
+    }
+
+    // Counts how many files use each tag, among the files matching the filter
+    pub async fn count_tags_by(&self, filter: Document) -> Result<Vec<(String, i64)>> {
+        let pipeline = vec![
+            doc! {"$match": filter},
+            doc! {"$unwind": "$tags"},
+            doc! {"$group": {"_id": "$tags", "count": {"$sum": 1}}},
+        ];
+        let counts = self
+            .collection
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .filter_map(|d| {
+                let tag = d.get_str("_id").ok()?.to_string();
+                let count = d.get_i32("count").ok()?;
+                Some((tag, count as i64))
+            })
+            .collect();
+        Ok(counts)
+    }
+
     pub async fn move_inner_files(
         &self,
         old_position: &str,
@@ -188,7 +274,7 @@ impl FileDB {
         // use new position to replace old position
 
         let position_regex = &Regex {
-            pattern: format!("^{old_fullpath}"),
+            pattern: format!("^{}", escape_regex(old_fullpath)),
             options: String::new(),
         };
 
@@ -239,9 +325,49 @@ impl FileDB {
         Ok(())
     }
 
+    pub async fn get_files_by_team(&self, team: &ObjectId) -> Result<Vec<File>> {
+        self.get_files_by(doc! {"team": team}).await
+    }
+
+    pub async fn delete_files_by_team(&self, team: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"team": team}, None)
+            .await?;
+        Ok(())
+    }
+
+    // Only the personal tree is hidden, what the user made in a team stays with the team
+    pub async fn update_hidden_by_owner(&self, owner: &ObjectId, hidden: bool) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"owner": owner, "team": null},
+                doc! {"$set": {"hidden": hidden}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    // The files created by a user in a team stay in the team when the user leaves for good
+    pub async fn transfer_team_files(
+        &self,
+        team: &ObjectId,
+        from: &ObjectId,
+        to: &ObjectId,
+    ) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"team": team, "owner": from},
+                doc! {"$set": {"owner": to}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn delete_files_by_prefix_position(&self, prefix: &str) -> Result<()> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.collection
@@ -254,9 +380,31 @@ impl FileDB {
         Ok(())
     }
 
+    pub async fn update_visibilities_by_prefix_fullpath(
+        &self,
+        prefix: &str,
+        visibility: Visibility,
+    ) -> Result<()> {
+        let fullpath_regex = Regex {
+            pattern: format!("^{}", escape_regex(prefix)),
+            options: String::new(),
+        };
+        self.collection
+            .update_many(
+                doc! {"fullpath": {"$regex": fullpath_regex}},
+                doc! {"$set": {
+                    "visibility": visibility.as_str(),
+                    "updatedAt": Utc::now().timestamp_millis()
+                }},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn delete_files_by_prefix_fullpath(&self, prefix: &str) -> Result<()> {
         let fullpath_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.collection
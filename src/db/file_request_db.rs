@@ -0,0 +1,138 @@
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::file_request::FileRequest;
+use crate::error::Error;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct FileRequestDB {
+    collection: Collection<FileRequest>,
+}
+
+impl FileRequestDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("FileRequest"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let token_index = IndexModel::builder()
+            .keys(doc! {"token": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        let folder_index = IndexModel::builder().keys(doc! {"folder": 1}).build();
+
+        self.collection
+            .create_indexes([token_index, folder_index], None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_request_by(&self, doc: Document) -> Result<FileRequest> {
+        let request = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the file request with the provided information".into())
+        })?;
+        Ok(request)
+    }
+
+    pub async fn get_request_by_id(&self, id: &ObjectId) -> Result<FileRequest> {
+        self.get_request_by(doc! {"_id": id}).await
+    }
+
+    pub async fn get_request_by_token(&self, token: &str) -> Result<FileRequest> {
+        self.get_request_by(doc! {"token": token}).await
+    }
+
+    pub async fn get_requests_by_folder(&self, folder: &ObjectId) -> Result<Vec<FileRequest>> {
+        let requests = self
+            .collection
+            .find(doc! {"folder": folder}, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(requests)
+    }
+
+    pub async fn create_request(&self, request: FileRequest) -> Result<FileRequest> {
+        let new_request_id = self
+            .collection
+            .insert_one(request, None)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .ok_or("Cannot create a new file request")?;
+        self.get_request_by_id(&new_request_id).await
+    }
+
+    // Takes one upload out of the limit, in a single query
+    // so that concurrent uploads cannot go over the limit together
+    pub async fn reserve_upload(&self, id: &ObjectId) -> Result<FileRequest> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let request = self
+            .collection
+            .find_one_and_update(
+                doc! {
+                    "_id": id,
+                    "$or": [
+                        {"maxFiles": null},
+                        {"$expr": {"$lt": ["$uploadCount", "$maxFiles"]}}
+                    ]
+                },
+                doc! {"$inc": {"uploadCount": 1}},
+                options,
+            )
+            .await?
+            .ok_or_else(|| Error::QuotaExceeded("This link has reached its upload limit".into()))?;
+        Ok(request)
+    }
+
+    // Gives the upload back when it did not go through
+    pub async fn release_upload(&self, id: &ObjectId) -> Result<()> {
+        self.collection
+            .update_one(
+                doc! {"_id": id, "uploadCount": {"$gt": 0}},
+                doc! {"$inc": {"uploadCount": -1}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_request_by_id_folder(
+        &self,
+        id: &ObjectId,
+        folder: &ObjectId,
+    ) -> Result<FileRequest> {
+        let request = self
+            .collection
+            .find_one_and_delete(doc! {"_id": id, "folder": folder}, None)
+            .await?
+            .ok_or("Cannot delete the file request")?;
+        Ok(request)
+    }
+
+    pub async fn delete_requests_by_folders(&self, folders: &[ObjectId]) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"folder": {"$in": folders}}, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_requests_by_creator(&self, creator: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"creator": creator}, None)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
 use futures::TryStreamExt;
 use mongodb::{
     bson::{doc, oid::ObjectId, Document},
+    options::FindOptions,
     Collection,
 };
 
 use crate::base::file_version::FileVersion;
 
 use super::mongo::DB;
+use crate::error::Error;
 use crate::Result;
 
 #[derive(Debug, Clone)]
@@ -30,12 +32,29 @@ impl FileVersionDB {
         self.get_versions_by(doc! {"file": file_id }).await
     }
 
-    pub async fn get_version_by(&self, doc: Document) -> Result<FileVersion> {
-        Ok(self
+    // Fetches the versions of every file in the id list in one round trip
+    // The versions are sorted by their version number, oldest first, same as the insertion order
+    pub async fn get_versions_by_file_ids(
+        &self,
+        file_ids: &[ObjectId],
+    ) -> Result<Vec<FileVersion>> {
+        let options = FindOptions::builder()
+            .sort(doc! {"versionNumber": 1})
+            .build();
+        let file_versions = self
             .collection
-            .find_one(doc, None)
+            .find(doc! {"file": {"$in": file_ids}}, options)
             .await?
-            .ok_or("Cannot find the version with the provided information")?)
+            .try_collect()
+            .await?;
+        Ok(file_versions)
+    }
+
+    pub async fn get_version_by(&self, doc: Document) -> Result<FileVersion> {
+        let version = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the version with the provided information".into())
+        })?;
+        Ok(version)
     }
 
     pub async fn get_version_by_id(&self, id: &ObjectId) -> Result<FileVersion> {
@@ -1,10 +1,15 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::{doc, Regex};
 use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
 use mongodb::{bson::Document, Collection};
 
-use crate::base::folder::Folder;
+use crate::base::folder::{Folder, Visibility};
+use crate::error::Error;
+use crate::helper::{escape::escape_regex, position::get_ancestor_dirs};
 use crate::Result;
 
 use super::mongo::DB;
@@ -48,11 +53,9 @@ impl FolderDB {
     }
 
     async fn get_folder_by(&self, doc: Document) -> Result<Folder> {
-        let folder = self
-            .collection
-            .find_one(doc, None)
-            .await?
-            .ok_or("Cannot find the folder with the provided information")?;
+        let folder = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the folder with the provided information".into())
+        })?;
         Ok(folder)
     }
 
@@ -73,8 +76,29 @@ impl FolderDB {
         self.get_folder_by(doc! {"fullpath": fullpath}).await
     }
 
+    pub async fn get_folders_by_fullpaths(&self, fullpaths: &[String]) -> Result<Vec<Folder>> {
+        self.get_folders_by(doc! {"fullpath": {"$in": fullpaths}})
+            .await
+    }
+
+    // The own visibility of every folder above the dirs, keyed by the fullpath of the folder
+    // Fetched in one query, for resolving the inherited visibility of many files or folders
+    pub async fn get_dir_visibilities(&self, dirs: &[&str]) -> Result<HashMap<String, Visibility>> {
+        let fullpaths = dirs
+            .iter()
+            .flat_map(|d| get_ancestor_dirs(d))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let folders = self.get_folders_by_fullpaths(&fullpaths).await?;
+        Ok(folders
+            .into_iter()
+            .map(|f| (f.fullpath, f.visibility))
+            .collect())
+    }
+
     pub async fn get_folders_by_prefix_position(&self, prefix: &str) -> Result<Vec<Folder>> {
-        let position_regex = format!("^{prefix}");
+        let position_regex = format!("^{}", escape_regex(prefix));
         self.get_folders_by(doc! {
             "position": {
                 "$regex": position_regex
@@ -84,7 +108,7 @@ impl FolderDB {
     }
 
     pub async fn get_folders_by_prefix_exact_position(&self, prefix: &str) -> Result<Vec<Folder>> {
-        let position_regex = format!("^{prefix}$");
+        let position_regex = format!("^{}$", escape_regex(prefix));
         self.get_folders_by(doc! {
             "position": {
                 "$regex": position_regex
@@ -95,7 +119,7 @@ impl FolderDB {
 
     pub async fn get_public_folders_by_prefix_position(&self, prefix: &str) -> Result<Vec<Folder>> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.get_folders_by(doc! {
@@ -152,6 +176,101 @@ impl FolderDB {
         Ok(folder)
     }
 
+    pub async fn update_visibility(&self, id: &ObjectId, visibility: Visibility) -> Result<Folder> {
+        self.update_folder_with(
+            id,
+            doc! {"$set": {
+                "visibility": visibility.as_str(),
+                "updatedAt": Utc::now().timestamp_millis()
+            }},
+        )
+        .await
+    }
+
+    async fn update_folder_with(&self, id: &ObjectId, update: Document) -> Result<Folder> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let folder = self
+            .collection
+            .find_one_and_update(doc! {"_id": id}, update, options)
+            .await?
+            .ok_or("Cannot update the folder")?;
+        Ok(folder)
+    }
+
+    pub async fn add_tags(&self, id: &ObjectId, tags: &[String]) -> Result<Folder> {
+        self.update_folder_with(
+            id,
+            doc! {
+                "$addToSet": {"tags": {"$each": tags}},
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+        )
+        .await
+    }
+
+    pub async fn remove_tags(&self, id: &ObjectId, tags: &[String]) -> Result<Folder> {
+        self.update_folder_with(
+            id,
+            doc! {
+                "$pull": {"tags": {"$in": tags}},
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+        )
+        .await
+    }
+
+    // Only the provided keys are changed, the other metadata entries are kept as they are
+    pub async fn update_metadata(
+        &self,
+        id: &ObjectId,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<Folder> {
+        let mut set = doc! {"updatedAt": Utc::now().timestamp_millis()};
+        for (key, value) in metadata {
+            set.insert(format!("metadata.{key}"), value);
+        }
+        self.update_folder_with(id, doc! {"$set": set}).await
+    }
+
+    pub async fn delete_metadata(&self, id: &ObjectId, key: &str) -> Result<Folder> {
+        let mut unset = Document::new();
+        unset.insert(format!("metadata.{key}"), "");
+        self.update_folder_with(
+            id,
+            doc! {
+                "$unset": unset,
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+        )
+        .await
+    }
+
+    // Counts how many folders use each tag, among the folders matching the filter
+    pub async fn count_tags_by(&self, filter: Document) -> Result<Vec<(String, i64)>> {
+        let pipeline = vec![
+            doc! {"$match": filter},
+            doc! {"$unwind": "$tags"},
+            doc! {"$group": {"_id": "$tags", "count": {"$sum": 1}}},
+        ];
+        let counts = self
+            .collection
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .filter_map(|d| {
+                let tag = d.get_str("_id").ok()?.to_string();
+                let count = d.get_i32("count").ok()?;
+                Some((tag, count as i64))
+            })
+            .collect();
+        Ok(counts)
+    }
+
     pub async fn move_inner_folders(
         &self,
         old_position: &str,
@@ -162,7 +281,7 @@ impl FolderDB {
         // use new position to replace old position
 
         let position_regex = &Regex {
-            pattern: format!("^{old_fullpath}"),
+            pattern: format!("^{}", escape_regex(old_fullpath)),
             options: String::new(),
         };
 
@@ -217,9 +336,49 @@ impl FolderDB {
         Ok(())
     }
 
+    pub async fn get_folders_by_team(&self, team: &ObjectId) -> Result<Vec<Folder>> {
+        self.get_folders_by(doc! {"team": team}).await
+    }
+
+    pub async fn delete_folders_by_team(&self, team: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"team": team}, None)
+            .await?;
+        Ok(())
+    }
+
+    // Only the personal tree is hidden, what the user made in a team stays with the team
+    pub async fn update_hidden_by_owner(&self, owner: &ObjectId, hidden: bool) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"owner": owner, "team": null},
+                doc! {"$set": {"hidden": hidden}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    // The folders created by a user in a team stay in the team when the user leaves for good
+    pub async fn transfer_team_folders(
+        &self,
+        team: &ObjectId,
+        from: &ObjectId,
+        to: &ObjectId,
+    ) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"team": team, "owner": from},
+                doc! {"$set": {"owner": to}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn delete_folders_by_prefix_position(&self, prefix: &str) -> Result<()> {
         let position_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.collection
@@ -228,9 +387,32 @@ impl FolderDB {
         Ok(())
     }
 
+    // Sets the visibility of the folder at the prefix, and of every folder inside of it
+    pub async fn update_visibilities_by_prefix_fullpath(
+        &self,
+        prefix: &str,
+        visibility: Visibility,
+    ) -> Result<()> {
+        let fullpath_regex = Regex {
+            pattern: format!("^{}", escape_regex(prefix)),
+            options: String::new(),
+        };
+        self.collection
+            .update_many(
+                doc! {"fullpath": {"$regex": fullpath_regex}},
+                doc! {"$set": {
+                    "visibility": visibility.as_str(),
+                    "updatedAt": Utc::now().timestamp_millis()
+                }},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn delete_folders_by_prefix_fullpath(&self, prefix: &str) -> Result<()> {
         let fullpath_regex = Regex {
-            pattern: format!("^{prefix}"),
+            pattern: format!("^{}", escape_regex(prefix)),
             options: String::new(),
         };
         self.collection
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::Collection;
+
+use crate::base::migration::Migration;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct MigrationDB {
+    collection: Collection<Migration>,
+    // The migrations can reach into any collection
+    db: DB,
+}
+
+impl MigrationDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("Migration"),
+            db: db.clone(),
+        }
+    }
+
+    pub async fn get_applied_names(&self) -> Result<HashSet<String>> {
+        let migrations = self
+            .collection
+            .find(doc! {}, None)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(migrations.into_iter().map(|m| m.name).collect())
+    }
+
+    pub async fn create_migration(&self, migration: Migration) -> Result<()> {
+        self.collection.insert_one(migration, None).await?;
+        Ok(())
+    }
+
+    // Gives the documents of the collection the default of every field they do not have yet
+    // Returns how many documents were changed, a document missing two fields counts twice
+    pub async fn set_missing_fields(
+        &self,
+        collection: &str,
+        defaults: Vec<(&str, Bson)>,
+    ) -> Result<u64> {
+        let collection = self.db.get_collection::<Document>(collection);
+        let mut modified = 0;
+        for (field, default) in defaults {
+            modified += collection
+                .update_many(
+                    doc! {field: {"$exists": false}},
+                    doc! {"$set": {field: default}},
+                    None,
+                )
+                .await?
+                .modified_count;
+        }
+        Ok(modified)
+    }
+}
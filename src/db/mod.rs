@@ -1,5 +1,39 @@
+pub mod acl_db;
+pub mod change_db;
 pub mod file_db;
+pub mod file_request_db;
 pub mod file_version_db;
 pub mod folder_db;
+pub mod migration_db;
 pub mod mongo;
+pub mod oidc_login_db;
+pub mod search_db;
+pub mod share_link_db;
+pub mod team_db;
 pub mod user_db;
+pub mod user_token_db;
+pub mod webhook_db;
+pub mod webhook_delivery_db;
+
+use crate::Result;
+
+use self::{
+    acl_db::AclDB, change_db::ChangeDB, file_request_db::FileRequestDB, mongo::DB,
+    oidc_login_db::OidcLoginDB, search_db::SearchDB, share_link_db::ShareLinkDB, team_db::TeamDB,
+    user_token_db::UserTokenDB, webhook_db::WebhookDB, webhook_delivery_db::WebhookDeliveryDB,
+};
+
+// Creating an index that already exists does nothing, so this is safe to run on every start
+pub async fn create_indexes(db: &DB) -> Result<()> {
+    SearchDB::init(db).create_indexes().await?;
+    AclDB::init(db).create_indexes().await?;
+    TeamDB::init(db).create_indexes().await?;
+    ShareLinkDB::init(db).create_indexes().await?;
+    FileRequestDB::init(db).create_indexes().await?;
+    UserTokenDB::init(db).create_indexes().await?;
+    OidcLoginDB::init(db).create_indexes().await?;
+    WebhookDB::init(db).create_indexes().await?;
+    WebhookDeliveryDB::init(db).create_indexes().await?;
+    ChangeDB::init(db).create_indexes().await?;
+    Ok(())
+}
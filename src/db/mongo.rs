@@ -1,23 +1,44 @@
-use crate::Result;
+use crate::{config::DatabaseConfig, Result};
 use mongodb::{options::ClientOptions, Client, Collection};
 
 #[derive(Debug, Clone)]
 pub struct DB {
     client: Client,
+    name: String,
 }
 
 impl DB {
-    pub async fn init() -> Result<Self> {
-        let mongodb_uri: String = std::env::var("MONGODB_URI")?;
-        let mut client_options = ClientOptions::parse(mongodb_uri).await?;
+    pub async fn init(config: &DatabaseConfig) -> Result<Self> {
+        let mut client_options = ClientOptions::parse(&config.uri).await?;
 
         client_options.app_name = Some("file-manager-backend".to_string());
         Ok(Self {
             client: Client::with_options(client_options)?,
+            name: config.name.clone(),
         })
     }
 
     pub fn get_collection<T>(&self, coll_name: &str) -> Collection<T> {
-        self.client.database("final-db").collection(coll_name)
+        self.client.database(&self.name).collection(coll_name)
+    }
+}
+
+// The tests that need the database get one of their own on the server at TEST_MONGODB_URI,
+// they are ignored by default and run with cargo test -- --ignored
+#[cfg(test)]
+impl DB {
+    pub async fn init_test() -> Self {
+        let uri = std::env::var("TEST_MONGODB_URI")
+            .unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
+        Self::init(&DatabaseConfig {
+            uri,
+            name: format!("test-{}", mongodb::bson::oid::ObjectId::new()),
+        })
+        .await
+        .unwrap()
+    }
+
+    pub async fn drop_test(&self) {
+        self.client.database(&self.name).drop(None).await.unwrap();
     }
 }
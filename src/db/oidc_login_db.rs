@@ -0,0 +1,61 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+
+use crate::base::oidc_login::{OidcLogin, OIDC_LOGIN_LIFETIME};
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct OidcLoginDB {
+    collection: Collection<OidcLogin>,
+}
+
+impl OidcLoginDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("OidcLogin"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let state_index = IndexModel::builder()
+            .keys(doc! {"state": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        self.collection.create_indexes([state_index], None).await?;
+        Ok(())
+    }
+
+    pub async fn create_login(&self, login: OidcLogin) -> Result<()> {
+        // The logins that were never finished are cleaned up along the way
+        self.collection
+            .delete_many(
+                doc! {"createdAt": {"$lte": Utc::now().timestamp_millis() - OIDC_LOGIN_LIFETIME}},
+                None,
+            )
+            .await?;
+        self.collection.insert_one(login, None).await?;
+        Ok(())
+    }
+
+    // Finding and deleting in one step, so that a callback cannot be replayed
+    pub async fn consume_login(&self, state: &str, provider: &str) -> Result<OidcLogin> {
+        let login = self
+            .collection
+            .find_one_and_delete(
+                doc! {
+                    "state": state,
+                    "provider": provider,
+                    "createdAt": {"$gt": Utc::now().timestamp_millis() - OIDC_LOGIN_LIFETIME},
+                },
+                None,
+            )
+            .await?
+            .ok_or("The login is invalid or has expired, please try again")?;
+        Ok(login)
+    }
+}
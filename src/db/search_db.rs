@@ -0,0 +1,170 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, from_document, Document};
+use mongodb::options::{IndexOptions, UpdateOptions};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::search_entry::{SearchEntry, SearchHit};
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct SearchDB {
+    collection: Collection<SearchEntry>,
+}
+
+impl SearchDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("Search"),
+        }
+    }
+
+    // MongoDB only allows one text index per collection,
+    // so both the name and the content are covered by the same index
+    // A match in the name weights more than a match in the content
+    pub async fn create_indexes(&self) -> Result<()> {
+        let text_index = IndexModel::builder()
+            .keys(doc! {"name": "text", "content": "text"})
+            .options(
+                IndexOptions::builder()
+                    .name("search_text".to_string())
+                    .weights(doc! {"name": 10, "content": 1})
+                    // Turn off stemming and stop words, the names and contents are in many languages
+                    .default_language("none".to_string())
+                    .build(),
+            )
+            .build();
+
+        let resource_index = IndexModel::builder()
+            .keys(doc! {"resource": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        self.collection
+            .create_indexes([text_index, resource_index], None)
+            .await?;
+        Ok(())
+    }
+
+    // Creates or refreshes the entry of a file or folder
+    // When with_content is false, the indexed content is left untouched,
+    // which is the case when a file is renamed or moved without uploading new data
+    pub async fn index_entry(&self, entry: SearchEntry, with_content: bool) -> Result<()> {
+        let resource = entry.resource;
+        let created_at = entry.created_at;
+        let content = entry.content.clone();
+
+        let mut entry_doc: Document = entry.into();
+        entry_doc.remove("createdAt");
+        entry_doc.remove("content");
+
+        let mut set_on_insert = doc! {"createdAt": created_at};
+        if with_content {
+            entry_doc.insert("content", content);
+        } else {
+            set_on_insert.insert("content", "");
+        }
+        entry_doc.insert("updatedAt", Utc::now().timestamp_millis());
+
+        let options = UpdateOptions::builder().upsert(true).build();
+
+        self.collection
+            .update_one(
+                doc! {"resource": resource},
+                doc! {"$set": entry_doc, "$setOnInsert": set_on_insert},
+                options,
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Searches the entries that match the access filter, ranked by relevance
+    // The access is only narrowed down here, the search service still checks the files and folders
+    pub async fn search(
+        &self,
+        query: &str,
+        access: Document,
+        skip: u64,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>> {
+        let pipeline = vec![
+            // The text search must be the first stage of the pipeline
+            doc! {"$match": {"$text": {"$search": query}}},
+            doc! {"$match": access},
+            doc! {"$addFields": {"score": {"$meta": "textScore"}}},
+            // The resource breaks the ties, so that the pages do not overlap
+            doc! {"$sort": {"score": -1, "resource": 1}},
+            doc! {"$skip": skip as i64},
+            doc! {"$limit": limit},
+        ];
+
+        let hits = self
+            .collection
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(from_document::<SearchHit>)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| format!("Cannot read the search result: {e}"))?;
+        Ok(hits)
+    }
+
+    pub async fn update_visibilities_by_resources(
+        &self,
+        resources: &[ObjectId],
+        visibility: &str,
+    ) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"resource": {"$in": resources}},
+                doc! {"$set": {"visibility": visibility}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_entry_by_resource(&self, resource: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_one(doc! {"resource": resource}, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_entries_by_resources(&self, resources: &[ObjectId]) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"resource": {"$in": resources}}, None)
+            .await?;
+        Ok(())
+    }
+
+    // The entries follow the files and folders when they are handed over to the owner of the team
+    pub async fn transfer_team_entries(
+        &self,
+        team: &ObjectId,
+        from: &ObjectId,
+        to: &ObjectId,
+    ) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"team": team, "owner": from},
+                doc! {"$set": {"owner": to}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Only the personal tree, the entries of the teams stay with the teams
+    pub async fn delete_entries_by_owner(&self, owner: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"owner": owner, "team": null}, None)
+            .await?;
+        Ok(())
+    }
+}
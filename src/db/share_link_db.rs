@@ -0,0 +1,101 @@
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+
+use crate::base::share_link::ShareLink;
+use crate::error::Error;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct ShareLinkDB {
+    collection: Collection<ShareLink>,
+}
+
+impl ShareLinkDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("ShareLink"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let token_index = IndexModel::builder()
+            .keys(doc! {"token": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        let folder_index = IndexModel::builder().keys(doc! {"folder": 1}).build();
+
+        self.collection
+            .create_indexes([token_index, folder_index], None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_link_by(&self, doc: Document) -> Result<ShareLink> {
+        let link = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the link with the provided information".into())
+        })?;
+        Ok(link)
+    }
+
+    pub async fn get_link_by_id(&self, id: &ObjectId) -> Result<ShareLink> {
+        self.get_link_by(doc! {"_id": id}).await
+    }
+
+    pub async fn get_link_by_token(&self, token: &str) -> Result<ShareLink> {
+        self.get_link_by(doc! {"token": token}).await
+    }
+
+    pub async fn get_links_by_folder(&self, folder: &ObjectId) -> Result<Vec<ShareLink>> {
+        let links = self
+            .collection
+            .find(doc! {"folder": folder}, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(links)
+    }
+
+    pub async fn create_link(&self, link: ShareLink) -> Result<ShareLink> {
+        let new_link_id = self
+            .collection
+            .insert_one(link, None)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .ok_or("Cannot create a new link")?;
+        self.get_link_by_id(&new_link_id).await
+    }
+
+    pub async fn delete_link_by_id_folder(
+        &self,
+        id: &ObjectId,
+        folder: &ObjectId,
+    ) -> Result<ShareLink> {
+        let link = self
+            .collection
+            .find_one_and_delete(doc! {"_id": id, "folder": folder}, None)
+            .await?
+            .ok_or("Cannot delete the link")?;
+        Ok(link)
+    }
+
+    pub async fn delete_links_by_folders(&self, folders: &[ObjectId]) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"folder": {"$in": folders}}, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_links_by_creator(&self, creator: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"creator": creator}, None)
+            .await?;
+        Ok(())
+    }
+}
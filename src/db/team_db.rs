@@ -0,0 +1,169 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::team::{Team, TeamMember, TeamRole};
+use crate::error::Error;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct TeamDB {
+    collection: Collection<Team>,
+}
+
+impl TeamDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("Team"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let name_index = IndexModel::builder()
+            .keys(doc! {"name": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        let member_index = IndexModel::builder().keys(doc! {"members.user": 1}).build();
+
+        self.collection
+            .create_indexes([name_index, member_index], None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_teams_by(&self, doc: Document) -> Result<Vec<Team>> {
+        let teams = self.collection.find(doc, None).await?.try_collect().await?;
+        Ok(teams)
+    }
+
+    pub async fn get_teams_by_member(&self, user: &ObjectId) -> Result<Vec<Team>> {
+        self.get_teams_by(doc! {"members.user": user}).await
+    }
+
+    pub async fn get_teams_by_owner(&self, user: &ObjectId) -> Result<Vec<Team>> {
+        self.get_teams_by(doc! {
+            "members": {"$elemMatch": {"user": user, "role": TeamRole::Owner.as_str()}}
+        })
+        .await
+    }
+
+    async fn get_team_by(&self, doc: Document) -> Result<Team> {
+        let team = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the team with the provided information".into())
+        })?;
+        Ok(team)
+    }
+
+    pub async fn get_team_by_id(&self, id: &ObjectId) -> Result<Team> {
+        self.get_team_by(doc! {"_id": id}).await
+    }
+
+    pub async fn exists_team_by_name(&self, name: &str) -> Result<bool> {
+        let count = self
+            .collection
+            .count_documents(doc! {"name": name}, None)
+            .await?;
+        Ok(count != 0)
+    }
+
+    pub async fn create_team(&self, team: Team) -> Result<Team> {
+        let new_team_id = self
+            .collection
+            .insert_one(team, None)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .ok_or("Cannot create a new team")?;
+        self.get_team_by_id(&new_team_id).await
+    }
+
+    async fn update_team_with(
+        &self,
+        filter: Document,
+        update: Document,
+        error: Error,
+    ) -> Result<Team> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let team = self
+            .collection
+            .find_one_and_update(filter, update, options)
+            .await?
+            .ok_or(error)?;
+        Ok(team)
+    }
+
+    // The member is only added when the user is not in the team yet
+    pub async fn add_member(&self, id: &ObjectId, member: TeamMember) -> Result<Team> {
+        let user = member.user;
+        let member_doc: Document = member.into();
+        self.update_team_with(
+            doc! {"_id": id, "members.user": {"$ne": user}},
+            doc! {
+                "$push": {"members": member_doc},
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+            Error::Conflict("The user is already a member of this team".into()),
+        )
+        .await
+    }
+
+    pub async fn update_member_role(
+        &self,
+        id: &ObjectId,
+        user: &ObjectId,
+        role: TeamRole,
+    ) -> Result<Team> {
+        self.update_team_with(
+            doc! {"_id": id, "members.user": user},
+            doc! {
+                "$set": {
+                    "members.$.role": role.as_str(),
+                    "updatedAt": Utc::now().timestamp_millis()
+                }
+            },
+            Error::NotFound("The user is not a member of this team".into()),
+        )
+        .await
+    }
+
+    pub async fn remove_member(&self, id: &ObjectId, user: &ObjectId) -> Result<Team> {
+        self.update_team_with(
+            doc! {"_id": id, "members.user": user},
+            doc! {
+                "$pull": {"members": {"user": user}},
+                "$set": {"updatedAt": Utc::now().timestamp_millis()}
+            },
+            Error::NotFound("The user is not a member of this team".into()),
+        )
+        .await
+    }
+
+    pub async fn remove_member_from_teams(&self, user: &ObjectId) -> Result<()> {
+        self.collection
+            .update_many(
+                doc! {"members.user": user},
+                doc! {"$pull": {"members": {"user": user}}},
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_team(&self, id: &ObjectId) -> Result<Team> {
+        let team = self
+            .collection
+            .find_one_and_delete(doc! {"_id": id}, None)
+            .await?
+            .ok_or("Cannot delete the team")?;
+        Ok(team)
+    }
+}
@@ -1,10 +1,13 @@
+use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::{doc, Document};
 use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
 use mongodb::Collection;
 
-use crate::base::user::User;
+use crate::base::user::{Identity, Status, TwoFactor, User};
+use crate::error::Error;
+use crate::helper::escape::escape_regex;
 use crate::Result;
 
 use super::mongo::DB;
@@ -30,12 +33,28 @@ impl UserDB {
         self.get_users_by(doc! {}).await
     }
 
+    // Matches the username or the email, case insensitively
+    pub async fn search_users(&self, query: &str) -> Result<Vec<User>> {
+        let pattern = escape_regex(query);
+        self.get_users_by(doc! {
+            "$or": [
+                {"username": {"$regex": &pattern, "$options": "i"}},
+                {"email": {"$regex": &pattern, "$options": "i"}},
+            ]
+        })
+        .await
+    }
+
+    // Fetches every user in the id list in one round trip
+    // Used by the listing handlers, so that they don't have to look up the owners one by one
+    pub async fn get_users_by_ids(&self, ids: &[ObjectId]) -> Result<Vec<User>> {
+        self.get_users_by(doc! {"_id": {"$in": ids}}).await
+    }
+
     async fn get_user_by(&self, doc: Document) -> Result<User> {
-        let user = self
-            .collection
-            .find_one(doc, None)
-            .await?
-            .ok_or("Cannot get the user with the provided information")?;
+        let user = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot get the user with the provided information".into())
+        })?;
         Ok(user)
     }
 
@@ -43,6 +62,28 @@ impl UserDB {
         self.get_user_by(doc! {"_id": id}).await
     }
 
+    pub async fn get_user_by_username(&self, username: &str) -> Result<User> {
+        self.get_user_by(doc! {"username": username}).await
+    }
+
+    pub async fn get_user_by_email(&self, email: &str) -> Result<User> {
+        self.get_user_by(doc! {"email": email}).await
+    }
+
+    // None when nobody has logged in with this identity yet
+    pub async fn find_user_by_identity(&self, identity: &Identity) -> Result<Option<User>> {
+        let user = self
+            .collection
+            .find_one(
+                doc! {"identities": {
+                    "$elemMatch": {"provider": &identity.provider, "subject": &identity.subject}
+                }},
+                None,
+            )
+            .await?;
+        Ok(user)
+    }
+
     pub async fn get_user_by_login_info(&self, username: &str, password: &str) -> Result<User> {
         self.get_user_by(doc! {"username": username, "password": password})
             .await
@@ -108,6 +149,174 @@ impl UserDB {
         Ok(())
     }
 
+    // The users whose grace period is over
+    pub async fn get_users_due_for_deletion(&self, now: i64) -> Result<Vec<User>> {
+        self.get_users_by(doc! {
+            "status": Status::PendingDeletion.as_str(),
+            "deletionScheduledAt": {"$lte": now},
+        })
+        .await
+    }
+
+    pub async fn update_status(
+        &self,
+        id: &ObjectId,
+        status: Status,
+        deletion_scheduled_at: Option<i64>,
+    ) -> Result<User> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        // Locking also logs the user out, the refresh token cannot match anymore
+        let mut set = doc! {
+            "status": status.as_str(),
+            "deletionScheduledAt": deletion_scheduled_at,
+            "updatedAt": Utc::now().timestamp_millis(),
+        };
+        if status != Status::Active {
+            set.insert("refreshToken", "");
+        }
+
+        let user = self
+            .collection
+            .find_one_and_update(doc! {"_id": id}, doc! {"$set": set}, options)
+            .await?
+            .ok_or("Cannot update the user")?;
+        Ok(user)
+    }
+
+    pub async fn update_password(&self, id: &ObjectId, password: &str) -> Result<User> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        // The sessions made with the old password are ended as well
+        let user = self
+            .collection
+            .find_one_and_update(
+                doc! {"_id": id},
+                doc! {"$set": {
+                    "password": password,
+                    "refreshToken": "",
+                    "updatedAt": Utc::now().timestamp_millis(),
+                }},
+                options,
+            )
+            .await?
+            .ok_or("Cannot update the user")?;
+        Ok(user)
+    }
+
+    // Only verifies the email that the token was sent to, not one the user has changed to since
+    pub async fn update_email_verified(
+        &self,
+        id: &ObjectId,
+        email: &str,
+        email_verified: bool,
+    ) -> Result<User> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let user = self
+            .collection
+            .find_one_and_update(
+                doc! {"_id": id, "email": email},
+                doc! {"$set": {"emailVerified": email_verified}},
+                options,
+            )
+            .await?
+            .ok_or("Cannot verify the email of the user")?;
+        Ok(user)
+    }
+
+    // The provider vouches for the email when it says that it is verified
+    pub async fn add_identity(
+        &self,
+        id: &ObjectId,
+        identity: Identity,
+        email_verified: bool,
+    ) -> Result<User> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let mut update = doc! {"$push": {"identities": Document::from(identity)}};
+        if email_verified {
+            update.insert("$set", doc! {"emailVerified": true});
+        }
+
+        let user = self
+            .collection
+            .find_one_and_update(doc! {"_id": id}, update, options)
+            .await?
+            .ok_or("Cannot link the identity to the user")?;
+        Ok(user)
+    }
+
+    pub async fn update_two_factor(
+        &self,
+        id: &ObjectId,
+        two_factor: Option<TwoFactor>,
+    ) -> Result<User> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let two_factor = two_factor.map(Document::from);
+
+        let user = self
+            .collection
+            .find_one_and_update(
+                doc! {"_id": id},
+                doc! {"$set": {"twoFactor": two_factor}},
+                options,
+            )
+            .await?
+            .ok_or("Cannot update the user")?;
+        Ok(user)
+    }
+
+    // Only moves the last step forward, so that two requests cannot both use the same code
+    // Returns None when another request has already used this step or a later one
+    pub async fn use_two_factor_step(&self, id: &ObjectId, step: i64) -> Result<Option<User>> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let user = self
+            .collection
+            .find_one_and_update(
+                doc! {"_id": id, "twoFactor.enabled": true, "twoFactor.lastStep": {"$lt": step}},
+                doc! {"$set": {"twoFactor.lastStep": step}},
+                options,
+            )
+            .await?;
+        Ok(user)
+    }
+
+    // Takes the hashed recovery code out, returns None when it is not there (anymore)
+    pub async fn use_recovery_code(
+        &self,
+        id: &ObjectId,
+        hashed_code: &str,
+    ) -> Result<Option<User>> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let user = self
+            .collection
+            .find_one_and_update(
+                doc! {"_id": id, "twoFactor.enabled": true, "twoFactor.recoveryCodes": hashed_code},
+                doc! {"$pull": {"twoFactor.recoveryCodes": hashed_code}},
+                options,
+            )
+            .await?;
+        Ok(user)
+    }
+
     pub async fn delete_user(&self, id: &ObjectId) -> Result<User> {
         let deleted_user = self
             .collection
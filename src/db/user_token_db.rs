@@ -0,0 +1,76 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+
+use crate::base::user_token::{TokenKind, UserToken};
+use crate::helper::hash::hash_token;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct UserTokenDB {
+    collection: Collection<UserToken>,
+}
+
+impl UserTokenDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("UserToken"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let token_index = IndexModel::builder()
+            .keys(doc! {"tokenHash": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        let user_index = IndexModel::builder()
+            .keys(doc! {"user": 1, "kind": 1})
+            .build();
+
+        self.collection
+            .create_indexes([token_index, user_index], None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_token(&self, token: UserToken) -> Result<()> {
+        self.collection.insert_one(token, None).await?;
+        Ok(())
+    }
+
+    // Finding and deleting in one step, so that a token can never be used twice
+    pub async fn consume_token(&self, token: &str, kind: TokenKind) -> Result<UserToken> {
+        let user_token = self
+            .collection
+            .find_one_and_delete(
+                doc! {
+                    "tokenHash": hash_token(token),
+                    "kind": kind.as_str(),
+                    "expiresAt": {"$gt": Utc::now().timestamp_millis()},
+                },
+                None,
+            )
+            .await?
+            .ok_or("The token is invalid or has expired")?;
+        Ok(user_token)
+    }
+
+    pub async fn delete_tokens_by_user_kind(&self, user: &ObjectId, kind: TokenKind) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"user": user, "kind": kind.as_str()}, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_tokens_by_user(&self, user: &ObjectId) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"user": user}, None)
+            .await?;
+        Ok(())
+    }
+}
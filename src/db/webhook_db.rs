@@ -0,0 +1,126 @@
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::webhook::Webhook;
+use crate::error::Error;
+use crate::Result;
+
+use super::mongo::DB;
+
+#[derive(Debug, Clone)]
+pub struct WebhookDB {
+    collection: Collection<Webhook>,
+}
+
+impl WebhookDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("Webhook"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let owner_index = IndexModel::builder().keys(doc! {"owner": 1}).build();
+
+        // Every event looks up the webhooks that are subscribed to it
+        let events_index = IndexModel::builder()
+            .keys(doc! {"events": 1, "active": 1})
+            .build();
+
+        self.collection
+            .create_indexes([owner_index, events_index], None)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_webhook_by(&self, doc: Document) -> Result<Webhook> {
+        let webhook = self.collection.find_one(doc, None).await?.ok_or_else(|| {
+            Error::NotFound("Cannot find the webhook with the provided information".into())
+        })?;
+        Ok(webhook)
+    }
+
+    pub async fn get_webhook_by_id(&self, id: &ObjectId) -> Result<Webhook> {
+        self.get_webhook_by(doc! {"_id": id}).await
+    }
+
+    pub async fn get_webhook_by_id_owner(
+        &self,
+        id: &ObjectId,
+        owner: &ObjectId,
+    ) -> Result<Webhook> {
+        self.get_webhook_by(doc! {"_id": id, "owner": owner}).await
+    }
+
+    async fn get_webhooks_by(&self, doc: Document) -> Result<Vec<Webhook>> {
+        let webhooks = self.collection.find(doc, None).await?.try_collect().await?;
+        Ok(webhooks)
+    }
+
+    pub async fn get_webhooks_by_owner(&self, owner: &ObjectId) -> Result<Vec<Webhook>> {
+        self.get_webhooks_by(doc! {"owner": owner}).await
+    }
+
+    pub async fn get_webhooks_by_event(&self, event: &str) -> Result<Vec<Webhook>> {
+        self.get_webhooks_by(doc! {"events": event, "active": true})
+            .await
+    }
+
+    pub async fn count_webhooks_by_owner(&self, owner: &ObjectId) -> Result<u64> {
+        let count = self
+            .collection
+            .count_documents(doc! {"owner": owner}, None)
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn create_webhook(&self, webhook: Webhook) -> Result<Webhook> {
+        let new_webhook_id = self
+            .collection
+            .insert_one(webhook, None)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .ok_or("Cannot create a new webhook")?;
+        self.get_webhook_by_id(&new_webhook_id).await
+    }
+
+    pub async fn update_webhook(&self, id: &ObjectId, update: Document) -> Result<Webhook> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let webhook = self
+            .collection
+            .find_one_and_update(doc! {"_id": id}, doc! {"$set": update}, options)
+            .await?
+            .ok_or("Cannot update the webhook")?;
+        Ok(webhook)
+    }
+
+    pub async fn delete_webhook_by_id(&self, id: &ObjectId) -> Result<Webhook> {
+        let webhook = self
+            .collection
+            .find_one_and_delete(doc! {"_id": id}, None)
+            .await?
+            .ok_or("Cannot delete the webhook")?;
+        Ok(webhook)
+    }
+
+    // Returns the ids of the deleted webhooks, so that their deliveries can go as well
+    pub async fn delete_webhooks_by_owner(&self, owner: &ObjectId) -> Result<Vec<ObjectId>> {
+        let ids = self
+            .get_webhooks_by_owner(owner)
+            .await?
+            .into_iter()
+            .map(|w| w.id)
+            .collect::<Vec<_>>();
+        self.collection
+            .delete_many(doc! {"owner": owner}, None)
+            .await?;
+        Ok(ids)
+    }
+}
@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use crate::base::webhook_delivery::{DeliveryAttempt, DeliveryStatus, WebhookDelivery};
+use crate::error::Error;
+use crate::Result;
+
+use super::mongo::DB;
+
+// A claimed delivery is left alone for this long, so a crashed try gets retried later
+const CLAIM_LEASE: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+pub struct WebhookDeliveryDB {
+    collection: Collection<WebhookDelivery>,
+}
+
+impl WebhookDeliveryDB {
+    pub fn init(db: &DB) -> Self {
+        Self {
+            collection: db.get_collection("WebhookDelivery"),
+        }
+    }
+
+    pub async fn create_indexes(&self) -> Result<()> {
+        let webhook_index = IndexModel::builder()
+            .keys(doc! {"webhook": 1, "createdAt": -1})
+            .build();
+
+        // The retries look for the pending deliveries that are due
+        let due_index = IndexModel::builder()
+            .keys(doc! {"status": 1, "nextAttemptAt": 1})
+            .build();
+
+        self.collection
+            .create_indexes([webhook_index, due_index], None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_delivery_by_id_webhook(
+        &self,
+        id: &ObjectId,
+        webhook: &ObjectId,
+    ) -> Result<WebhookDelivery> {
+        let delivery = self
+            .collection
+            .find_one(doc! {"_id": id, "webhook": webhook}, None)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound("Cannot find the delivery with the provided information".into())
+            })?;
+        Ok(delivery)
+    }
+
+    // The newest ones first
+    pub async fn get_deliveries_by_webhook(
+        &self,
+        webhook: &ObjectId,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let options = FindOptions::builder()
+            .sort(doc! {"createdAt": -1})
+            .limit(limit)
+            .build();
+        let deliveries = self
+            .collection
+            .find(doc! {"webhook": webhook}, options)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(deliveries)
+    }
+
+    pub async fn create_delivery(&self, delivery: WebhookDelivery) -> Result<WebhookDelivery> {
+        let new_delivery_id = self
+            .collection
+            .insert_one(delivery, None)
+            .await?
+            .inserted_id
+            .as_object_id()
+            .ok_or("Cannot create a new delivery")?;
+        let delivery = self
+            .collection
+            .find_one(doc! {"_id": new_delivery_id}, None)
+            .await?
+            .ok_or("Cannot create a new delivery")?;
+        Ok(delivery)
+    }
+
+    // Takes one pending delivery that is due, pushing its next try into the future,
+    // so that no one else sends it at the same time
+    async fn claim_delivery_by(&self, filter: Document) -> Result<Option<WebhookDelivery>> {
+        let now = Utc::now().timestamp_millis();
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .sort(doc! {"nextAttemptAt": 1})
+            .build();
+
+        let mut filter = filter;
+        filter.insert("status", DeliveryStatus::Pending.as_str());
+        filter.insert("nextAttemptAt", doc! {"$lte": now});
+
+        let delivery = self
+            .collection
+            .find_one_and_update(
+                filter,
+                doc! {"$set": {"nextAttemptAt": now + CLAIM_LEASE.as_millis() as i64}},
+                options,
+            )
+            .await?;
+        Ok(delivery)
+    }
+
+    pub async fn claim_delivery_by_id(&self, id: &ObjectId) -> Result<Option<WebhookDelivery>> {
+        self.claim_delivery_by(doc! {"_id": id}).await
+    }
+
+    pub async fn claim_due_delivery(&self) -> Result<Option<WebhookDelivery>> {
+        self.claim_delivery_by(doc! {}).await
+    }
+
+    pub async fn record_attempt(
+        &self,
+        id: &ObjectId,
+        attempt: DeliveryAttempt,
+        status: DeliveryStatus,
+        next_attempt_at: Option<i64>,
+    ) -> Result<()> {
+        let attempt: Document = attempt.into();
+        self.collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {
+                    "$push": {"attempts": attempt},
+                    "$set": {
+                        "status": status.as_str(),
+                        "nextAttemptAt": next_attempt_at,
+                        "updatedAt": Utc::now().timestamp_millis(),
+                    }
+                },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_deliveries_by_webhooks(&self, webhooks: &[ObjectId]) -> Result<()> {
+        self.collection
+            .delete_many(doc! {"webhook": {"$in": webhooks}}, None)
+            .await?;
+        Ok(())
+    }
+}
@@ -5,7 +5,11 @@ use aws_sdk_s3::{
     },
     types::SdkError,
 };
-use salvo::{prelude::StatusError, Piece};
+use salvo::{
+    http::{header::RETRY_AFTER, StatusCode},
+    Piece,
+};
+use serde_json::json;
 use thiserror::Error;
 
 use crate::{helper::print_validation::extract_validation_error, web::Web};
@@ -24,9 +28,28 @@ pub enum Error {
     #[error("Generic error: {0}")]
     Generic(String),
 
+    // Logged in, but not allowed to do this
     #[error("Permissions error: {0}")]
     Permissions(String),
 
+    // Not logged in, or the credentials are wrong
+    #[error("Unauthorized error: {0}")]
+    Unauthorized(String),
+
+    #[error("Not found error: {0}")]
+    NotFound(String),
+
+    // The request clashes with what is already there, like a taken name
+    #[error("Conflict error: {0}")]
+    Conflict(String),
+
+    #[error("Payload too large error: {0}")]
+    PayloadTooLarge(String),
+
+    // A limit on the number of uploads or on the storage has been reached
+    #[error("Quota exceeded error: {0}")]
+    QuotaExceeded(String),
+
     #[error("Validation error: {0}")]
     Validation(#[from] validator::ValidationErrors),
 
@@ -63,6 +86,10 @@ pub enum Error {
     #[error("IO error: {0}")]
     IO(#[from] std::io::Error),
 
+    // The number of seconds until the request can be made again
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(u64),
+
     #[error("AWS HTTP error: {0}")]
     Aws(#[from] aws_smithy_http::byte_stream::Error),
 }
@@ -115,6 +142,52 @@ impl From<SdkError<DeleteObjectsError>> for Error {
     }
 }
 
+impl Error {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Error::Generic(_) | Error::Validation(_) | Error::HttpParse(_) | Error::ObjectId(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Error::Unauthorized(_) | Error::Jwt(_) => StatusCode::UNAUTHORIZED,
+            Error::Permissions(_) | Error::QuotaExceeded(_) => StatusCode::FORBIDDEN,
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Conflict(_) => StatusCode::CONFLICT,
+            Error::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // Stable, so the clients can match on it instead of the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Var(_) | Error::Env(_) => "config_error",
+            Error::MongoDB(_) => "database_error",
+            Error::Generic(_) => "bad_request",
+            Error::Permissions(_) => "forbidden",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::NotFound(_) => "not_found",
+            Error::Conflict(_) => "conflict",
+            Error::PayloadTooLarge(_) => "payload_too_large",
+            Error::QuotaExceeded(_) => "quota_exceeded",
+            Error::Validation(_) => "validation_failed",
+            Error::HttpParse(_) => "invalid_request",
+            Error::Jwt(_) => "invalid_token",
+            Error::ObjectId(_) => "invalid_id",
+            Error::Presign(_)
+            | Error::PutObject(_)
+            | Error::GetObject(_)
+            | Error::ListObject(_)
+            | Error::CopyObject(_)
+            | Error::DeleteObject(_)
+            | Error::DeleteObjects(_)
+            | Error::Aws(_) => "storage_error",
+            Error::IO(_) => "io_error",
+            Error::TooManyRequests(_) => "too_many_requests",
+        }
+    }
+}
+
 impl Piece for Error {
     fn render(self, res: &mut salvo::Response) {
         let error_message = match self {
@@ -123,9 +196,12 @@ impl Piece for Error {
             Error::MongoDB(ref e) => format!("MongoDB has encountered an error {e}"),
             Error::Generic(ref e) => format!("Generic error: {e}"),
             Error::Permissions(ref e) => format!("Permission error: {e}"),
-            Error::Validation(ref e) => {
-                format!("Validation error {}", extract_validation_error(e))
-            }
+            Error::Unauthorized(ref e)
+            | Error::NotFound(ref e)
+            | Error::Conflict(ref e)
+            | Error::PayloadTooLarge(ref e)
+            | Error::QuotaExceeded(ref e) => e.clone(),
+            Error::Validation(_) => "Some of the fields are not valid".to_string(),
             Error::HttpParse(ref e) => format!("Http Parse error: {e}"),
             Error::Jwt(ref e) => format!("JWT error {e}"),
             Error::ObjectId(ref e) => format!("ObjectId parse error: {e}"),
@@ -138,25 +214,128 @@ impl Piece for Error {
             Error::DeleteObjects(ref e) => format!("DeleteObjects error {e}"),
             Error::IO(ref e) => format!("IO error {e}"),
             Error::Aws(ref e) => format!("Aws error {e}"),
-        };
-
-        let error_status = match self {
-            Error::Permissions(_) => StatusError::forbidden(),
-            Error::Generic(_) | Error::Validation(_) | Error::HttpParse(_) | Error::Jwt(_) => {
-                StatusError::bad_request()
+            Error::TooManyRequests(retry_after) => {
+                format!("Too many requests, please try again in {retry_after} seconds")
             }
-            _ => StatusError::internal_server_error(),
         };
 
-        let error = match self {
-            Error::Permissions(_) => Web::forbidden(error_message),
-            Error::Generic(_) | Error::Validation(_) | Error::HttpParse(_) | Error::Jwt(_) => {
-                Web::bad_request(error_message)
-            }
-            _ => Web::internal_error(error_message),
-        };
+        if let Error::TooManyRequests(retry_after) = self {
+            res.headers_mut().insert(RETRY_AFTER, retry_after.into());
+        }
+
+        let mut error = Web::error(self.status(), self.code(), error_message);
+        if let Error::Validation(ref e) = self {
+            error = error.with_details(json!({ "fields": extract_validation_error(e) }));
+        }
 
         res.render(error);
-        res.set_status_error(error_status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::{http::ResBody, Response};
+    use serde_json::Value;
+    use validator::{ValidationError, ValidationErrors};
+
+    use super::*;
+
+    fn render(error: Error) -> (StatusCode, Value) {
+        let mut res = Response::new();
+        error.render(&mut res);
+        let body = match res.take_body() {
+            ResBody::Once(bytes) => bytes.to_vec(),
+            ResBody::Chunks(chunks) => chunks.into_iter().flatten().collect(),
+            _ => panic!("the error has no body"),
+        };
+        (
+            res.status_code().unwrap(),
+            serde_json::from_slice(&body).unwrap(),
+        )
+    }
+
+    // The clients are told that these do not change, so a change here has to be on purpose
+    #[test]
+    fn maps_every_error_to_its_status_and_code() {
+        let table = [
+            (
+                Error::Var(std::env::VarError::NotPresent),
+                500,
+                "config_error",
+            ),
+            (
+                Error::MongoDB(std::io::Error::other("down").into()),
+                500,
+                "database_error",
+            ),
+            (Error::from("bad"), 400, "bad_request"),
+            (Error::Permissions("no".into()), 403, "forbidden"),
+            (Error::Unauthorized("no".into()), 401, "unauthorized"),
+            (Error::NotFound("gone".into()), 404, "not_found"),
+            (Error::Conflict("taken".into()), 409, "conflict"),
+            (
+                Error::PayloadTooLarge("big".into()),
+                413,
+                "payload_too_large",
+            ),
+            (Error::QuotaExceeded("full".into()), 403, "quota_exceeded"),
+            (
+                Error::Validation(ValidationErrors::new()),
+                400,
+                "validation_failed",
+            ),
+            (
+                Error::HttpParse(salvo::http::ParseError::EmptyBody),
+                400,
+                "invalid_request",
+            ),
+            (
+                Error::Jwt(jsonwebtoken::errors::ErrorKind::InvalidToken.into()),
+                401,
+                "invalid_token",
+            ),
+            (
+                mongodb::bson::oid::ObjectId::parse_str("nope")
+                    .unwrap_err()
+                    .into(),
+                400,
+                "invalid_id",
+            ),
+            (Error::IO(std::io::Error::other("disk")), 500, "io_error"),
+            (Error::TooManyRequests(30), 429, "too_many_requests"),
+        ];
+
+        for (error, status, code) in table {
+            let name = format!("{error:?}");
+            assert_eq!(error.status().as_u16(), status, "{name}");
+            assert_eq!(error.code(), code, "{name}");
+
+            let (rendered_status, body) = render(error);
+            assert_eq!(rendered_status.as_u16(), status, "{name}");
+            assert_eq!(body["code"], status, "{name}");
+            assert_eq!(body["errorCode"], code, "{name}");
+        }
+    }
+
+    #[test]
+    fn lists_the_problems_of_each_field() {
+        let mut errors = ValidationErrors::new();
+        let mut too_short = ValidationError::new("length");
+        too_short.message = Some("Username must be at least 2 characters".into());
+        too_short.add_param("value".into(), &"a");
+        errors.add("username", too_short);
+        errors.add("email", ValidationError::new("email"));
+
+        let (status, body) = render(Error::Validation(errors));
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["errorCode"], "validation_failed");
+        assert_eq!(
+            body["details"],
+            json!({"fields": {
+                "username": [{"code": "length", "message": "Username must be at least 2 characters"}],
+                "email": [{"code": "email", "message": "email"}],
+            }})
+        );
     }
 }
@@ -0,0 +1,229 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    error::Error,
+    helper::{
+        body::extract_from_body, cookie::get_cookie_user_id, depot::get_user_service,
+        param::get_param_user_id,
+    },
+    request::admin::password::ResetPasswordRequest as AdminResetPasswordRequest,
+    web::Web,
+    WebResult,
+};
+
+/// List the users
+#[utoipa::path(
+    get,
+    path = "/admin/users",
+    tag = "admin",
+    params(
+        ("search" = Option<String>, Query, description = "Only the users whose username or email contains this"),
+    ),
+    responses(
+        (status = 200, description = "List the users successfully", body = [UserResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_users_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let user_service = get_user_service(depot)?;
+
+    // Search by the username or the email, or list everyone without a search query
+    let users = match req.query::<String>("search") {
+        Some(search) => user_service.search_users(&search).await?,
+        None => user_service.get_users().await?,
+    };
+
+    let mut user_responses = vec![];
+    for u in users {
+        user_responses.push(u.into_response()?)
+    }
+
+    Ok(Web::ok("Get all users successfully", user_responses))
+}
+
+/// Get a user
+#[utoipa::path(
+    get,
+    path = "/admin/users/{param_user_id}",
+    tag = "admin",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Get a user successfully", body = UserResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let param_user_id = get_param_user_id(req)?;
+
+    let user = get_user_service(depot)?
+        .get_user_by_id(&param_user_id)
+        .await?;
+
+    Ok(Web::ok(
+        "Get user by id successfully",
+        user.into_response()?,
+    ))
+}
+
+/// Suspend a user
+#[utoipa::path(
+    put,
+    path = "/admin/users/{param_user_id}/suspend",
+    tag = "admin",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Suspend a user successfully", body = UserResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn suspend_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let param_user_id = get_param_user_id(req)?;
+
+    let user_service = get_user_service(depot)?;
+
+    // An admin would lock themselves out, and other admins have to be demoted first
+    if param_user_id == *get_cookie_user_id(depot)? {
+        return Err(Error::Permissions("You cannot suspend yourself".into()));
+    }
+    if user_service
+        .get_user_by_id(&param_user_id)
+        .await?
+        .is_admin()
+    {
+        return Err(Error::Permissions("An admin cannot be suspended".into()));
+    }
+
+    let user = user_service
+        .suspend_user_by_id(&param_user_id, true)
+        .await?;
+
+    Ok(Web::ok("Suspend user successfully", user.into_response()?))
+}
+
+/// Unsuspend a user
+#[utoipa::path(
+    put,
+    path = "/admin/users/{param_user_id}/unsuspend",
+    tag = "admin",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Unsuspend a user successfully", body = UserResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn unsuspend_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let param_user_id = get_param_user_id(req)?;
+
+    let user = get_user_service(depot)?
+        .suspend_user_by_id(&param_user_id, false)
+        .await?;
+
+    Ok(Web::ok(
+        "Unsuspend user successfully",
+        user.into_response()?,
+    ))
+}
+
+/// Reset the password of a user
+///
+/// The user is logged out everywhere
+#[utoipa::path(
+    put,
+    path = "/admin/users/{param_user_id}/password",
+    tag = "admin",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    request_body = AdminResetPasswordRequest,
+    responses(
+        (status = 200, description = "Reset the password of a user successfully", body = UserResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn reset_password_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the new password from request
+    let password = extract_from_body::<AdminResetPasswordRequest>(req)
+        .await?
+        .into_password()?;
+
+    let param_user_id = get_param_user_id(req)?;
+
+    // The user is logged out everywhere, and has to log in with the new password
+    let user = get_user_service(depot)?
+        .reset_password_by_id(&param_user_id, &password)
+        .await?;
+
+    Ok(Web::ok(
+        "Reset password successfully",
+        user.into_response()?,
+    ))
+}
+
+/// Get the storage usage of a user
+#[utoipa::path(
+    get,
+    path = "/admin/users/{param_user_id}/usage",
+    tag = "admin",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Get the storage usage of a user successfully", body = UsageResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_user_usage_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let param_user_id = get_param_user_id(req)?;
+
+    let usage = get_user_service(depot)?
+        .get_usage_by_id(&param_user_id)
+        .await?;
+
+    Ok(Web::ok(
+        "Get user usage successfully",
+        usage.into_response(),
+    ))
+}
+
+/// Delete a user
+#[utoipa::path(
+    delete,
+    path = "/admin/users/{param_user_id}/delete",
+    tag = "admin",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Delete a user successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let param_user_id = get_param_user_id(req)?;
+
+    // Admins delete their own account through the normal route, with their password
+    if param_user_id == *get_cookie_user_id(depot)? {
+        return Err(Error::Permissions(
+            "You cannot force delete your own account".into(),
+        ));
+    }
+
+    get_user_service(depot)?
+        .delete_user_by_id(&param_user_id)
+        .await?;
+
+    Ok(Web::ok("Delete user successfully", ()))
+}
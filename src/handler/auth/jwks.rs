@@ -0,0 +1,22 @@
+use salvo::{handler, writer::Json, Response};
+
+use crate::{helper::jwt::get_access_jwks, response::jwks::JwksResponse, Result};
+
+/// Get the public keys of the access tokens
+///
+/// Not wrapped in the envelope, so the usual JWKS clients can read it
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Get the public keys of the access tokens successfully", body = JwksResponse),
+    )
+)]
+#[handler]
+pub async fn get_jwks_handler(res: &mut Response) -> Result<()> {
+    let keys = get_access_jwks()?;
+
+    res.render(Json(JwksResponse { keys }));
+    Ok(())
+}
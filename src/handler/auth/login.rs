@@ -5,16 +5,31 @@ use salvo::{
 };
 
 use crate::{
+    base::user::User,
     helper::{
         body::extract_from_body,
-        depot::get_user_service,
-        jwt::{encode_jwt, JwtType},
+        depot::{get_login_throttle, get_user_service},
+        jwt::{encode_jwt, get_lifetime, JwtType},
     },
     request::user::login::LoginRequest,
+    response::two_factor::TwoFactorChallengeResponse,
+    service::user_service::UserService,
     web::Web,
-    WebResult,
+    Result, WebResult,
 };
 
+/// Log in
+///
+/// Sets the accessToken and refreshToken cookies, or returns a challenge token when two-factor authentication is on
+#[utoipa::path(
+    post,
+    path = "/user/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Log in successfully", body = UserResponse),
+    )
+)]
 #[handler]
 pub async fn login_handler(req: &mut Request, depot: &mut Depot, res: &mut Response) -> WebResult {
     // Extract the login request and validate
@@ -25,23 +40,58 @@ pub async fn login_handler(req: &mut Request, depot: &mut Depot, res: &mut Respo
     // Get the user_db
     let user_service = get_user_service(depot)?;
 
+    // After too many failures, the account is locked out for a while, even with the right password
+    let login_throttle = get_login_throttle(depot)?;
+    login_throttle.check(&user_req.username)?;
+
     // Find the user
-    let cookie_user = user_service
+    let cookie_user = match user_service
         .get_user_by_login_info(&user_req.username, &user_req.password)
-        .await?;
+        .await
+    {
+        Ok(cookie_user) => cookie_user,
+        Err(e) => {
+            login_throttle.record_failure(&user_req.username)?;
+            return Err(e);
+        }
+    };
+    login_throttle.reset(&user_req.username)?;
+
+    // A locked user cannot log in until the suspension is lifted or the deletion is cancelled
+    cookie_user.check_active()?;
+
+    // With two-factor authentication, the cookies are only given after the second step
+    if cookie_user.has_two_factor() {
+        let challenge_token = encode_jwt(&cookie_user, JwtType::Challenge)?;
+        return Ok(Web::ok(
+            "Two-factor authentication is required",
+            TwoFactorChallengeResponse { challenge_token },
+        ));
+    }
 
+    start_session(user_service, &cookie_user, res).await?;
+
+    Ok(Web::ok("Login successfully", cookie_user.into_response()?))
+}
+
+// Gives the tokens to the user, the last step of every kind of login
+pub async fn start_session(
+    user_service: &UserService,
+    cookie_user: &User,
+    res: &mut Response,
+) -> Result<()> {
     // Get the id from the cookie user
     let cookie_user_id = cookie_user.id;
 
     // Create the access token and the refresh token
-    let access_jwt = encode_jwt(&cookie_user, JwtType::Access)?;
-    let refresh_jwt = encode_jwt(&cookie_user, JwtType::Refresh)?;
+    let access_jwt = encode_jwt(cookie_user, JwtType::Access)?;
+    let refresh_jwt = encode_jwt(cookie_user, JwtType::Refresh)?;
 
     // Get the cookie storage in response object, and put the tokens there
     res.cookies_mut().add(
         Cookie::build("accessToken", access_jwt.clone())
             .path("/")
-            .max_age(Duration::minutes(30))
+            .max_age(Duration::seconds(get_lifetime(JwtType::Access)?))
             .http_only(true)
             .same_site(SameSite::None)
             .finish(),
@@ -50,7 +100,7 @@ pub async fn login_handler(req: &mut Request, depot: &mut Depot, res: &mut Respo
     res.cookies_mut().add(
         Cookie::build("refreshToken", refresh_jwt.clone())
             .path("/")
-            .max_age(Duration::hours(2))
+            .max_age(Duration::seconds(get_lifetime(JwtType::Refresh)?))
             .http_only(true)
             .same_site(SameSite::None)
             .finish(),
@@ -59,7 +109,5 @@ pub async fn login_handler(req: &mut Request, depot: &mut Depot, res: &mut Respo
     // Update the refresh token field in the user
     user_service
         .update_refresh_token(&cookie_user_id, &refresh_jwt)
-        .await?;
-
-    Ok(Web::ok("Login successfully", cookie_user.into_response()?))
+        .await
 }
@@ -6,11 +6,25 @@ use salvo::{
 
 use crate::{
     error::Error,
-    helper::{cookie::get_cookie_user_id, depot::get_user_service},
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::get_user_service,
+        jwt::{get_lifetime, JwtType},
+    },
     web::Web,
     WebResult,
 };
 
+/// Log out
+#[utoipa::path(
+    delete,
+    path = "/user/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Log out successfully"),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn logout_handler(req: &mut Request, depot: &Depot, res: &mut Response) -> WebResult {
     // Get the cookie user id send from the check login middleware
@@ -18,13 +32,13 @@ pub async fn logout_handler(req: &mut Request, depot: &Depot, res: &mut Response
 
     let access_jwt = req
         .cookie("accessToken")
-        .ok_or_else(|| Error::Permissions("You are not logged in to logout".to_string()))?
+        .ok_or_else(|| Error::Unauthorized("You are not logged in to logout".to_string()))?
         .value()
         .to_string();
 
     let refresh_jwt = req
         .cookie("refreshToken")
-        .ok_or_else(|| Error::Permissions("You are not logged in to logout".to_string()))?
+        .ok_or_else(|| Error::Unauthorized("You are not logged in to logout".to_string()))?
         .value()
         .to_string();
 
@@ -40,7 +54,7 @@ pub async fn logout_handler(req: &mut Request, depot: &Depot, res: &mut Response
     res.cookies_mut().remove(
         Cookie::build("accessToken", access_jwt)
             .path("/")
-            .max_age(Duration::minutes(30))
+            .max_age(Duration::seconds(get_lifetime(JwtType::Access)?))
             .http_only(true)
             .same_site(SameSite::None)
             .finish(),
@@ -48,7 +62,7 @@ pub async fn logout_handler(req: &mut Request, depot: &Depot, res: &mut Response
     res.cookies_mut().remove(
         Cookie::build("refreshToken", refresh_jwt)
             .path("/")
-            .max_age(Duration::hours(2))
+            .max_age(Duration::seconds(get_lifetime(JwtType::Refresh)?))
             .http_only(true)
             .same_site(SameSite::None)
             .finish(),
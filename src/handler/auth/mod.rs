@@ -1,3 +1,6 @@
+pub mod jwks;
 pub mod login;
 pub mod logout;
+pub mod oidc;
 pub mod refresh;
+pub mod two_factor;
@@ -0,0 +1,125 @@
+use salvo::{handler, prelude::StatusCode, writer::Redirect, Depot, Request, Response};
+
+use crate::{
+    error::Error,
+    handler::auth::login::start_session,
+    helper::{
+        depot::{get_oidc_service, get_user_service},
+        into_string,
+        jwt::{encode_jwt, JwtType},
+        param::extract_from_param,
+    },
+    response::two_factor::TwoFactorChallengeResponse,
+    web::Web,
+    Result, WebResult,
+};
+
+/// List the identity providers
+#[utoipa::path(
+    get,
+    path = "/auth/oidc",
+    tag = "auth",
+    responses(
+        (status = 200, description = "List the identity providers successfully", body = [String]),
+    )
+)]
+#[handler]
+pub async fn get_oidc_providers_handler(depot: &mut Depot) -> WebResult {
+    let providers = get_oidc_service(depot)?.get_provider_names();
+
+    Ok(Web::ok("Get identity providers successfully", providers))
+}
+
+/// Start the single sign-on
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/{oidc_provider}/login",
+    tag = "auth",
+    params(
+        ("oidc_provider" = String, Path, description = "The name of the identity provider"),
+    ),
+    responses(
+        (status = 302, description = "Redirects to the identity provider"),
+    )
+)]
+#[handler]
+pub async fn oidc_login_handler(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> Result<()> {
+    let provider: String = extract_from_param(req, "oidc_provider")?;
+
+    // The user logs in at the provider, which then sends them to the callback
+    let url = get_oidc_service(depot)?.begin_login(&provider).await?;
+
+    res.render(Redirect::with_status_code(StatusCode::FOUND, url).map_err(into_string)?);
+    Ok(())
+}
+
+/// Finish the single sign-on
+///
+/// The identity provider redirects back here
+///
+/// Sets the accessToken and refreshToken cookies, or returns a challenge token when two-factor authentication is on
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/{oidc_provider}/callback",
+    tag = "auth",
+    params(
+        ("oidc_provider" = String, Path, description = "The name of the identity provider"),
+        ("code" = Option<String>, Query, description = "The authorization code"),
+        ("state" = Option<String>, Query, description = "The state from the login"),
+        ("error" = Option<String>, Query, description = "Set when the identity provider refused the login"),
+    ),
+    responses(
+        (status = 200, description = "Finish the single sign-on successfully", body = UserResponse),
+    )
+)]
+#[handler]
+pub async fn oidc_callback_handler(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> WebResult {
+    let provider: String = extract_from_param(req, "oidc_provider")?;
+
+    // The provider sends an error instead of a code when the user cancels the login
+    if let Some(error) = req.query::<String>("error") {
+        return Err(Error::Unauthorized(format!(
+            "The identity provider refused the login: {error}"
+        )));
+    }
+
+    let code = req
+        .query::<String>("code")
+        .ok_or("Cannot get the code from the query")?;
+    let state = req
+        .query::<String>("state")
+        .ok_or("Cannot get the state from the query")?;
+
+    let claims = get_oidc_service(depot)?
+        .finish_login(&provider, &code, &state)
+        .await?;
+
+    let user_service = get_user_service(depot)?;
+    let cookie_user = user_service
+        .get_or_create_sso_user(&provider, &claims)
+        .await?;
+
+    cookie_user.check_active()?;
+
+    // The account could have been linked by its email, the provider does not know about the second factor,
+    // so it is asked for the same way as after a password
+    if cookie_user.has_two_factor() {
+        let challenge_token = encode_jwt(&cookie_user, JwtType::Challenge)?;
+        return Ok(Web::ok(
+            "Two-factor authentication is required",
+            TwoFactorChallengeResponse { challenge_token },
+        ));
+    }
+
+    start_session(user_service, &cookie_user, res).await?;
+
+    Ok(Web::ok("Login successfully", cookie_user.into_response()?))
+}
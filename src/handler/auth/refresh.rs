@@ -8,18 +8,30 @@ use crate::{
     error::Error,
     helper::{
         depot::get_user_service,
-        jwt::{decode_jwt, encode_jwt, JwtType},
+        jwt::{decode_jwt, encode_jwt, get_lifetime, JwtType},
     },
     web::Web,
     WebResult,
 };
 
+/// Refresh the access token
+///
+/// Needs the refreshToken cookie
+#[utoipa::path(
+    post,
+    path = "/user/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Refresh the access token successfully"),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn refresh_handler(req: &mut Request, depot: &Depot, res: &mut Response) -> WebResult {
     // Get the refresh token from the cookie, there are two cases might happen
-    let refresh_token = req
-        .cookie("refreshToken")
-        .ok_or_else(|| Error::Permissions("You haven't logged in to perform this action".into()))?;
+    let refresh_token = req.cookie("refreshToken").ok_or_else(|| {
+        Error::Unauthorized("You haven't logged in to perform this action".into())
+    })?;
 
     // If there IS a cookie named refreshToken
 
@@ -39,7 +51,7 @@ pub async fn refresh_handler(req: &mut Request, depot: &Depot, res: &mut Respons
     res.cookies_mut().remove(
         Cookie::build("accessToken", access_jwt.clone())
             .path("/")
-            .max_age(Duration::minutes(30))
+            .max_age(Duration::seconds(get_lifetime(JwtType::Access)?))
             .http_only(true)
             .same_site(SameSite::None)
             .finish(),
@@ -49,7 +61,7 @@ pub async fn refresh_handler(req: &mut Request, depot: &Depot, res: &mut Respons
     res.add_cookie(
         Cookie::build("accessToken", access_jwt)
             .path("/")
-            .max_age(Duration::minutes(30))
+            .max_age(Duration::seconds(get_lifetime(JwtType::Access)?))
             .http_only(true)
             .same_site(SameSite::None)
             .finish(),
@@ -0,0 +1,65 @@
+use salvo::{handler, Depot, Request, Response};
+
+use crate::{
+    error::Error,
+    handler::auth::login::start_session,
+    helper::{
+        body::extract_from_body,
+        depot::{get_login_throttle, get_user_service},
+        jwt::{decode_jwt, JwtType},
+    },
+    request::user::two_factor::TwoFactorLoginRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Finish the login with a two-factor code
+#[utoipa::path(
+    post,
+    path = "/user/login/2fa",
+    tag = "auth",
+    request_body = TwoFactorLoginRequest,
+    responses(
+        (status = 200, description = "Finish the login with a two-factor code successfully", body = UserResponse),
+    )
+)]
+#[handler]
+pub async fn two_factor_login_handler(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+) -> WebResult {
+    let user_req = extract_from_body::<TwoFactorLoginRequest>(req)
+        .await?
+        .validate_self()?;
+
+    // The challenge token proves that the password was right a few minutes ago
+    let user_id = decode_jwt(user_req.challenge_token, JwtType::Challenge)
+        .map_err(|_| Error::Unauthorized("The login has expired, please login again".into()))?;
+
+    let user_service = get_user_service(depot)?;
+
+    // The codes are short, so the failures are throttled apart from the password ones
+    let login_throttle = get_login_throttle(depot)?;
+    let account = format!("2fa:{user_id}");
+    login_throttle.check(&account)?;
+
+    let cookie_user = match user_service
+        .verify_two_factor(&user_id, &user_req.code)
+        .await
+    {
+        Ok(cookie_user) => cookie_user,
+        Err(e) => {
+            login_throttle.record_failure(&account)?;
+            return Err(e);
+        }
+    };
+    login_throttle.reset(&account)?;
+
+    // The account could have been locked in between the two steps
+    cookie_user.check_active()?;
+
+    start_session(user_service, &cookie_user, res).await?;
+
+    Ok(Web::ok("Login successfully", cookie_user.into_response()?))
+}
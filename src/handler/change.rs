@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+use mongodb::bson::oid::ObjectId;
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::{get_change_service, get_team_service},
+    },
+    response::change::ChangesResponse,
+    web::Web,
+    WebResult,
+};
+
+// The default and the maximum amount of changes returned at once
+const DEFAULT_LIMIT: i64 = 500;
+const MAX_LIMIT: i64 = 1000;
+
+/// List the changes since a cursor
+///
+/// For the sync clients. Without a cursor, only the current cursor is returned:
+/// take it first, list the tree, then follow the changes from it.
+/// The changes are in order, a deleted change is a tombstone for a file or folder that is gone,
+/// and a moved change has the previous fullpath. Everything inside of a moved or deleted folder
+/// gets its own change as well. The changes of the last 5 seconds are held back until they settle.
+/// That delay is a guess, not a guarantee: a change whose write to the database takes longer
+/// than it can be missed. A client that has to be sure lists the tree again from time to time.
+#[utoipa::path(
+    get,
+    path = "/changes",
+    tag = "changes",
+    params(
+        ("cursor" = Option<String>, Query, description = "The cursor from the previous response"),
+        ("team" = Option<String>, Query, description = "Follow the tree of this team instead of the personal tree"),
+        ("limit" = Option<i64>, Query, description = "How many changes to return, 500 by default"),
+    ),
+    responses(
+        (status = 200, description = "List the changes successfully", body = ChangesResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_changes_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let change_service = get_change_service(depot)?;
+
+    let cursor = match req.query::<String>("cursor") {
+        Some(cursor) => Some(
+            cursor
+                .parse::<i64>()
+                .ok()
+                .filter(|c| *c >= 0)
+                .ok_or("The cursor is not valid")?,
+        ),
+        None => None,
+    };
+
+    let limit = req
+        .query::<i64>("limit")
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT);
+
+    let cookie_user_id = get_cookie_user_id(depot)?;
+
+    // Only the members can follow the tree of a team
+    let team = match req.query::<String>("team") {
+        Some(team_id) => Some(
+            get_team_service(depot)?
+                .get_team_by_id_member(&ObjectId::from_str(&team_id)?, cookie_user_id)
+                .await?,
+        ),
+        None => None,
+    };
+
+    let Some(cursor) = cursor else {
+        let latest_cursor = change_service.get_latest_cursor().await?;
+        return Ok(Web::ok(
+            "Get changes successfully",
+            ChangesResponse::new(vec![], latest_cursor, false),
+        ));
+    };
+
+    let (changes, next_cursor, has_more) = match team {
+        Some(team) => {
+            change_service
+                .get_team_changes(&team.id, cursor, limit)
+                .await?
+        }
+        None => {
+            change_service
+                .get_user_changes(cookie_user_id, cursor, limit)
+                .await?
+        }
+    };
+
+    Ok(Web::ok(
+        "Get changes successfully",
+        ChangesResponse::new(changes, next_cursor, has_more),
+    ))
+}
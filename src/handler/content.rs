@@ -9,11 +9,26 @@ use salvo::{fs::NamedFile, handler, Depot, Request, Response};
 use crate::{
     helper::{
         depot::{get_param_file, get_storage},
+        escape::content_disposition,
+        into_string,
         param::get_param_version_number,
     },
     Result,
 };
 
+/// Download a file
+#[utoipa::path(
+    get,
+    path = "/content/{param_file_id}",
+    tag = "content",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    responses(
+        (status = 200, description = "The content of the file", content_type = "application/octet-stream"),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_content_handler(
     req: &mut Request,
@@ -24,15 +39,15 @@ pub async fn get_content_handler(
 
     let storage = get_storage(depot)?;
 
-    let mut data = storage
-        .get_data_by_key(&format!(
-            "{}.{}",
-            param_file.id,
-            param_file.extension_to_str()
-        ))
-        .await?;
+    let mut data = storage.get_data_by_key(&param_file.internal_path()).await?;
 
-    let local_file_path = &format!("downloads/{}", param_file.full_filename);
+    // The local copy is named after the id, the real name only goes into the header
+    let local_file_path = &format!("downloads/{}", param_file.id);
+
+    let mime_type = param_file
+        .mime_type
+        .parse()
+        .unwrap_or(mime_guess::mime::APPLICATION_OCTET_STREAM);
 
     let file = File::create(local_file_path)?;
     let mut buf_writer = BufWriter::new(file);
@@ -41,16 +56,33 @@ pub async fn get_content_handler(
     }
     buf_writer.flush()?;
 
-    NamedFile::builder(local_file_path)
-        .attached_name(&param_file.full_filename)
-        .send(req.headers(), res)
-        .await;
+    let mut named_file = NamedFile::builder(local_file_path)
+        .content_type(mime_type)
+        .build()
+        .await
+        .map_err(into_string)?;
+    named_file.set_content_disposition(content_disposition(&param_file.full_filename)?);
+    named_file.send(req.headers(), res).await;
 
     remove_file(local_file_path)?;
 
     Ok(())
 }
 
+/// Download a version of a file
+#[utoipa::path(
+    get,
+    path = "/content/{param_file_id}/versions/{version_number}",
+    tag = "content",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+        ("version_number" = i64, Path, description = "The number of the version"),
+    ),
+    responses(
+        (status = 200, description = "The content of the version", content_type = "application/octet-stream"),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_content_with_version_handler(
     req: &mut Request,
@@ -64,15 +96,15 @@ pub async fn get_content_with_version_handler(
     let version_number = get_param_version_number(req)?;
 
     let mut data = storage
-        .get_data_by_key(&format!(
-            "{}/{}.{}",
-            param_file.id,
-            version_number,
-            param_file.extension_to_str()
-        ))
+        .get_data_by_key(&param_file.internal_version_path(version_number))
         .await?;
 
-    let local_file_path = &format!("downloads/{}", param_file.full_filename);
+    let local_file_path = &format!("downloads/{}-{version_number}", param_file.id);
+
+    let mime_type = param_file
+        .mime_type
+        .parse()
+        .unwrap_or(mime_guess::mime::APPLICATION_OCTET_STREAM);
 
     let file = File::create(local_file_path)?;
     let mut buf_writer = BufWriter::new(file);
@@ -81,10 +113,13 @@ pub async fn get_content_with_version_handler(
     }
     buf_writer.flush()?;
 
-    NamedFile::builder(local_file_path)
-        .attached_name(&param_file.full_filename)
-        .send(req.headers(), res)
-        .await;
+    let mut named_file = NamedFile::builder(local_file_path)
+        .content_type(mime_type)
+        .build()
+        .await
+        .map_err(into_string)?;
+    named_file.set_content_disposition(content_disposition(&param_file.full_filename)?);
+    named_file.send(req.headers(), res).await;
 
     remove_file(local_file_path)?;
 
@@ -0,0 +1,53 @@
+use salvo::{
+    handler,
+    writer::{Json, Text},
+    Response,
+};
+use utoipa::OpenApi;
+
+use crate::openapi::ApiDoc;
+
+// The page loads Swagger UI from a CDN, so nothing has to be bundled into the binary
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>API docs</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+        window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+    </script>
+</body>
+</html>"##;
+
+/// Get the OpenAPI specification
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "docs",
+    responses(
+        (status = 200, description = "The OpenAPI specification, not wrapped in the envelope", body = Object),
+    )
+)]
+#[handler]
+pub async fn get_openapi_handler(res: &mut Response) {
+    res.render(Json(ApiDoc::openapi()));
+}
+
+/// Browse the API docs
+#[utoipa::path(
+    get,
+    path = "/docs",
+    tag = "docs",
+    responses(
+        (status = 200, description = "The Swagger UI page", content_type = "text/html"),
+    )
+)]
+#[handler]
+pub async fn get_docs_handler(res: &mut Response) {
+    res.render(Text::Html(SWAGGER_UI));
+}
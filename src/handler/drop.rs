@@ -0,0 +1,150 @@
+use mongodb::bson::oid::ObjectId;
+use salvo::{handler, Depot, Request};
+use tokio::{fs::File as LocalFile, io::AsyncReadExt};
+
+use crate::{
+    base::file::{numbered_full_filename, File, Visibility},
+    helper::{
+        depot::{
+            get_file_request_service, get_file_service, get_param_file_request,
+            get_param_file_request_folder, get_team_service, get_user_service,
+        },
+        file::get_file_from_req,
+        form::extract_from_form,
+    },
+    request::file_request::upload::DropFileRequest,
+    response::file_request::{DropInfoResponse, DropUploadResponse},
+    web::Web,
+    WebResult,
+};
+
+// How many numbered names are tried for an upload whose name is taken
+const MAX_NAME_NUMBER: usize = 1000;
+
+/// Get a file request
+#[utoipa::path(
+    get,
+    path = "/drop/{drop_token}",
+    tag = "drop",
+    params(
+        ("drop_token" = String, Path, description = "The token of the file request"),
+    ),
+    responses(
+        (status = 200, description = "Get a file request successfully", body = DropInfoResponse),
+    )
+)]
+#[handler]
+pub async fn get_drop_handler(depot: &mut Depot) -> WebResult {
+    // Only the limits are shown, never what is inside the folder
+    Ok(Web::ok(
+        "Get file request successfully",
+        DropInfoResponse::new(
+            get_param_file_request(depot)?,
+            get_param_file_request_folder(depot)?,
+        ),
+    ))
+}
+
+/// Upload a file to a file request
+///
+/// The file goes in the file field
+#[utoipa::path(
+    post,
+    path = "/drop/{drop_token}",
+    tag = "drop",
+    params(
+        ("drop_token" = String, Path, description = "The token of the file request"),
+    ),
+    request_body(content = DropFileRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Upload a file to a file request successfully", body = DropUploadResponse),
+    )
+)]
+#[handler]
+pub async fn upload_drop_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the uploader from the form
+    let uploader = extract_from_form::<DropFileRequest>(req)
+        .await?
+        .into_uploader()?;
+
+    let param_file_request = get_param_file_request(depot)?;
+    let param_file_request_folder = get_param_file_request_folder(depot)?;
+
+    // Get the attachment file
+    let file = get_file_from_req(req).await?;
+
+    // Open the received file from temporary path
+    let mut local_file = LocalFile::open(file.path()).await?;
+
+    // Read the file's byte stream and store it inside file_stream
+    let mut file_stream = vec![];
+    local_file.read_to_end(&mut file_stream).await?;
+
+    let full_filename = file
+        .name()
+        .ok_or("The attached file does not have a name")?;
+
+    // The uploaded file belongs to whoever owns the folder, and stays private
+    let owner = get_user_service(depot)?
+        .get_user_by_id(&param_file_request_folder.owner)
+        .await?;
+    let team = match &param_file_request_folder.team {
+        Some(team_id) => Some(get_team_service(depot)?.get_team_by_id(team_id).await?),
+        None => None,
+    };
+
+    // File::new puts the root folder in front of the position by itself
+    let (_, position) = param_file_request_folder
+        .fullpath
+        .split_once('/')
+        .ok_or("Cannot get the position of the folder")?;
+
+    // The visitors cannot see what is in the folder, so a name that is taken gets a number
+    // instead of failing, which would also tell them that the name exists
+    let file_service = get_file_service(depot)?;
+    let mut number = 1;
+    let mut file_model = loop {
+        let file_model = File::new(
+            ObjectId::new(),
+            &owner,
+            team.as_ref(),
+            &numbered_full_filename(full_filename, number),
+            Visibility::Private,
+            position,
+            None,
+        )?;
+        if !file_service
+            .exists_file_by_fullpath(&file_model.fullpath)
+            .await?
+        {
+            break file_model;
+        }
+        if number == MAX_NAME_NUMBER {
+            return Err("Cannot find a free name for the file, please rename it".into());
+        }
+        number += 1;
+    };
+    file_model.uploaded_by = Some(uploader);
+
+    // Count the upload against the limits before creating the file,
+    // and give it back if the file cannot be created
+    let file_request_service = get_file_request_service(depot)?;
+    let reserved_file_request = file_request_service
+        .reserve_upload(param_file_request, file_stream.len())
+        .await?;
+
+    let created_file = match file_service.create_file(file_model, file_stream).await {
+        Ok(created_file) => created_file,
+        Err(e) => {
+            file_request_service
+                .release_upload(param_file_request)
+                .await?;
+            return Err(e);
+        }
+    };
+
+    Ok(Web::ok(
+        "Upload file successfully",
+        DropUploadResponse::new(full_filename, &created_file, &reserved_file_request),
+    ))
+}
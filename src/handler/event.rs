@@ -0,0 +1,78 @@
+use std::convert::Infallible;
+
+use futures::stream;
+use salvo::{
+    handler,
+    sse::{SseEvent, SseKeepAlive},
+    Depot, Response,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::{get_acl_service, get_event_bus},
+        into_string,
+    },
+    Result,
+};
+
+/// Stream the changes to the files and folders
+///
+/// A Server-Sent Events stream, each event is named like file.created or folder.moved.
+/// A resync event means that some events were missed, and everything should be fetched again.
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "events",
+    responses(
+        (status = 200, description = "The stream of events", body = EventResponse, content_type = "text/event-stream"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn events_handler(depot: &mut Depot, res: &mut Response) -> Result<()> {
+    let cookie_user_id = *get_cookie_user_id(depot)?;
+    let acl_service = get_acl_service(depot)?.clone();
+    let receiver = get_event_bus(depot)?.subscribe();
+
+    // Every client checks the events on its own, and skips the ones about things it cannot see
+    let events = stream::unfold(receiver, move |mut receiver| {
+        let acl_service = acl_service.clone();
+        async move {
+            loop {
+                let event = match receiver.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(missed)) => {
+                        let resync = SseEvent::default().name("resync").data(missed.to_string());
+                        return Some((Ok::<_, Infallible>(resync), receiver));
+                    }
+                    Err(RecvError::Closed) => return None,
+                };
+
+                // A failed check is treated like no access, the stream itself keeps going
+                if !acl_service
+                    .can_see_event(&cookie_user_id, &event)
+                    .await
+                    .unwrap_or(false)
+                {
+                    continue;
+                }
+
+                let name = event.name();
+                let Ok(sse_event) = SseEvent::default()
+                    .name(name)
+                    .json_data(event.into_response())
+                else {
+                    continue;
+                };
+                return Some((Ok(sse_event), receiver));
+            }
+        }
+    });
+
+    SseKeepAlive::new(events)
+        .streaming(res)
+        .map_err(into_string)?;
+    Ok(())
+}
@@ -1,9 +1,14 @@
+use std::str::FromStr;
+
+use mongodb::bson::oid::ObjectId;
 use salvo::{handler, Depot, Request};
 use tokio::{fs::File, io::AsyncReadExt};
 
 use crate::{
     helper::{
-        cookie::get_cookie_user, depot::get_file_service, file::get_file_from_req,
+        cookie::get_cookie_user,
+        depot::{get_file_service, get_folder_service, get_team_service},
+        file::get_file_from_req,
         form::extract_from_form,
     },
     request::file::create::CreateFileRequest,
@@ -12,6 +17,19 @@ use crate::{
     WebResult,
 };
 
+/// Upload a file
+///
+/// The file goes in the file field
+#[utoipa::path(
+    post,
+    path = "/file/create",
+    tag = "file",
+    request_body(content = CreateFileRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Upload a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn create_file_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Extract the data from request
@@ -39,8 +57,28 @@ pub async fn create_file_handler(req: &mut Request, depot: &mut Depot) -> WebRes
     // Find the user
     let cookie_user = get_cookie_user(depot)?;
 
+    // Every member of the team can create files in the folders of the team
+    let team = match &file_req.team {
+        Some(team_id) => Some(
+            get_team_service(depot)?
+                .get_team_by_id_member(&ObjectId::from_str(team_id)?, &cookie_user.id)
+                .await?,
+        ),
+        None => None,
+    };
+
+    let default_visibility = file_req.visibility.is_none();
+
     // Construct the file model from request
-    let file_model = file_req.into_file(cookie_user, full_filename)?;
+    let mut file_model = file_req.into_file(cookie_user, team.as_ref(), full_filename)?;
+
+    // Without a visibility, the file gets the default of the folder it is created in
+    if default_visibility {
+        file_model.visibility = get_folder_service(depot)?
+            .get_child_visibility(&file_model.position)
+            .await?
+            .into();
+    }
 
     // Send the file_model and the file_stream to the database to create a new file model
     // with the file stream send straight to S3
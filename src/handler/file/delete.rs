@@ -4,12 +4,25 @@ use crate::{
     error::Error,
     helper::{
         cookie::get_cookie_user_id,
-        depot::{get_file_service, get_param_file},
+        depot::{get_file_service, get_param_access, get_param_file},
     },
     web::Web,
     WebResult,
 };
 
+/// Delete a file
+#[utoipa::path(
+    delete,
+    path = "/file/delete/{param_file_id}",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    responses(
+        (status = 200, description = "Delete a file successfully"),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn delete_file_handler(depot: &Depot) -> WebResult {
     // Get the logged in user
@@ -21,7 +34,8 @@ pub async fn delete_file_handler(depot: &Depot) -> WebResult {
     // Get the file from the storage
     let param_file = get_param_file(depot)?;
 
-    if *cookie_user_id != param_file.owner {
+    // The owner and the editors can delete the file
+    if !get_param_access(depot)?.can_edit() {
         return Err(Error::Permissions(
             "You cannot delete other user's file".into(),
         ));
@@ -1,22 +1,46 @@
 use std::{collections::HashMap, str::FromStr};
 
+use futures::try_join;
 use mongodb::bson::oid::ObjectId;
 use salvo::{handler, Depot, Request};
 
 use crate::{
     helper::{
         cookie::get_cookie_user_id_option,
-        depot::{get_file_service, get_file_version_service, get_param_file, get_user_service},
+        depot::{
+            get_acl_service, get_file_service, get_file_version_service, get_param_file,
+            get_user_service,
+        },
+        position::normalize_path,
     },
     response::FinalFileResponse,
     web::Web,
     WebResult,
 };
 
+/// List the files
+#[utoipa::path(
+    get,
+    path = "/file",
+    tag = "file",
+    params(
+        ("owner" = Option<String>, Query, description = "Only the files of this user"),
+        ("team" = Option<String>, Query, description = "Only the files of this team"),
+        ("position" = Option<String>, Query, description = "Only the files inside this folder, like /docs"),
+        ("filename" = Option<String>, Query, description = "Only the files with this name"),
+        ("fullpath" = Option<String>, Query, description = "Only the file at this full path"),
+        ("visibility" = Option<String>, Query, description = "public, private or inherit"),
+        ("tags" = Option<String>, Query, description = "Only the files having all of these comma separated tags, metadata.<key>=<value> filters by the metadata"),
+    ),
+    responses(
+        (status = 200, description = "List the files successfully", body = [FinalFileResponse]),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_files_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Get the query data
-    let mut queries = req
+    let queries = req
         .queries()
         .iter()
         .map(|i| (i.0.clone(), i.1.clone()))
@@ -27,37 +51,70 @@ pub async fn get_files_handler(req: &mut Request, depot: &mut Depot) -> WebResul
     let file_service = get_file_service(depot)?;
     let user_service = get_user_service(depot)?;
 
-    let files = match (cookie_user_id_option, queries.get(&"owner".to_string())) {
-        (Some(cookie_user_id), Some(query_owner)) => {
-            let owner_id = ObjectId::from_str(query_owner)?;
-            if *cookie_user_id == owner_id {
-                file_service.get_files_by_map(&queries)
-            } else {
-                queries.insert("visibility".to_string(), "public".to_string());
-                file_service.get_files_by_map(&queries)
-            }
+    // The members of a team and the users that a folder is shared with
+    // can list the private files inside of it
+    let dir_access = match (cookie_user_id_option, queries.get("position")) {
+        (Some(cookie_user_id), Some(position)) => {
+            get_acl_service(depot)?
+                .get_access_by_dir(cookie_user_id, &normalize_path(position))
+                .await?
         }
-        _ => {
-            queries.insert("visibility".to_string(), "public".to_string());
-            file_service.get_files_by_map(&queries)
+        _ => None,
+    };
+
+    let public_only = match (cookie_user_id_option, queries.get("owner")) {
+        (Some(cookie_user_id), Some(query_owner)) => {
+            *cookie_user_id != ObjectId::from_str(query_owner)? && dir_access.is_none()
         }
+        _ => dir_access.is_none(),
     };
 
-    let files = files.await?;
+    let files = file_service.get_files_by_map(&queries).await?;
+
+    // The visibility cannot be filtered in the query,
+    // since the inherit files depend on the folders above them
+    let files = match public_only {
+        true => file_service.filter_public_files(files).await?,
+        false => files,
+    };
+
+    // Fetch the owners and the versions of every file in one go
+    // instead of querying them for each file
+    let owner_ids = files.iter().map(|f| f.owner).collect::<Vec<_>>();
+    let file_ids = files.iter().map(|f| f.id).collect::<Vec<_>>();
+
+    let (owners, mut versions) = try_join!(
+        user_service.get_users_map_by_ids(&owner_ids),
+        get_file_version_service(depot)?.get_versions_map_by_file_ids(&file_ids)
+    )?;
 
     let mut responses = vec![];
 
     for file in files {
-        let owner = user_service.get_user_by_id(&file.owner).await?;
-        let versions = get_file_version_service(depot)?
-            .get_versions_by_file_id(&file.id)
-            .await?;
+        let owner = owners
+            .get(&file.owner)
+            .cloned()
+            .ok_or("Cannot find the owner of the file")?;
+        let versions = versions.remove(&file.id).unwrap_or_default();
         responses.push(FinalFileResponse::new(file, owner, versions)?)
     }
 
     Ok(Web::ok("Get files successfully", responses))
 }
 
+/// Get a file
+#[utoipa::path(
+    get,
+    path = "/file/{param_file_id}",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    responses(
+        (status = 200, description = "Get a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_file_by_id_handler(depot: &mut Depot) -> WebResult {
     // Get the param file
@@ -2,4 +2,6 @@ pub mod create;
 pub mod delete;
 pub mod get;
 pub mod restore;
+pub mod share;
+pub mod tag;
 pub mod update;
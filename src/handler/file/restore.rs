@@ -1,9 +1,13 @@
 use salvo::{handler, Depot, Request};
 
 use crate::{
+    error::Error,
     helper::{
         cookie::get_cookie_user_id,
-        depot::{get_file_service, get_file_version_service, get_param_file, get_user_service},
+        depot::{
+            get_file_service, get_file_version_service, get_param_access, get_param_file,
+            get_user_service,
+        },
         param::get_param_version_number,
     },
     response::FinalFileResponse,
@@ -11,12 +15,33 @@ use crate::{
     WebResult,
 };
 
+/// Restore a version of a file
+#[utoipa::path(
+    put,
+    path = "/file/{param_file_id}/versions/restore/{version_number}",
+    tag = "version",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+        ("version_number" = i64, Path, description = "The number of the version"),
+    ),
+    responses(
+        (status = 200, description = "Restore a version of a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn restore_file_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Check if the user is logged in or not
     let cookie_user_id = get_cookie_user_id(depot)?;
 
-    let param_file_id = get_param_file(depot)?.id;
+    let param_file = get_param_file(depot)?;
+
+    // The owner and the editors can restore the file
+    if !get_param_access(depot)?.can_edit() {
+        return Err(Error::Permissions(
+            "You cannot restore other user's file".into(),
+        ));
+    }
 
     let version_number = get_param_version_number(req)?;
 
@@ -24,7 +49,7 @@ pub async fn restore_file_handler(req: &mut Request, depot: &mut Depot) -> WebRe
     let file_service = get_file_service(depot)?;
 
     let file = file_service
-        .restore_file_from_version(&param_file_id, cookie_user_id, version_number)
+        .restore_file_from_version(&param_file.id, &param_file.owner, version_number)
         .await?;
     let file_id = file.id;
 
@@ -33,7 +58,7 @@ pub async fn restore_file_handler(req: &mut Request, depot: &mut Depot) -> WebRe
         FinalFileResponse::new(
             file,
             get_user_service(depot)?
-                .get_user_by_id(cookie_user_id)
+                .get_user_by_id(&param_file.owner)
                 .await?,
             get_file_version_service(depot)?
                 .get_versions_by_file_id(&file_id)
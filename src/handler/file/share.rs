@@ -0,0 +1,127 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::file::File,
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        depot::{get_acl_service, get_param_access, get_param_file, get_user_service},
+        param::get_param_user_id,
+    },
+    request::share::create::ShareRequest,
+    response::share::ShareResponse,
+    web::Web,
+    Result, WebResult,
+};
+
+// Only the owner can decide who the file is shared with
+fn check_owner(depot: &Depot) -> Result<&File> {
+    if !get_param_access(depot)?.is_owner() {
+        return Err(Error::Permissions(
+            "You cannot share other user's file".into(),
+        ));
+    }
+    get_param_file(depot)
+}
+
+/// Share a file
+#[utoipa::path(
+    post,
+    path = "/file/{param_file_id}/share",
+    tag = "share",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    request_body = ShareRequest,
+    responses(
+        (status = 200, description = "Share a file successfully", body = ShareResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn share_file_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the user and the role from the request
+    let (username, role) = extract_from_body::<ShareRequest>(req).await?.into_share()?;
+
+    let param_file = check_owner(depot)?;
+
+    let entry = get_acl_service(depot)?
+        .share_file(param_file, &username, role)
+        .await?;
+
+    let grantee = get_user_service(depot)?
+        .get_user_by_id(&entry.grantee)
+        .await?;
+
+    Ok(Web::ok(
+        "Share file successfully",
+        ShareResponse::new(entry, grantee)?,
+    ))
+}
+
+/// List the users a file is shared with
+#[utoipa::path(
+    get,
+    path = "/file/{param_file_id}/share",
+    tag = "share",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    responses(
+        (status = 200, description = "List the users a file is shared with successfully", body = [ShareResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_file_shares_handler(depot: &mut Depot) -> WebResult {
+    let param_file = check_owner(depot)?;
+
+    let entries = get_acl_service(depot)?
+        .get_entries_by_resource(&param_file.id)
+        .await?;
+
+    // Fetch all of the users at once, instead of one query per entry
+    let grantee_ids = entries.iter().map(|e| e.grantee).collect::<Vec<_>>();
+    let grantees = get_user_service(depot)?
+        .get_users_map_by_ids(&grantee_ids)
+        .await?;
+
+    let mut responses = vec![];
+    for entry in entries {
+        let grantee = grantees
+            .get(&entry.grantee)
+            .cloned()
+            .ok_or("Cannot find the user that the file is shared with")?;
+        responses.push(ShareResponse::new(entry, grantee)?);
+    }
+
+    Ok(Web::ok("Get file shares successfully", responses))
+}
+
+/// Stop sharing a file
+#[utoipa::path(
+    delete,
+    path = "/file/{param_file_id}/share/delete/{param_user_id}",
+    tag = "share",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Stop sharing a file successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn unshare_file_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the user to stop sharing with
+    let param_user_id = get_param_user_id(req)?;
+
+    let param_file = check_owner(depot)?;
+
+    get_acl_service(depot)?
+        .unshare(&param_file.id, &param_user_id)
+        .await?;
+
+    Ok(Web::ok("Stop sharing file successfully", ()))
+}
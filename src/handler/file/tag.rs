@@ -0,0 +1,162 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::{file::File, user::User},
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        cookie::get_cookie_user,
+        depot::{get_file_service, get_file_version_service, get_param_file},
+        param::get_param_metadata_key,
+    },
+    request::tag::{metadata::MetadataRequest, tags::TagsRequest},
+    response::FinalFileResponse,
+    web::Web,
+    Result, WebResult,
+};
+
+// Only the owner can change the tags and the metadata of a file
+fn check_owner(depot: &Depot) -> Result<(&User, &File)> {
+    let cookie_user = get_cookie_user(depot)?;
+    let param_file = get_param_file(depot)?;
+
+    if cookie_user.id != param_file.owner {
+        return Err(Error::Permissions(
+            "You cannot change the tags or metadata of other user's file".into(),
+        ));
+    }
+    Ok((cookie_user, param_file))
+}
+
+async fn into_final_response(depot: &Depot, file: File, owner: &User) -> Result<FinalFileResponse> {
+    let versions = get_file_version_service(depot)?
+        .get_versions_by_file_id(&file.id)
+        .await?;
+    FinalFileResponse::new(file, owner.clone(), versions)
+}
+
+/// Add tags to a file
+#[utoipa::path(
+    put,
+    path = "/file/{param_file_id}/tags/add",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    request_body = TagsRequest,
+    responses(
+        (status = 200, description = "Add tags to a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn add_file_tags_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the tags from the request
+    let tags = extract_from_body::<TagsRequest>(req).await?.into_tags()?;
+
+    let (cookie_user, param_file) = check_owner(depot)?;
+
+    let file = get_file_service(depot)?
+        .add_tags_by_id(&param_file.id, &tags)
+        .await?;
+
+    Ok(Web::ok(
+        "Add tags successfully",
+        into_final_response(depot, file, cookie_user).await?,
+    ))
+}
+
+/// Remove tags from a file
+#[utoipa::path(
+    put,
+    path = "/file/{param_file_id}/tags/remove",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    request_body = TagsRequest,
+    responses(
+        (status = 200, description = "Remove tags from a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn remove_file_tags_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the tags from the request
+    let tags = extract_from_body::<TagsRequest>(req).await?.into_tags()?;
+
+    let (cookie_user, param_file) = check_owner(depot)?;
+
+    let file = get_file_service(depot)?
+        .remove_tags_by_id(&param_file.id, &tags)
+        .await?;
+
+    Ok(Web::ok(
+        "Remove tags successfully",
+        into_final_response(depot, file, cookie_user).await?,
+    ))
+}
+
+/// Update the metadata of a file
+#[utoipa::path(
+    put,
+    path = "/file/{param_file_id}/metadata/update",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    request_body = MetadataRequest,
+    responses(
+        (status = 200, description = "Update the metadata of a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn update_file_metadata_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the metadata from the request
+    let metadata = extract_from_body::<MetadataRequest>(req)
+        .await?
+        .into_metadata()?;
+
+    let (cookie_user, param_file) = check_owner(depot)?;
+
+    let file = get_file_service(depot)?
+        .update_metadata_by_id(&param_file.id, &metadata)
+        .await?;
+
+    Ok(Web::ok(
+        "Update metadata successfully",
+        into_final_response(depot, file, cookie_user).await?,
+    ))
+}
+
+/// Delete a metadata key of a file
+#[utoipa::path(
+    delete,
+    path = "/file/{param_file_id}/metadata/delete/{metadata_key}",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+        ("metadata_key" = String, Path, description = "The key of the metadata"),
+    ),
+    responses(
+        (status = 200, description = "Delete a metadata key of a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_file_metadata_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the metadata key from param
+    let key = get_param_metadata_key(req)?;
+
+    let (cookie_user, param_file) = check_owner(depot)?;
+
+    let file = get_file_service(depot)?
+        .delete_metadata_by_id(&param_file.id, &key)
+        .await?;
+
+    Ok(Web::ok(
+        "Delete metadata successfully",
+        into_final_response(depot, file, cookie_user).await?,
+    ))
+}
@@ -2,10 +2,14 @@ use salvo::{handler, Depot, Request};
 use tokio::{fs::File, io::AsyncReadExt};
 
 use crate::{
+    base::acl::Access,
     error::Error,
     helper::{
         cookie::get_cookie_user,
-        depot::{get_file_service, get_file_version_service, get_param_file},
+        depot::{
+            get_acl_service, get_file_service, get_file_version_service, get_param_access,
+            get_param_file, get_team_service, get_user_service,
+        },
         file::get_file_from_req_option,
         form::extract_from_form,
     },
@@ -15,6 +19,22 @@ use crate::{
     WebResult,
 };
 
+/// Update a file
+///
+/// A new file in the file field creates a new version
+#[utoipa::path(
+    put,
+    path = "/file/update/{param_file_id}",
+    tag = "file",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    request_body(content = UpdateFileRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Update a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn update_file_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Checks if the user has logged in or not
@@ -31,19 +51,36 @@ pub async fn update_file_handler(req: &mut Request, depot: &mut Depot) -> WebRes
     // Get the cookie_user
     let cookie_user = get_cookie_user(depot)?;
 
-    if cookie_user.id != old_file.owner {
+    // The owner and the editors can update the file
+    if !get_param_access(depot)?.can_edit() {
         return Err(Error::Permissions(
             "You cannot update other user's file".into(),
         ));
     }
 
+    // The file stays in the owner's folders, even when an editor updates it
+    let owner = match cookie_user.id == old_file.owner {
+        true => cookie_user.clone(),
+        false => {
+            get_user_service(depot)?
+                .get_user_by_id(&old_file.owner)
+                .await?
+        }
+    };
+
+    // The files of a team stay in the tree of the team
+    let team = match &old_file.team {
+        Some(team_id) => Some(get_team_service(depot)?.get_team_by_id(team_id).await?),
+        None => None,
+    };
+
     // Get the attachment file
     // Since this is optional, I have deal with 2 cases
     // 1) The user actually uploads a new file to replace the old one
     // 2) The user doesn't upload anything
     let file_option = get_file_from_req_option(req).await;
 
-    match file_option {
+    let (file_model, file_stream) = match file_option {
         // If there is a file
         Some(file) => {
             // Open the received file from temporary path
@@ -59,47 +96,48 @@ pub async fn update_file_handler(req: &mut Request, depot: &mut Depot) -> WebRes
             let full_filename = file.name();
 
             // Construct the file model from request
-            let file_model = file_req.into_file(full_filename, old_file.clone(), cookie_user)?;
-
-            // Send the file model to the database
-            let updated_file = file_service
-                .update_file_by_id(&old_file.id, file_model, file_stream)
-                .await?;
-            let updated_file_id = updated_file.id;
-
-            // Return back the created file
-            Ok(Web::ok(
-                "Update file successfully",
-                FinalFileResponse::new(
-                    updated_file,
-                    cookie_user.clone(),
-                    get_file_version_service(depot)?
-                        .get_versions_by_file_id(&updated_file_id)
-                        .await?,
-                )?,
-            ))
+            let file_model =
+                file_req.into_file(full_filename, old_file.clone(), &owner, team.as_ref())?;
+            (file_model, file_stream)
         }
         // If there is no file
-        None => {
-            // Construct a file model
-            let file_model = file_req.into_file(None, old_file.clone(), cookie_user)?;
-            // Send the information to the database
-            // Without the file ( vec![] )
-            let updated_file = file_service
-                .update_file_by_id(&old_file.id, file_model, vec![])
-                .await?;
-            let updated_file_id = updated_file.id;
-
-            Ok(Web::ok(
-                "Update file successfully",
-                FinalFileResponse::new(
-                    updated_file,
-                    cookie_user.clone(),
-                    get_file_version_service(depot)?
-                        .get_versions_by_file_id(&updated_file_id)
-                        .await?,
-                )?,
-            ))
-        }
+        // Without the file ( vec![] )
+        None => (
+            file_req.into_file(None, old_file.clone(), &owner, team.as_ref())?,
+            vec![],
+        ),
+    };
+
+    // An editor only has the file, not the folders around it,
+    // so it can only be moved into a folder that they can edit as well
+    // The same error for every other folder, whether it exists or not
+    if file_model.position != old_file.position
+        && *get_param_access(depot)? != Access::Owner
+        && !get_acl_service(depot)?
+            .get_access_by_dir(&cookie_user.id, &file_model.position)
+            .await?
+            .is_some_and(|a| a.can_edit())
+    {
+        return Err(Error::Permissions(
+            "You cannot move the file into this folder".into(),
+        ));
     }
+
+    // Send the file model to the database
+    let updated_file = file_service
+        .update_file_by_id(&old_file.id, file_model, file_stream)
+        .await?;
+    let updated_file_id = updated_file.id;
+
+    // Return back the updated file
+    Ok(Web::ok(
+        "Update file successfully",
+        FinalFileResponse::new(
+            updated_file,
+            owner,
+            get_file_version_service(depot)?
+                .get_versions_by_file_id(&updated_file_id)
+                .await?,
+        )?,
+    ))
 }
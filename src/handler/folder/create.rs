@@ -1,12 +1,30 @@
+use std::str::FromStr;
+
+use mongodb::bson::oid::ObjectId;
 use salvo::{handler, Depot, Request};
 
 use crate::{
-    helper::{body::extract_from_body, cookie::get_cookie_user, depot::get_folder_service},
+    helper::{
+        body::extract_from_body,
+        cookie::get_cookie_user,
+        depot::{get_folder_service, get_team_service},
+    },
     request::folder::create::CreateFolderRequest,
     web::Web,
     WebResult,
 };
 
+/// Create a folder
+#[utoipa::path(
+    post,
+    path = "/folder/create",
+    tag = "folder",
+    request_body = CreateFolderRequest,
+    responses(
+        (status = 200, description = "Create a folder successfully", body = FolderResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn create_folder_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Checks if the user has logged in or not
@@ -20,7 +38,26 @@ pub async fn create_folder_handler(req: &mut Request, depot: &mut Depot) -> WebR
     // Get the cookie user
     let cookie_user = get_cookie_user(depot)?;
 
-    let folder_model = folder_req.into_folder(cookie_user)?;
+    // Every member of the team can create folders in the tree of the team
+    let team = match &folder_req.team {
+        Some(team_id) => Some(
+            get_team_service(depot)?
+                .get_team_by_id_member(&ObjectId::from_str(team_id)?, &cookie_user.id)
+                .await?,
+        ),
+        None => None,
+    };
+
+    let default_visibility = folder_req.visibility.is_none();
+
+    let mut folder_model = folder_req.into_folder(cookie_user, team.as_ref())?;
+
+    // Without a visibility, the folder gets the default of the folder it is created in
+    if default_visibility {
+        folder_model.visibility = folder_service
+            .get_child_visibility(&folder_model.position)
+            .await?;
+    }
 
     let created_folder = folder_service
         .create_folder(folder_model)
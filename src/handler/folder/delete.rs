@@ -4,13 +4,28 @@ use crate::{
     error::Error,
     helper::{
         cookie::get_cookie_user_id,
-        depot::{get_folder_service, get_param_folder},
+        depot::{get_folder_service, get_param_access, get_param_folder},
         param::get_param_folder_id,
     },
     web::Web,
     WebResult,
 };
 
+/// Delete a folder
+///
+/// Everything inside of it is deleted as well
+#[utoipa::path(
+    delete,
+    path = "/folder/delete/{param_folder_id}",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    responses(
+        (status = 200, description = "Delete a folder successfully"),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn delete_folder_handler(req: &mut Request, depot: &Depot) -> WebResult {
     // First we need to get the folder from param, and then check the owner
@@ -29,8 +44,8 @@ pub async fn delete_folder_handler(req: &mut Request, depot: &Depot) -> WebResul
     // Get the folder from the storage
     let param_folder = get_param_folder(depot)?;
 
-    // If the owners doesn't match
-    if *cookie_user_id != param_folder.owner {
+    // Only the owner and the editors can delete the folder
+    if !get_param_access(depot)?.can_edit() {
         // Return error
         return Err(Error::Permissions(
             "You cannot delete other user's folder".into(),
@@ -39,7 +54,7 @@ pub async fn delete_folder_handler(req: &mut Request, depot: &Depot) -> WebResul
 
     // Else just delete the folder
     folder_service
-        .delete_folder_by_id_owner(&param_folder_id, cookie_user_id)
+        .delete_folder_by_id_owner(&param_folder_id, &param_folder.owner)
         .await?;
 
     Ok(Web::ok("Folder deleted", ()))
@@ -0,0 +1,115 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::folder::Folder,
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        cookie::get_cookie_user_id,
+        depot::{get_file_request_service, get_param_access, get_param_folder},
+        param::get_param_file_request_id,
+    },
+    request::file_request::create::CreateFileRequestRequest,
+    web::Web,
+    Result, WebResult,
+};
+
+// Only the owner can let other people upload into the folder
+fn check_owner(depot: &Depot) -> Result<&Folder> {
+    if !get_param_access(depot)?.is_owner() {
+        return Err(Error::Permissions(
+            "You cannot request files into other user's folder".into(),
+        ));
+    }
+    get_param_folder(depot)
+}
+
+/// Create a file request
+#[utoipa::path(
+    post,
+    path = "/folder/{param_folder_id}/requests",
+    tag = "drop",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = CreateFileRequestRequest,
+    responses(
+        (status = 200, description = "Create a file request successfully", body = FileRequestResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn create_file_request_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the data from request
+    let file_request_req = extract_from_body::<CreateFileRequestRequest>(req).await?;
+
+    let param_folder = check_owner(depot)?;
+
+    let file_request_model =
+        file_request_req.into_file_request(&param_folder.id, get_cookie_user_id(depot)?)?;
+
+    let created_file_request = get_file_request_service(depot)?
+        .create_request(file_request_model)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok(
+        "Create file request successfully",
+        created_file_request,
+    ))
+}
+
+/// List the file requests of a folder
+#[utoipa::path(
+    get,
+    path = "/folder/{param_folder_id}/requests",
+    tag = "drop",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    responses(
+        (status = 200, description = "List the file requests of a folder successfully", body = [FileRequestResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_file_requests_handler(depot: &mut Depot) -> WebResult {
+    let param_folder = check_owner(depot)?;
+
+    let file_requests = get_file_request_service(depot)?
+        .get_requests_by_folder(&param_folder.id)
+        .await?
+        .into_iter()
+        .flat_map(|r| r.into_response())
+        .collect::<Vec<_>>();
+
+    Ok(Web::ok("Get file requests successfully", file_requests))
+}
+
+/// Delete a file request
+#[utoipa::path(
+    delete,
+    path = "/folder/{param_folder_id}/requests/delete/{param_file_request_id}",
+    tag = "drop",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+        ("param_file_request_id" = String, Path, description = "The id of the file request"),
+    ),
+    responses(
+        (status = 200, description = "Delete a file request successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_file_request_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the file request to delete
+    let param_file_request_id = get_param_file_request_id(req)?;
+
+    let param_folder = check_owner(depot)?;
+
+    get_file_request_service(depot)?
+        .delete_request_by_id_folder(&param_file_request_id, &param_folder.id)
+        .await?;
+
+    Ok(Web::ok("File request deleted", ()))
+}
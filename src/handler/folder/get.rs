@@ -6,18 +6,38 @@ use salvo::{handler, Depot, Request};
 use crate::{
     helper::{
         cookie::get_cookie_user_id_option,
-        depot::{get_folder_service, get_param_folder, get_user_service},
+        depot::{get_acl_service, get_folder_service, get_param_folder, get_user_service},
         param::get_param_folder_id,
+        position::normalize_path,
     },
     response::FinalFolderResponse,
     web::Web,
     WebResult,
 };
 
+/// List the folders
+#[utoipa::path(
+    get,
+    path = "/folder",
+    tag = "folder",
+    params(
+        ("owner" = Option<String>, Query, description = "Only the folders of this user"),
+        ("team" = Option<String>, Query, description = "Only the folders of this team"),
+        ("position" = Option<String>, Query, description = "Only the folders inside this folder, like /docs"),
+        ("folderName" = Option<String>, Query, description = "Only the folders with this name"),
+        ("fullpath" = Option<String>, Query, description = "Only the folder at this full path"),
+        ("visibility" = Option<String>, Query, description = "public, private or inherit"),
+        ("tags" = Option<String>, Query, description = "Only the folders having all of these comma separated tags, metadata.<key>=<value> filters by the metadata"),
+    ),
+    responses(
+        (status = 200, description = "List the folders successfully", body = [FinalFolderResponse]),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_folders_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Get the query data
-    let mut queries = req
+    let queries = req
         .queries()
         .iter()
         .map(|i| (i.0.clone(), i.1.clone()))
@@ -25,33 +45,66 @@ pub async fn get_folders_handler(req: &mut Request, depot: &mut Depot) -> WebRes
 
     let cookie_user_id_option = get_cookie_user_id_option(depot);
 
-    match (cookie_user_id_option, queries.get(&"owner".to_string())) {
-        (Some(cookie_user_id), Some(query_owner)) => {
-            if *cookie_user_id != ObjectId::from_str(query_owner)? {
-                queries.insert("visibility".to_string(), "public".to_string());
-            }
+    // The members of a team and the users that a folder is shared with
+    // can list the private folders inside of it
+    let dir_access = match (cookie_user_id_option, queries.get("position")) {
+        (Some(cookie_user_id), Some(position)) => {
+            get_acl_service(depot)?
+                .get_access_by_dir(cookie_user_id, &normalize_path(position))
+                .await?
         }
-        _ => {
-            queries.insert("visibility".to_string(), "public".to_string());
+        _ => None,
+    };
+
+    let public_only = match (cookie_user_id_option, queries.get("owner")) {
+        (Some(cookie_user_id), Some(query_owner)) => {
+            *cookie_user_id != ObjectId::from_str(query_owner)? && dir_access.is_none()
         }
+        _ => dir_access.is_none(),
+    };
+
+    let folder_service = get_folder_service(depot)?;
+    let folders = folder_service.get_folders_by_map(&queries).await?;
+
+    // The visibility cannot be filtered in the query,
+    // since the inherit folders depend on the folders above them
+    let folders = match public_only {
+        true => folder_service.filter_public_folders(folders).await?,
+        false => folders,
     };
 
-    let folders = get_folder_service(depot)?
-        .get_folders_by_map(&queries)
+    // Fetch all of the owners at once, instead of one query per folder
+    let owner_ids = folders.iter().map(|f| f.owner).collect::<Vec<_>>();
+    let owners = get_user_service(depot)?
+        .get_users_map_by_ids(&owner_ids)
         .await?;
 
     let mut responses = vec![];
 
     for folder in folders {
-        let owner = get_user_service(depot)?
-            .get_user_by_id(&folder.owner)
-            .await?;
+        let owner = owners
+            .get(&folder.owner)
+            .cloned()
+            .ok_or("Cannot find the owner of the folder")?;
         responses.push(FinalFolderResponse::new(folder, owner)?)
     }
 
     Ok(Web::ok("Get folders successfully", responses))
 }
 
+/// Get a folder
+#[utoipa::path(
+    get,
+    path = "/folder/{param_folder_id}",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    responses(
+        (status = 200, description = "Get a folder successfully", body = FinalFolderResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_folder_by_id_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     let param_folder_id = get_param_folder_id(req)?;
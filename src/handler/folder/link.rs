@@ -0,0 +1,111 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::folder::Folder,
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        cookie::get_cookie_user_id,
+        depot::{get_param_access, get_param_folder, get_share_link_service},
+        param::get_param_link_id,
+    },
+    request::share_link::create::CreateShareLinkRequest,
+    web::Web,
+    Result, WebResult,
+};
+
+// Only the owner can decide who the folder is shared with, links included
+fn check_owner(depot: &Depot) -> Result<&Folder> {
+    if !get_param_access(depot)?.is_owner() {
+        return Err(Error::Permissions(
+            "You cannot share other user's folder".into(),
+        ));
+    }
+    get_param_folder(depot)
+}
+
+/// Create a share link
+#[utoipa::path(
+    post,
+    path = "/folder/{param_folder_id}/links",
+    tag = "link",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 200, description = "Create a share link successfully", body = ShareLinkResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn create_folder_link_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the data from request
+    let link_req = extract_from_body::<CreateShareLinkRequest>(req).await?;
+
+    let param_folder = check_owner(depot)?;
+
+    let link_model = link_req.into_link(&param_folder.id, get_cookie_user_id(depot)?)?;
+
+    let created_link = get_share_link_service(depot)?
+        .create_link(link_model)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok("Create link successfully", created_link))
+}
+
+/// List the share links of a folder
+#[utoipa::path(
+    get,
+    path = "/folder/{param_folder_id}/links",
+    tag = "link",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    responses(
+        (status = 200, description = "List the share links of a folder successfully", body = [ShareLinkResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_folder_links_handler(depot: &mut Depot) -> WebResult {
+    let param_folder = check_owner(depot)?;
+
+    let links = get_share_link_service(depot)?
+        .get_links_by_folder(&param_folder.id)
+        .await?
+        .into_iter()
+        .flat_map(|l| l.into_response())
+        .collect::<Vec<_>>();
+
+    Ok(Web::ok("Get folder links successfully", links))
+}
+
+/// Delete a share link
+#[utoipa::path(
+    delete,
+    path = "/folder/{param_folder_id}/links/delete/{param_link_id}",
+    tag = "link",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+        ("param_link_id" = String, Path, description = "The id of the share link"),
+    ),
+    responses(
+        (status = 200, description = "Delete a share link successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_folder_link_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the link to delete
+    let param_link_id = get_param_link_id(req)?;
+
+    let param_folder = check_owner(depot)?;
+
+    get_share_link_service(depot)?
+        .delete_link_by_id_folder(&param_link_id, &param_folder.id)
+        .await?;
+
+    Ok(Web::ok("Link deleted", ()))
+}
@@ -1,4 +1,9 @@
 pub mod create;
 pub mod delete;
+pub mod file_request;
 pub mod get;
+pub mod link;
+pub mod share;
+pub mod tag;
 pub mod update;
+pub mod visibility;
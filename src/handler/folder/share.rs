@@ -0,0 +1,127 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::folder::Folder,
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        depot::{get_acl_service, get_param_access, get_param_folder, get_user_service},
+        param::get_param_user_id,
+    },
+    request::share::create::ShareRequest,
+    response::share::ShareResponse,
+    web::Web,
+    Result, WebResult,
+};
+
+// Only the owner can decide who the folder is shared with
+fn check_owner(depot: &Depot) -> Result<&Folder> {
+    if !get_param_access(depot)?.is_owner() {
+        return Err(Error::Permissions(
+            "You cannot share other user's folder".into(),
+        ));
+    }
+    get_param_folder(depot)
+}
+
+/// Share a folder
+#[utoipa::path(
+    post,
+    path = "/folder/{param_folder_id}/share",
+    tag = "share",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = ShareRequest,
+    responses(
+        (status = 200, description = "Share a folder successfully", body = ShareResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn share_folder_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the user and the role from the request
+    let (username, role) = extract_from_body::<ShareRequest>(req).await?.into_share()?;
+
+    let param_folder = check_owner(depot)?;
+
+    let entry = get_acl_service(depot)?
+        .share_folder(param_folder, &username, role)
+        .await?;
+
+    let grantee = get_user_service(depot)?
+        .get_user_by_id(&entry.grantee)
+        .await?;
+
+    Ok(Web::ok(
+        "Share folder successfully",
+        ShareResponse::new(entry, grantee)?,
+    ))
+}
+
+/// List the users a folder is shared with
+#[utoipa::path(
+    get,
+    path = "/folder/{param_folder_id}/share",
+    tag = "share",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    responses(
+        (status = 200, description = "List the users a folder is shared with successfully", body = [ShareResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_folder_shares_handler(depot: &mut Depot) -> WebResult {
+    let param_folder = check_owner(depot)?;
+
+    let entries = get_acl_service(depot)?
+        .get_entries_by_resource(&param_folder.id)
+        .await?;
+
+    // Fetch all of the users at once, instead of one query per entry
+    let grantee_ids = entries.iter().map(|e| e.grantee).collect::<Vec<_>>();
+    let grantees = get_user_service(depot)?
+        .get_users_map_by_ids(&grantee_ids)
+        .await?;
+
+    let mut responses = vec![];
+    for entry in entries {
+        let grantee = grantees
+            .get(&entry.grantee)
+            .cloned()
+            .ok_or("Cannot find the user that the folder is shared with")?;
+        responses.push(ShareResponse::new(entry, grantee)?);
+    }
+
+    Ok(Web::ok("Get folder shares successfully", responses))
+}
+
+/// Stop sharing a folder
+#[utoipa::path(
+    delete,
+    path = "/folder/{param_folder_id}/share/delete/{param_user_id}",
+    tag = "share",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Stop sharing a folder successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn unshare_folder_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the user to stop sharing with
+    let param_user_id = get_param_user_id(req)?;
+
+    let param_folder = check_owner(depot)?;
+
+    get_acl_service(depot)?
+        .unshare(&param_folder.id, &param_user_id)
+        .await?;
+
+    Ok(Web::ok("Stop sharing folder successfully", ()))
+}
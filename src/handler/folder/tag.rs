@@ -0,0 +1,155 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::{folder::Folder, user::User},
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        cookie::get_cookie_user,
+        depot::{get_folder_service, get_param_folder},
+        param::get_param_metadata_key,
+    },
+    request::tag::{metadata::MetadataRequest, tags::TagsRequest},
+    response::FinalFolderResponse,
+    web::Web,
+    Result, WebResult,
+};
+
+// Only the owner can change the tags and the metadata of a folder
+fn check_owner(depot: &Depot) -> Result<(&User, &Folder)> {
+    let cookie_user = get_cookie_user(depot)?;
+    let param_folder = get_param_folder(depot)?;
+
+    if cookie_user.id != param_folder.owner {
+        return Err(Error::Permissions(
+            "You cannot change the tags or metadata of other user's folder".into(),
+        ));
+    }
+    Ok((cookie_user, param_folder))
+}
+
+/// Add tags to a folder
+#[utoipa::path(
+    put,
+    path = "/folder/{param_folder_id}/tags/add",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = TagsRequest,
+    responses(
+        (status = 200, description = "Add tags to a folder successfully", body = FinalFolderResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn add_folder_tags_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the tags from the request
+    let tags = extract_from_body::<TagsRequest>(req).await?.into_tags()?;
+
+    let (cookie_user, param_folder) = check_owner(depot)?;
+
+    let folder = get_folder_service(depot)?
+        .add_tags_by_id(&param_folder.id, &tags)
+        .await?;
+
+    Ok(Web::ok(
+        "Add tags successfully",
+        FinalFolderResponse::new(folder, cookie_user.clone())?,
+    ))
+}
+
+/// Remove tags from a folder
+#[utoipa::path(
+    put,
+    path = "/folder/{param_folder_id}/tags/remove",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = TagsRequest,
+    responses(
+        (status = 200, description = "Remove tags from a folder successfully", body = FinalFolderResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn remove_folder_tags_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the tags from the request
+    let tags = extract_from_body::<TagsRequest>(req).await?.into_tags()?;
+
+    let (cookie_user, param_folder) = check_owner(depot)?;
+
+    let folder = get_folder_service(depot)?
+        .remove_tags_by_id(&param_folder.id, &tags)
+        .await?;
+
+    Ok(Web::ok(
+        "Remove tags successfully",
+        FinalFolderResponse::new(folder, cookie_user.clone())?,
+    ))
+}
+
+/// Update the metadata of a folder
+#[utoipa::path(
+    put,
+    path = "/folder/{param_folder_id}/metadata/update",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = MetadataRequest,
+    responses(
+        (status = 200, description = "Update the metadata of a folder successfully", body = FinalFolderResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn update_folder_metadata_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the metadata from the request
+    let metadata = extract_from_body::<MetadataRequest>(req)
+        .await?
+        .into_metadata()?;
+
+    let (cookie_user, param_folder) = check_owner(depot)?;
+
+    let folder = get_folder_service(depot)?
+        .update_metadata_by_id(&param_folder.id, &metadata)
+        .await?;
+
+    Ok(Web::ok(
+        "Update metadata successfully",
+        FinalFolderResponse::new(folder, cookie_user.clone())?,
+    ))
+}
+
+/// Delete a metadata key of a folder
+#[utoipa::path(
+    delete,
+    path = "/folder/{param_folder_id}/metadata/delete/{metadata_key}",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+        ("metadata_key" = String, Path, description = "The key of the metadata"),
+    ),
+    responses(
+        (status = 200, description = "Delete a metadata key of a folder successfully", body = FinalFolderResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_folder_metadata_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the metadata key from param
+    let key = get_param_metadata_key(req)?;
+
+    let (cookie_user, param_folder) = check_owner(depot)?;
+
+    let folder = get_folder_service(depot)?
+        .delete_metadata_by_id(&param_folder.id, &key)
+        .await?;
+
+    Ok(Web::ok(
+        "Delete metadata successfully",
+        FinalFolderResponse::new(folder, cookie_user.clone())?,
+    ))
+}
@@ -1,11 +1,15 @@
 use salvo::{handler, Depot, Request};
 
 use crate::{
+    base::acl::Access,
     error::Error,
     helper::{
         body::extract_from_body,
         cookie::get_cookie_user,
-        depot::{get_folder_service, get_param_folder},
+        depot::{
+            get_acl_service, get_folder_service, get_param_access, get_param_folder,
+            get_team_service, get_user_service,
+        },
         param::get_param_folder_id,
     },
     request::folder::update::UpdateFolderRequest,
@@ -13,6 +17,20 @@ use crate::{
     WebResult,
 };
 
+/// Update a folder
+#[utoipa::path(
+    put,
+    path = "/folder/update/{param_folder_id}",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = UpdateFolderRequest,
+    responses(
+        (status = 200, description = "Update a folder successfully", body = FolderResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn update_folder_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Checks if the user has logged in or not
@@ -32,13 +50,45 @@ pub async fn update_folder_handler(req: &mut Request, depot: &mut Depot) -> WebR
     // Get the cookie user
     let cookie_user = get_cookie_user(depot)?;
 
-    if cookie_user.id != old_folder.owner {
+    // The owner and the editors can update the folder
+    if !get_param_access(depot)?.can_edit() {
         return Err(Error::Permissions(
             "You cannot update other user's folder".into(),
         ));
     }
 
-    let folder_model = folder_req.into_folder(cookie_user, old_folder.clone())?;
+    // The folder stays in the owner's folders, even when an editor updates it
+    let owner = match cookie_user.id == old_folder.owner {
+        true => cookie_user.clone(),
+        false => {
+            get_user_service(depot)?
+                .get_user_by_id(&old_folder.owner)
+                .await?
+        }
+    };
+
+    // The folders of a team stay in the tree of the team
+    let team = match &old_folder.team {
+        Some(team_id) => Some(get_team_service(depot)?.get_team_by_id(team_id).await?),
+        None => None,
+    };
+
+    let folder_model = folder_req.into_folder(&owner, team.as_ref(), old_folder.clone())?;
+
+    // An editor only has the folder, not the folders around it,
+    // so it can only be moved into a folder that they can edit as well
+    // The same error for every other folder, whether it exists or not
+    if folder_model.position != old_folder.position
+        && *get_param_access(depot)? != Access::Owner
+        && !get_acl_service(depot)?
+            .get_access_by_dir(&cookie_user.id, &folder_model.position)
+            .await?
+            .is_some_and(|a| a.can_edit())
+    {
+        return Err(Error::Permissions(
+            "You cannot move the folder into this folder".into(),
+        ));
+    }
 
     let updated_folder = folder_service
         .update_folder_by_id(&param_folder_id, folder_model)
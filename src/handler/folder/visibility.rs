@@ -0,0 +1,53 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        depot::{get_folder_service, get_param_access, get_param_folder},
+    },
+    request::folder::visibility::UpdateVisibilityRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Change the visibility of a folder
+#[utoipa::path(
+    put,
+    path = "/folder/{param_folder_id}/visibility",
+    tag = "folder",
+    params(
+        ("param_folder_id" = String, Path, description = "The id of the folder"),
+    ),
+    request_body = UpdateVisibilityRequest,
+    responses(
+        (status = 200, description = "Change the visibility of a folder successfully", body = FolderResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn update_folder_visibility_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the visibility from request
+    let (visibility, recursive) = extract_from_body::<UpdateVisibilityRequest>(req)
+        .await?
+        .into_visibility()?;
+
+    let param_folder = get_param_folder(depot)?;
+
+    // The owner and the editors can change the visibility of the folder
+    if !get_param_access(depot)?.can_edit() {
+        return Err(Error::Permissions(
+            "You cannot update other user's folder".into(),
+        ));
+    }
+
+    let updated_folder = get_folder_service(depot)?
+        .update_visibility_by_id(&param_folder.id, visibility, recursive)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok(
+        "Update folder visibility successfully",
+        updated_folder,
+    ))
+}
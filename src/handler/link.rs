@@ -0,0 +1,126 @@
+use mongodb::bson::oid::ObjectId;
+use salvo::{handler, Depot, Request};
+use tokio::{fs::File as LocalFile, io::AsyncReadExt};
+
+use crate::{
+    base::file::{File, Visibility},
+    error::Error,
+    helper::{
+        depot::{
+            get_file_service, get_param_link, get_param_link_folder, get_share_link_service,
+            get_team_service, get_user_service,
+        },
+        file::get_file_from_req,
+    },
+    response::share_link::LinkContentResponse,
+    web::Web,
+    WebResult,
+};
+
+/// List the content of a share link
+///
+/// A protected link takes its password in the X-Link-Password header.
+/// After too many wrong passwords, the link is locked for a while and answers 429.
+#[utoipa::path(
+    get,
+    path = "/link/{link_token}",
+    tag = "link",
+    params(
+        ("link_token" = String, Path, description = "The token of the share link"),
+        ("path" = Option<String>, Query, description = "The folder to list, relative to the shared folder"),
+    ),
+    responses(
+        (status = 200, description = "List the content of a share link successfully", body = LinkContentResponse),
+    )
+)]
+#[handler]
+pub async fn get_link_content_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // The path to list, relative to the shared folder, the shared folder itself by default
+    let path = req.query::<String>("path").unwrap_or_default();
+
+    let param_link = get_param_link(depot)?;
+    let param_link_folder = get_param_link_folder(depot)?;
+
+    let (folders, files) = get_share_link_service(depot)?
+        .get_link_content(param_link_folder, &path)
+        .await?;
+
+    Ok(Web::ok(
+        "Get link content successfully",
+        LinkContentResponse::new(param_link, param_link_folder.clone(), &path, folders, files)?,
+    ))
+}
+
+/// Upload a file through a share link
+///
+/// The file goes in the file field
+#[utoipa::path(
+    post,
+    path = "/link/{link_token}/upload",
+    tag = "link",
+    params(
+        ("link_token" = String, Path, description = "The token of the share link"),
+        ("path" = Option<String>, Query, description = "The folder to upload into, relative to the shared folder"),
+    ),
+    request_body(content = Object, content_type = "multipart/form-data", description = "The file in the file field"),
+    responses(
+        (status = 200, description = "Upload a file through a share link successfully", body = FileResponse),
+    )
+)]
+#[handler]
+pub async fn upload_link_file_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // The position to upload into, relative to the shared folder
+    let path = req.query::<String>("path").unwrap_or_default();
+
+    let param_link = get_param_link(depot)?;
+    if !param_link.allow_upload {
+        return Err(Error::Permissions(
+            "This link does not allow uploading".into(),
+        ));
+    }
+
+    let param_link_folder = get_param_link_folder(depot)?;
+
+    // Get the attachment file
+    let file = get_file_from_req(req).await?;
+
+    // Open the received file from temporary path
+    let mut local_file = LocalFile::open(file.path()).await?;
+
+    // Read the file's byte stream and store it inside file_stream
+    let mut file_stream = vec![];
+    local_file.read_to_end(&mut file_stream).await?;
+
+    let full_filename = file
+        .name()
+        .ok_or("The attached file does not have a name")?;
+
+    // The uploaded file belongs to whoever owns the shared folder, and stays private
+    let owner = get_user_service(depot)?
+        .get_user_by_id(&param_link_folder.owner)
+        .await?;
+    let team = match &param_link_folder.team {
+        Some(team_id) => Some(get_team_service(depot)?.get_team_by_id(team_id).await?),
+        None => None,
+    };
+
+    let position =
+        get_share_link_service(depot)?.get_link_relative_position(param_link_folder, &path)?;
+
+    let file_model = File::new(
+        ObjectId::new(),
+        &owner,
+        team.as_ref(),
+        full_filename,
+        Visibility::Private,
+        &position,
+        None,
+    )?;
+
+    let created_file = get_file_service(depot)?
+        .create_file(file_model, file_stream)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok("Upload file successfully", created_file))
+}
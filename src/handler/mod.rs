@@ -1,6 +1,16 @@
+pub mod admin;
 pub mod auth;
+pub mod change;
 pub mod content;
+pub mod docs;
+pub mod drop;
+pub mod event;
 pub mod file;
 pub mod folder;
+pub mod link;
+pub mod search;
+pub mod shared;
+pub mod team;
 pub mod user;
 pub mod version;
+pub mod webhook;
@@ -0,0 +1,59 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id_option, depot::get_search_service, snippet::get_search_terms,
+    },
+    response::search::SearchResponse,
+    web::Web,
+    WebResult,
+};
+
+// The default and the maximum amount of results returned in one search
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+/// Search the files and folders
+#[utoipa::path(
+    get,
+    path = "/search",
+    tag = "search",
+    params(
+        ("q" = Option<String>, Query, description = "The search query"),
+        ("limit" = Option<i64>, Query, description = "How many results to return, 20 by default"),
+    ),
+    responses(
+        (status = 200, description = "Search the files and folders successfully", body = [SearchResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn search_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the search query
+    let query = req
+        .query::<String>("q")
+        .map(|q| q.trim().to_string())
+        .filter(|q| !q.is_empty())
+        .ok_or("The search query q cannot be empty")?;
+
+    let limit = req
+        .query::<i64>("limit")
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT);
+
+    // Guests can only search the public files and folders
+    let cookie_user_id_option = get_cookie_user_id_option(depot);
+
+    let results = get_search_service(depot)?
+        .search(&query, cookie_user_id_option, limit)
+        .await?;
+
+    let terms = get_search_terms(&query);
+
+    let mut responses = vec![];
+    for result in results {
+        responses.push(SearchResponse::new(result, &terms)?);
+    }
+
+    Ok(Web::ok("Search successfully", responses))
+}
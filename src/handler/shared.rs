@@ -0,0 +1,64 @@
+use salvo::{handler, Depot};
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::{get_acl_service, get_user_service},
+    },
+    response::share::SharedResponse,
+    web::Web,
+    WebResult,
+};
+
+/// List the files and folders shared with the user
+#[utoipa::path(
+    get,
+    path = "/shared",
+    tag = "share",
+    responses(
+        (status = 200, description = "List the files and folders shared with the user successfully", body = [SharedResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_shared_handler(depot: &mut Depot) -> WebResult {
+    // Check if the user is logged in or not
+    let cookie_user_id = get_cookie_user_id(depot)?;
+
+    let (files, folders) = get_acl_service(depot)?
+        .get_shared_with(cookie_user_id)
+        .await?;
+
+    // Fetch all of the owners at once, instead of one query per file or folder
+    let owner_ids = files
+        .iter()
+        .map(|(_, f)| f.owner)
+        .chain(folders.iter().map(|(_, f)| f.owner))
+        .collect::<Vec<_>>();
+    let owners = get_user_service(depot)?
+        .get_users_map_by_ids(&owner_ids)
+        .await?;
+
+    let mut responses = vec![];
+
+    for (role, folder) in folders {
+        let owner = owners
+            .get(&folder.owner)
+            .cloned()
+            .ok_or("Cannot find the owner of the folder")?;
+        responses.push(SharedResponse::from_folder(folder, role, owner)?);
+    }
+
+    for (role, file) in files {
+        let owner = owners
+            .get(&file.owner)
+            .cloned()
+            .ok_or("Cannot find the owner of the file")?;
+        responses.push(SharedResponse::from_file(file, role, owner)?);
+    }
+
+    Ok(Web::ok(
+        "Get shared files and folders successfully",
+        responses,
+    ))
+}
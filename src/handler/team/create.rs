@@ -0,0 +1,38 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{body::extract_from_body, cookie::get_cookie_user, depot::get_team_service},
+    request::team::create::CreateTeamRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Create a team
+#[utoipa::path(
+    post,
+    path = "/team/create",
+    tag = "team",
+    request_body = CreateTeamRequest,
+    responses(
+        (status = 200, description = "Create a team successfully", body = TeamResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn create_team_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the data from request
+    let team_req = extract_from_body::<CreateTeamRequest>(req).await?;
+
+    // Get the cookie user, who becomes the owner of the team
+    let cookie_user = get_cookie_user(depot)?;
+
+    let team_model = team_req.into_team(cookie_user)?;
+
+    // Create the team along with its root folder
+    let created_team = get_team_service(depot)?
+        .create_team(team_model, cookie_user)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok("Create team successfully", created_team))
+}
@@ -0,0 +1,43 @@
+use salvo::{handler, Depot};
+
+use crate::{
+    base::team::TeamRole,
+    error::Error,
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::{get_param_team, get_team_service},
+    },
+    web::Web,
+    WebResult,
+};
+
+/// Delete a team
+#[utoipa::path(
+    delete,
+    path = "/team/delete/{param_team_id}",
+    tag = "team",
+    params(
+        ("param_team_id" = String, Path, description = "The id of the team"),
+    ),
+    responses(
+        (status = 200, description = "Delete a team successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_team_handler(depot: &mut Depot) -> WebResult {
+    let cookie_user_id = get_cookie_user_id(depot)?;
+
+    let param_team = get_param_team(depot)?;
+
+    // Only the owner can delete the team, along with everything inside of it
+    if param_team.role_of(cookie_user_id) != Some(TeamRole::Owner) {
+        return Err(Error::Permissions(
+            "Only the owner can delete the team".into(),
+        ));
+    }
+
+    get_team_service(depot)?.delete_team(&param_team.id).await?;
+
+    Ok(Web::ok("Team deleted", ()))
+}
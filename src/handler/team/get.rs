@@ -0,0 +1,69 @@
+use salvo::{handler, Depot};
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::{get_param_team, get_team_service, get_user_service},
+    },
+    response::FinalTeamResponse,
+    web::Web,
+    WebResult,
+};
+
+/// List the teams of the user
+#[utoipa::path(
+    get,
+    path = "/team",
+    tag = "team",
+    responses(
+        (status = 200, description = "List the teams of the user successfully", body = [TeamResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_teams_handler(depot: &mut Depot) -> WebResult {
+    let cookie_user_id = get_cookie_user_id(depot)?;
+
+    // The teams that the user is a member of
+    let teams = get_team_service(depot)?
+        .get_teams_by_member(cookie_user_id)
+        .await?
+        .into_iter()
+        .flat_map(|t| t.into_response())
+        .collect::<Vec<_>>();
+
+    Ok(Web::ok("Get teams successfully", teams))
+}
+
+/// Get a team
+#[utoipa::path(
+    get,
+    path = "/team/{param_team_id}",
+    tag = "team",
+    params(
+        ("param_team_id" = String, Path, description = "The id of the team"),
+    ),
+    responses(
+        (status = 200, description = "Get a team successfully", body = FinalTeamResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_team_by_id_handler(depot: &mut Depot) -> WebResult {
+    let param_team = get_param_team(depot)?;
+
+    // Fetch all of the members at once, instead of one query per member
+    let member_ids = param_team
+        .members
+        .iter()
+        .map(|m| m.user)
+        .collect::<Vec<_>>();
+    let members = get_user_service(depot)?
+        .get_users_map_by_ids(&member_ids)
+        .await?;
+
+    Ok(Web::ok(
+        "Get team successfully",
+        FinalTeamResponse::new(param_team.clone(), &members)?,
+    ))
+}
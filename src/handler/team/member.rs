@@ -0,0 +1,158 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    base::team::{Team, TeamRole},
+    error::Error,
+    helper::{
+        body::extract_from_body,
+        cookie::get_cookie_user_id,
+        depot::{get_param_team, get_team_service, get_user_service},
+        param::get_param_user_id,
+    },
+    request::team::member::{AddMemberRequest, UpdateMemberRequest},
+    response::FinalTeamResponse,
+    web::Web,
+    Result, WebResult,
+};
+
+// The role of the logged in user in the param team
+fn get_cookie_team_role(depot: &Depot) -> Result<(&Team, TeamRole)> {
+    let param_team = get_param_team(depot)?;
+    let role = param_team
+        .role_of(get_cookie_user_id(depot)?)
+        .ok_or_else(|| {
+            Error::NotFound("Cannot find the team with the provided information".into())
+        })?;
+    Ok((param_team, role))
+}
+
+async fn into_final_response(team: Team, depot: &Depot) -> Result<FinalTeamResponse> {
+    let member_ids = team.members.iter().map(|m| m.user).collect::<Vec<_>>();
+    let members = get_user_service(depot)?
+        .get_users_map_by_ids(&member_ids)
+        .await?;
+    FinalTeamResponse::new(team, &members)
+}
+
+/// Add a member to a team
+#[utoipa::path(
+    put,
+    path = "/team/{param_team_id}/members/add",
+    tag = "team",
+    params(
+        ("param_team_id" = String, Path, description = "The id of the team"),
+    ),
+    request_body = AddMemberRequest,
+    responses(
+        (status = 200, description = "Add a member to a team successfully", body = FinalTeamResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn add_member_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the user and the role from the request
+    let (username, role) = extract_from_body::<AddMemberRequest>(req)
+        .await?
+        .into_member()?;
+
+    let (param_team, cookie_role) = get_cookie_team_role(depot)?;
+
+    // The admins can add members, but only the owner can add other admins
+    if cookie_role < TeamRole::Admin || (role >= TeamRole::Admin && cookie_role < TeamRole::Owner) {
+        return Err(Error::Permissions(
+            "You cannot add members with this role to the team".into(),
+        ));
+    }
+
+    let team = get_team_service(depot)?
+        .add_member(param_team, &username, role)
+        .await?;
+
+    Ok(Web::ok(
+        "Add member successfully",
+        into_final_response(team, depot).await?,
+    ))
+}
+
+/// Change the role of a member
+#[utoipa::path(
+    put,
+    path = "/team/{param_team_id}/members/update/{param_user_id}",
+    tag = "team",
+    params(
+        ("param_team_id" = String, Path, description = "The id of the team"),
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    request_body = UpdateMemberRequest,
+    responses(
+        (status = 200, description = "Change the role of a member successfully", body = FinalTeamResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn update_member_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let role = extract_from_body::<UpdateMemberRequest>(req)
+        .await?
+        .into_role()?;
+
+    // Get the member to update
+    let param_user_id = get_param_user_id(req)?;
+
+    let (param_team, cookie_role) = get_cookie_team_role(depot)?;
+
+    // Only the owner can promote or demote the members
+    if cookie_role != TeamRole::Owner {
+        return Err(Error::Permissions(
+            "Only the owner can change the role of the members".into(),
+        ));
+    }
+
+    let team = get_team_service(depot)?
+        .update_member_role(param_team, &param_user_id, role)
+        .await?;
+
+    Ok(Web::ok(
+        "Update member successfully",
+        into_final_response(team, depot).await?,
+    ))
+}
+
+/// Remove a member from a team
+#[utoipa::path(
+    delete,
+    path = "/team/{param_team_id}/members/delete/{param_user_id}",
+    tag = "team",
+    params(
+        ("param_team_id" = String, Path, description = "The id of the team"),
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Remove a member from a team successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn remove_member_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the member to remove
+    let param_user_id = get_param_user_id(req)?;
+
+    let (param_team, cookie_role) = get_cookie_team_role(depot)?;
+
+    // Everyone can leave the team
+    // The admins can remove the members, and the owner can remove anyone
+    let member_role = param_team.role_of(&param_user_id).ok_or_else(|| {
+        Error::NotFound("Cannot find the member with the provided information".into())
+    })?;
+    let is_self = *get_cookie_user_id(depot)? == param_user_id;
+    if !is_self && (cookie_role < TeamRole::Admin || member_role >= cookie_role) {
+        return Err(Error::Permissions(
+            "You cannot remove this member from the team".into(),
+        ));
+    }
+
+    get_team_service(depot)?
+        .remove_member(param_team, &param_user_id)
+        .await?;
+
+    Ok(Web::ok("Remove member successfully", ()))
+}
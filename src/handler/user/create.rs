@@ -1,12 +1,25 @@
 use salvo::{handler, Depot, Request};
 
 use crate::{
-    helper::{body::extract_from_body, depot::get_user_service},
+    helper::{
+        body::extract_from_body,
+        depot::{get_account_service, get_user_service},
+    },
     request::user::create::CreateUserRequest,
     web::Web,
     WebResult,
 };
 
+/// Register
+#[utoipa::path(
+    post,
+    path = "/user/register",
+    tag = "user",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 200, description = "Register successfully", body = UserResponse),
+    )
+)]
 #[handler]
 pub async fn create_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Get the user_db
@@ -17,7 +30,18 @@ pub async fn create_user_handler(req: &mut Request, depot: &mut Depot) -> WebRes
         .await?
         .into_user()?;
 
-    let new_user = user_service.create_user(user).await?.into_response()?;
+    let new_user = user_service.create_user(user).await?;
 
-    Ok(Web::ok("Create user successfully", new_user))
+    // The account exists either way, the user can ask for another mail if this one fails
+    if let Err(e) = get_account_service(depot)?
+        .send_verification(&new_user)
+        .await
+    {
+        tracing::warn!("Cannot send the verification mail to {}: {e}", new_user.id);
+    }
+
+    Ok(Web::ok(
+        "Create user successfully",
+        new_user.into_response()?,
+    ))
 }
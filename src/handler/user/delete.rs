@@ -11,6 +11,22 @@ use crate::{
     WebResult,
 };
 
+/// Delete the account
+///
+/// It can be restored until it is purged
+#[utoipa::path(
+    delete,
+    path = "/user/delete/{param_user_id}",
+    tag = "user",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    request_body = DeleteUserRequest,
+    responses(
+        (status = 200, description = "Delete the account successfully", body = UserResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn delete_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Extract the delete user request
@@ -39,9 +55,12 @@ pub async fn delete_user_handler(req: &mut Request, depot: &mut Depot) -> WebRes
     // The above code checks if the password and confirmPassword are the same
     // and also check if the request password is the same as the user's current password
 
-    // Finally delete the user
-    user_service.delete_user_by_id(&param_user_id).await?;
+    // The account is locked now, and purged once the grace period is over
+    // Until then, the user can still cancel the deletion
+    let deleted_user = user_service.schedule_deletion_by_id(&param_user_id).await?;
 
-    // We have successflly deleted a user
-    Ok(Web::ok("Delete user successfully", ()))
+    Ok(Web::ok(
+        "Delete user successfully, the account can be restored until it is purged",
+        deleted_user.into_response()?,
+    ))
 }
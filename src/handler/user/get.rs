@@ -10,22 +10,20 @@ use crate::{
     WebResult,
 };
 
-#[handler]
-pub async fn get_users_handler(depot: &mut Depot) -> WebResult {
-    // Get the user_db from depot
-    let user_service = get_user_service(depot)?;
-
-    // Get the list of users
-    let users = user_service.get_users().await?;
-
-    let mut user_responses = vec![];
-    for u in users {
-        user_responses.push(u.into_response()?)
-    }
-
-    Ok(Web::ok("Get all users successfully", user_responses))
-}
-
+/// Get a user
+///
+/// Other users and guests only see the public files and folders
+#[utoipa::path(
+    get,
+    path = "/user/{param_user_id}",
+    tag = "user",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Get a user successfully", body = FinalUserResponse),
+    )
+)]
 #[handler]
 pub async fn get_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Get user_service from depot
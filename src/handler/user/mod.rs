@@ -1,5 +1,10 @@
 pub mod create;
 pub mod delete;
 pub mod get;
+pub mod password;
 pub mod profile;
+pub mod restore;
+pub mod tag;
+pub mod two_factor;
 pub mod update;
+pub mod verify;
@@ -0,0 +1,61 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{body::extract_from_body, depot::get_account_service},
+    request::user::password::{ForgotPasswordRequest, ResetPasswordRequest},
+    web::Web,
+    WebResult,
+};
+
+/// Send a password reset mail
+#[utoipa::path(
+    post,
+    path = "/user/password/forgot",
+    tag = "user",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 200, description = "Send a password reset mail successfully"),
+    )
+)]
+#[handler]
+pub async fn forgot_password_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let user_req = extract_from_body::<ForgotPasswordRequest>(req)
+        .await?
+        .validate_self()?;
+
+    get_account_service(depot)?
+        .request_password_reset(&user_req.email)
+        .await?;
+
+    // The same answer whether there is an account with this email or not
+    Ok(Web::ok(
+        "If there is an account with this email, a reset mail has been sent",
+        (),
+    ))
+}
+
+/// Reset the password with the token from the mail
+#[utoipa::path(
+    post,
+    path = "/user/password/reset",
+    tag = "user",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Reset the password with the token from the mail successfully", body = UserResponse),
+    )
+)]
+#[handler]
+pub async fn reset_password_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let user_req = extract_from_body::<ResetPasswordRequest>(req)
+        .await?
+        .validate_self()?;
+
+    let updated_user = get_account_service(depot)?
+        .reset_password(&user_req.token, &user_req.new_password)
+        .await?;
+
+    Ok(Web::ok(
+        "Reset password successfully, please login again",
+        updated_user.into_response()?,
+    ))
+}
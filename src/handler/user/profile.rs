@@ -10,6 +10,16 @@ use crate::{
     WebResult,
 };
 
+/// Get the logged in user
+#[utoipa::path(
+    get,
+    path = "/user/profile",
+    tag = "user",
+    responses(
+        (status = 200, description = "Get the logged in user successfully", body = FinalUserResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn profile_handler(depot: &Depot) -> WebResult {
     // Get the cookie user id
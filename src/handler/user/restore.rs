@@ -0,0 +1,58 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        body::extract_from_body,
+        depot::{get_login_throttle, get_user_service},
+    },
+    request::user::restore::RestoreUserRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Restore a deleted account
+///
+/// An account with two-factor authentication sends a code or a recovery code along.
+#[utoipa::path(
+    post,
+    path = "/user/restore",
+    tag = "user",
+    request_body = RestoreUserRequest,
+    responses(
+        (status = 200, description = "Restore a deleted account successfully", body = UserResponse),
+    )
+)]
+#[handler]
+pub async fn restore_user_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // The account cannot log in while it is pending deletion,
+    // so the login info is sent along with the request instead
+    let user_req = extract_from_body::<RestoreUserRequest>(req)
+        .await?
+        .validate_self()?;
+
+    // The same lockout as the login, or this would be a way around it
+    // A wrong two-factor code counts as a failure as well
+    let login_throttle = get_login_throttle(depot)?;
+    login_throttle.check(&user_req.username)?;
+
+    let restored_user = match get_user_service(depot)?
+        .cancel_deletion(
+            &user_req.username,
+            &user_req.password,
+            user_req.code.as_deref(),
+        )
+        .await
+    {
+        Ok(restored_user) => restored_user,
+        Err(e) => {
+            login_throttle.record_failure(&user_req.username)?;
+            return Err(e);
+        }
+    };
+    login_throttle.reset(&user_req.username)?;
+
+    Ok(Web::ok(
+        "Restore user successfully, please login again",
+        restored_user.into_response()?,
+    ))
+}
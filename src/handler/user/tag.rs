@@ -0,0 +1,44 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id_option, depot::get_user_service, param::get_param_user_id,
+    },
+    response::tag::TagCountResponse,
+    web::Web,
+    WebResult,
+};
+
+/// Count the tags of a user
+#[utoipa::path(
+    get,
+    path = "/user/{param_user_id}/tags",
+    tag = "user",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    responses(
+        (status = 200, description = "Count the tags of a user successfully", body = TagCountResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_user_tags_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Get the param user id from param
+    let param_user_id = get_param_user_id(req)?;
+
+    // Other users and guests only see the tags on the public files and folders
+    let public_only = match get_cookie_user_id_option(depot) {
+        Some(cookie_user_id) => *cookie_user_id != param_user_id,
+        None => true,
+    };
+
+    let (file_counts, folder_counts) = get_user_service(depot)?
+        .get_tag_counts(&param_user_id, public_only)
+        .await?;
+
+    Ok(Web::ok(
+        "Get tag counts successfully",
+        TagCountResponse::from_counts(file_counts, folder_counts),
+    ))
+}
@@ -0,0 +1,101 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        body::extract_from_body,
+        cookie::{get_cookie_user, get_cookie_user_id},
+        depot::get_user_service,
+        totp::otpauth_uri,
+    },
+    request::user::two_factor::{DisableTwoFactorRequest, TwoFactorCodeRequest},
+    response::two_factor::{RecoveryCodesResponse, TwoFactorSetupResponse},
+    web::Web,
+    WebResult,
+};
+
+/// Start the two-factor enrollment
+#[utoipa::path(
+    post,
+    path = "/user/2fa/enroll",
+    tag = "user",
+    responses(
+        (status = 200, description = "Start the two-factor enrollment successfully", body = TwoFactorSetupResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn enroll_two_factor_handler(depot: &mut Depot) -> WebResult {
+    let cookie_user = get_cookie_user(depot)?;
+
+    let secret = get_user_service(depot)?
+        .enroll_two_factor(&cookie_user.id)
+        .await?;
+
+    // Two-factor authentication is only enabled once a code is verified
+    Ok(Web::ok(
+        "Enroll in two-factor authentication successfully, verify a code to enable it",
+        TwoFactorSetupResponse {
+            otpauth_uri: otpauth_uri(&secret, &cookie_user.username),
+            secret,
+        },
+    ))
+}
+
+/// Enable two-factor authentication
+#[utoipa::path(
+    post,
+    path = "/user/2fa/enable",
+    tag = "user",
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Enable two-factor authentication successfully", body = RecoveryCodesResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn enable_two_factor_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let user_req = extract_from_body::<TwoFactorCodeRequest>(req)
+        .await?
+        .validate_self()?;
+
+    let recovery_codes = get_user_service(depot)?
+        .enable_two_factor(get_cookie_user_id(depot)?, &user_req.code)
+        .await?;
+
+    // The recovery codes are stored hashed, this is the only time they can be seen
+    Ok(Web::ok(
+        "Enable two-factor authentication successfully",
+        RecoveryCodesResponse { recovery_codes },
+    ))
+}
+
+/// Disable two-factor authentication
+#[utoipa::path(
+    post,
+    path = "/user/2fa/disable",
+    tag = "user",
+    request_body = DisableTwoFactorRequest,
+    responses(
+        (status = 200, description = "Disable two-factor authentication successfully", body = UserResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn disable_two_factor_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let user_req = extract_from_body::<DisableTwoFactorRequest>(req)
+        .await?
+        .validate_self()?;
+
+    let updated_user = get_user_service(depot)?
+        .disable_two_factor(
+            get_cookie_user_id(depot)?,
+            &user_req.password,
+            &user_req.code,
+        )
+        .await?;
+
+    Ok(Web::ok(
+        "Disable two-factor authentication successfully",
+        updated_user.into_response()?,
+    ))
+}
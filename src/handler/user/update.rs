@@ -6,7 +6,7 @@ use crate::{
     helper::{
         body::extract_from_body,
         cookie::get_cookie_user_id,
-        depot::{get_file_service, get_folder_service, get_user_service},
+        depot::{get_account_service, get_file_service, get_folder_service, get_user_service},
         param::get_param_user_id,
     },
     request::user::update::UpdateUserRequest,
@@ -15,6 +15,20 @@ use crate::{
     WebResult,
 };
 
+/// Update the account
+#[utoipa::path(
+    put,
+    path = "/user/update/{param_user_id}",
+    tag = "user",
+    params(
+        ("param_user_id" = String, Path, description = "The id of the user"),
+    ),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "Update the account successfully", body = FinalUserResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn update_user_handler(req: &mut Request, depot: &Depot) -> WebResult {
     // Extract the update user request
@@ -38,12 +52,28 @@ pub async fn update_user_handler(req: &mut Request, depot: &Depot) -> WebResult
         ));
     }
 
+    let old_email = param_user.email.clone();
+
     // Construct the user to update
     let update_user = user_req.into_user(param_user)?;
 
     // Update the user
     let changed_user = user_service.update_user(update_user).await?;
 
+    // A new email is unverified until the user opens the mail sent to it
+    // The email is changed either way, the user can ask for another mail if this one fails
+    if changed_user.email != old_email {
+        if let Err(e) = get_account_service(depot)?
+            .send_verification(&changed_user)
+            .await
+        {
+            tracing::warn!(
+                "Cannot send the verification mail to {}: {e}",
+                changed_user.id
+            );
+        }
+    }
+
     // Get the files and folders that the user owns
     let (files, folders) = try_join!(
         get_file_service(depot)?.get_files_by_owner(cookie_user_id),
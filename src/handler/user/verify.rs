@@ -0,0 +1,56 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{body::extract_from_body, cookie::get_cookie_user, depot::get_account_service},
+    request::user::verify::VerifyEmailRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Verify the email with the token from the mail
+#[utoipa::path(
+    post,
+    path = "/user/verify",
+    tag = "user",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Verify the email with the token from the mail successfully", body = UserResponse),
+    )
+)]
+#[handler]
+pub async fn verify_email_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // The token comes from the link in the mail, the user does not have to be logged in
+    let user_req = extract_from_body::<VerifyEmailRequest>(req)
+        .await?
+        .validate_self()?;
+
+    let verified_user = get_account_service(depot)?
+        .verify_email(&user_req.token)
+        .await?;
+
+    Ok(Web::ok(
+        "Verify email successfully",
+        verified_user.into_response()?,
+    ))
+}
+
+/// Send the verification mail again
+#[utoipa::path(
+    post,
+    path = "/user/verify/resend",
+    tag = "user",
+    responses(
+        (status = 200, description = "Send the verification mail again successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn resend_verification_handler(depot: &mut Depot) -> WebResult {
+    let cookie_user = get_cookie_user(depot)?;
+
+    get_account_service(depot)?
+        .send_verification(cookie_user)
+        .await?;
+
+    Ok(Web::ok("Send verification mail successfully", ()))
+}
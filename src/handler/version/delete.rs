@@ -4,7 +4,7 @@ use crate::{
     error::Error,
     helper::{
         cookie::get_cookie_user_id,
-        depot::{get_file_version_service, get_param_file, get_user_service},
+        depot::{get_file_version_service, get_param_access, get_param_file, get_user_service},
         param::get_param_version_number,
     },
     response::FinalFileResponse,
@@ -12,6 +12,20 @@ use crate::{
     WebResult,
 };
 
+/// Delete a version of a file
+#[utoipa::path(
+    delete,
+    path = "/file/{param_file_id}/versions/delete/{version_number}",
+    tag = "version",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+        ("version_number" = i64, Path, description = "The number of the version"),
+    ),
+    responses(
+        (status = 200, description = "Delete a version of a file successfully", body = FinalFileResponse),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn delete_file_version_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     // Check if the user is logged in or not
@@ -22,7 +36,8 @@ pub async fn delete_file_version_handler(req: &mut Request, depot: &mut Depot) -
 
     let param_file = get_param_file(depot)?;
 
-    if *cookie_user_id != param_file.owner {
+    // The owner and the editors can delete the versions of the file
+    if !get_param_access(depot)?.can_edit() {
         return Err(Error::Permissions(
             "You cannot delete other user's file".into(),
         ));
@@ -44,7 +59,7 @@ pub async fn delete_file_version_handler(req: &mut Request, depot: &mut Depot) -
         FinalFileResponse::new(
             param_file.clone(),
             get_user_service(depot)?
-                .get_user_by_id(cookie_user_id)
+                .get_user_by_id(&param_file.owner)
                 .await?,
             file_version_service
                 .get_versions_by_file_id(&param_file_id)
@@ -10,6 +10,19 @@ use crate::{
     WebResult,
 };
 
+/// List the version numbers of a file
+#[utoipa::path(
+    get,
+    path = "/file/{param_file_id}/versions",
+    tag = "version",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    responses(
+        (status = 200, description = "The version numbers, like {\"versions\": [1, 2]}", body = Object),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_versions_handler(depot: &mut Depot) -> WebResult {
     let param_file = get_param_file(depot)?;
@@ -29,6 +42,20 @@ pub async fn get_versions_handler(depot: &mut Depot) -> WebResult {
     ))
 }
 
+/// Get a version of a file
+#[utoipa::path(
+    get,
+    path = "/file/{param_file_id}/versions/{version_number}",
+    tag = "version",
+    params(
+        ("param_file_id" = String, Path, description = "The id of the file"),
+        ("version_number" = i64, Path, description = "The number of the version"),
+    ),
+    responses(
+        (status = 200, description = "The version number, like {\"version\": 2}", body = Object),
+    ),
+    security(("access_token" = []))
+)]
 #[handler]
 pub async fn get_version_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
     let param_file_id = get_param_file(depot)?.id;
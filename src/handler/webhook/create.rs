@@ -0,0 +1,38 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{body::extract_from_body, cookie::get_cookie_user, depot::get_webhook_service},
+    request::webhook::create::CreateWebhookRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Register a webhook
+///
+/// The secret that signs the deliveries is only in this response, keep it somewhere safe.
+/// Every delivery is a POST with the X-Webhook-Event, X-Webhook-Delivery and X-Webhook-Signature headers,
+/// the signature is t=<unix seconds>,v1=<hex HMAC-SHA256 of "<unix seconds>.<body>" with the secret>.
+#[utoipa::path(
+    post,
+    path = "/webhook/create",
+    tag = "webhook",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 200, description = "Register a webhook successfully", body = WebhookResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn create_webhook_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the data from request
+    let webhook_req = extract_from_body::<CreateWebhookRequest>(req).await?;
+
+    let webhook_model = webhook_req.into_webhook(get_cookie_user(depot)?)?;
+
+    let created_webhook = get_webhook_service(depot)?
+        .create_webhook(webhook_model)
+        .await?
+        .into_response_with_secret()?;
+
+    Ok(Web::ok("Create webhook successfully", created_webhook))
+}
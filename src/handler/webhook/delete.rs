@@ -0,0 +1,32 @@
+use salvo::{handler, Depot};
+
+use crate::{
+    helper::depot::{get_param_webhook, get_webhook_service},
+    web::Web,
+    WebResult,
+};
+
+/// Delete a webhook
+#[utoipa::path(
+    delete,
+    path = "/webhook/delete/{param_webhook_id}",
+    tag = "webhook",
+    params(
+        ("param_webhook_id" = String, Path, description = "The id of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "Delete a webhook successfully"),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn delete_webhook_handler(depot: &mut Depot) -> WebResult {
+    let param_webhook = get_param_webhook(depot)?;
+
+    // The deliveries are deleted along with it
+    get_webhook_service(depot)?
+        .delete_webhook(&param_webhook.id)
+        .await?;
+
+    Ok(Web::ok("Webhook deleted", ()))
+}
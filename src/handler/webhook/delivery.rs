@@ -0,0 +1,76 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        depot::{get_param_webhook, get_webhook_service},
+        param::get_param_delivery_id,
+    },
+    web::Web,
+    WebResult,
+};
+
+/// List the deliveries of a webhook
+///
+/// The latest 100 deliveries, newest first, with every try to send them.
+#[utoipa::path(
+    get,
+    path = "/webhook/{param_webhook_id}/deliveries",
+    tag = "webhook",
+    params(
+        ("param_webhook_id" = String, Path, description = "The id of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "List the deliveries of a webhook successfully", body = [WebhookDeliveryResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_deliveries_handler(depot: &mut Depot) -> WebResult {
+    let param_webhook = get_param_webhook(depot)?;
+
+    let deliveries = get_webhook_service(depot)?
+        .get_deliveries(&param_webhook.id)
+        .await?
+        .into_iter()
+        .flat_map(|d| d.into_response())
+        .collect::<Vec<_>>();
+
+    Ok(Web::ok("Get deliveries successfully", deliveries))
+}
+
+/// Redeliver a delivery
+///
+/// Sends the same payload again as a new delivery, with the same event id, and returns it after the first try.
+#[utoipa::path(
+    post,
+    path = "/webhook/{param_webhook_id}/deliveries/{param_delivery_id}/redeliver",
+    tag = "webhook",
+    params(
+        ("param_webhook_id" = String, Path, description = "The id of the webhook"),
+        ("param_delivery_id" = String, Path, description = "The id of the delivery"),
+    ),
+    responses(
+        (status = 200, description = "Redeliver a delivery successfully", body = WebhookDeliveryResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn redeliver_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    let param_delivery_id = get_param_delivery_id(req)?;
+
+    let param_webhook = get_param_webhook(depot)?;
+
+    let webhook_service = get_webhook_service(depot)?;
+
+    // The delivery has to belong to the param webhook
+    let delivery = webhook_service
+        .get_delivery_by_id_webhook(&param_delivery_id, &param_webhook.id)
+        .await?;
+
+    let new_delivery = webhook_service
+        .redeliver(&delivery)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok("Redeliver successfully", new_delivery))
+}
@@ -0,0 +1,55 @@
+use salvo::{handler, Depot};
+
+use crate::{
+    helper::{
+        cookie::get_cookie_user_id,
+        depot::{get_param_webhook, get_webhook_service},
+    },
+    web::Web,
+    WebResult,
+};
+
+/// List the webhooks of the user
+#[utoipa::path(
+    get,
+    path = "/webhook",
+    tag = "webhook",
+    responses(
+        (status = 200, description = "List the webhooks of the user successfully", body = [WebhookResponse]),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_webhooks_handler(depot: &mut Depot) -> WebResult {
+    let webhooks = get_webhook_service(depot)?
+        .get_webhooks_by_owner(get_cookie_user_id(depot)?)
+        .await?
+        .into_iter()
+        .flat_map(|w| w.into_response())
+        .collect::<Vec<_>>();
+
+    Ok(Web::ok("Get webhooks successfully", webhooks))
+}
+
+/// Get a webhook
+#[utoipa::path(
+    get,
+    path = "/webhook/{param_webhook_id}",
+    tag = "webhook",
+    params(
+        ("param_webhook_id" = String, Path, description = "The id of the webhook"),
+    ),
+    responses(
+        (status = 200, description = "Get a webhook successfully", body = WebhookResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn get_webhook_by_id_handler(depot: &mut Depot) -> WebResult {
+    let param_webhook = get_param_webhook(depot)?;
+
+    Ok(Web::ok(
+        "Get webhook successfully",
+        param_webhook.clone().into_response()?,
+    ))
+}
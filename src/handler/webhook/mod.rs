@@ -0,0 +1,5 @@
+pub mod create;
+pub mod delete;
+pub mod delivery;
+pub mod get;
+pub mod update;
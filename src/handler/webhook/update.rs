@@ -0,0 +1,42 @@
+use salvo::{handler, Depot, Request};
+
+use crate::{
+    helper::{
+        body::extract_from_body,
+        depot::{get_param_webhook, get_webhook_service},
+    },
+    request::webhook::update::UpdateWebhookRequest,
+    web::Web,
+    WebResult,
+};
+
+/// Update a webhook
+///
+/// A disabled webhook stops getting new deliveries, and the pending ones are given up on.
+#[utoipa::path(
+    put,
+    path = "/webhook/update/{param_webhook_id}",
+    tag = "webhook",
+    params(
+        ("param_webhook_id" = String, Path, description = "The id of the webhook"),
+    ),
+    request_body = UpdateWebhookRequest,
+    responses(
+        (status = 200, description = "Update a webhook successfully", body = WebhookResponse),
+    ),
+    security(("access_token" = []))
+)]
+#[handler]
+pub async fn update_webhook_handler(req: &mut Request, depot: &mut Depot) -> WebResult {
+    // Extract the data from request
+    let webhook_req = extract_from_body::<UpdateWebhookRequest>(req).await?;
+
+    let webhook_model = webhook_req.into_webhook(get_param_webhook(depot)?)?;
+
+    let updated_webhook = get_webhook_service(depot)?
+        .update_webhook(webhook_model)
+        .await?
+        .into_response()?;
+
+    Ok(Web::ok("Update webhook successfully", updated_webhook))
+}
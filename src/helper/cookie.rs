@@ -8,7 +8,7 @@ use crate::Result;
 
 pub fn get_cookie_user_id(depot: &Depot) -> Result<&ObjectId> {
     let oid = get_cookie_user_id_option(depot)
-        .ok_or_else(|| Error::Permissions("You have to be logged in".into()))?;
+        .ok_or_else(|| Error::Unauthorized("You have to be logged in".into()))?;
     Ok(oid)
 }
 
@@ -19,5 +19,5 @@ pub fn get_cookie_user_id_option(depot: &Depot) -> Option<&ObjectId> {
 pub fn get_cookie_user(depot: &Depot) -> Result<&User> {
     depot
         .get::<User>("cookie_user")
-        .ok_or_else(|| Error::Permissions("You have to be logged in".into()))
+        .ok_or_else(|| Error::Unauthorized("You have to be logged in".into()))
 }
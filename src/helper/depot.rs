@@ -2,10 +2,21 @@ use salvo::Depot;
 
 use crate::{
     aws::S3,
-    base::{file::File, folder::Folder},
+    base::{
+        acl::Access, file::File, file_request::FileRequest, folder::Folder, share_link::ShareLink,
+        team::Team, webhook::Webhook,
+    },
+    helper::{
+        event_bus::EventBus,
+        rate_limit::{ClientIp, LoginThrottle},
+    },
     service::{
-        file_service::FileService, file_version_service::FileVersionService,
-        folder_service::FolderService, user_service::UserService,
+        account_service::AccountService, acl_service::AclService, change_service::ChangeService,
+        file_request_service::FileRequestService, file_service::FileService,
+        file_version_service::FileVersionService, folder_service::FolderService,
+        oidc_service::OidcService, search_service::SearchService,
+        share_link_service::ShareLinkService, team_service::TeamService, user_service::UserService,
+        webhook_service::WebhookService,
     },
     Result,
 };
@@ -55,6 +66,54 @@ pub fn get_file_version_service(depot: &Depot) -> Result<&FileVersionService> {
     extract_from_depot(depot, "file_version_service")
 }
 
+pub fn get_account_service(depot: &Depot) -> Result<&AccountService> {
+    extract_from_depot(depot, "account_service")
+}
+
+pub fn get_login_throttle(depot: &Depot) -> Result<&LoginThrottle> {
+    extract_from_depot(depot, "login_throttle")
+}
+
+pub fn get_client_ip(depot: &Depot) -> Result<&ClientIp> {
+    extract_from_depot(depot, "client_ip")
+}
+
+pub fn get_event_bus(depot: &Depot) -> Result<&EventBus> {
+    extract_from_depot(depot, "event_bus")
+}
+
+pub fn get_oidc_service(depot: &Depot) -> Result<&OidcService> {
+    extract_from_depot(depot, "oidc_service")
+}
+
+pub fn get_search_service(depot: &Depot) -> Result<&SearchService> {
+    extract_from_depot(depot, "search_service")
+}
+
+pub fn get_acl_service(depot: &Depot) -> Result<&AclService> {
+    extract_from_depot(depot, "acl_service")
+}
+
+pub fn get_team_service(depot: &Depot) -> Result<&TeamService> {
+    extract_from_depot(depot, "team_service")
+}
+
+pub fn get_share_link_service(depot: &Depot) -> Result<&ShareLinkService> {
+    extract_from_depot(depot, "share_link_service")
+}
+
+pub fn get_file_request_service(depot: &Depot) -> Result<&FileRequestService> {
+    extract_from_depot(depot, "file_request_service")
+}
+
+pub fn get_change_service(depot: &Depot) -> Result<&ChangeService> {
+    extract_from_depot(depot, "change_service")
+}
+
+pub fn get_webhook_service(depot: &Depot) -> Result<&WebhookService> {
+    extract_from_depot(depot, "webhook_service")
+}
+
 pub fn get_param_file(depot: &Depot) -> Result<&File> {
     extract_from_depot(depot, "param_file")
 }
@@ -62,3 +121,35 @@ pub fn get_param_file(depot: &Depot) -> Result<&File> {
 pub fn get_param_folder(depot: &Depot) -> Result<&Folder> {
     extract_from_depot(depot, "param_folder")
 }
+
+// What the user can do with the param file or the param folder
+pub fn get_param_access(depot: &Depot) -> Result<&Access> {
+    extract_from_depot(depot, "param_access")
+}
+
+pub fn get_param_team(depot: &Depot) -> Result<&Team> {
+    extract_from_depot(depot, "param_team")
+}
+
+// The link is only in the depot after its password has been checked
+pub fn get_param_link(depot: &Depot) -> Result<&ShareLink> {
+    extract_from_depot(depot, "param_link")
+}
+
+// The folder that the param link exposes
+pub fn get_param_link_folder(depot: &Depot) -> Result<&Folder> {
+    extract_from_depot(depot, "param_link_folder")
+}
+
+pub fn get_param_file_request(depot: &Depot) -> Result<&FileRequest> {
+    extract_from_depot(depot, "param_file_request")
+}
+
+// The folder that receives the uploads of the param file request
+pub fn get_param_file_request_folder(depot: &Depot) -> Result<&Folder> {
+    extract_from_depot(depot, "param_file_request_folder")
+}
+
+pub fn get_param_webhook(depot: &Depot) -> Result<&Webhook> {
+    extract_from_depot(depot, "param_webhook")
+}
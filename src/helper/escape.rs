@@ -0,0 +1,96 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use salvo::http::HeaderValue;
+
+use crate::Result;
+
+// S3 expects the copy source to be URL encoded, the slashes between the bucket and the key stay as they are
+const COPY_SOURCE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+// The characters allowed in an RFC 5987 value without being encoded
+const ATTR_CHAR: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
+// Positions and fullpaths are used as prefixes in MongoDB regexes,
+// names like Q1 (draft).txt would otherwise be read as a regex
+pub fn escape_regex(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len());
+    for c in str.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub fn encode_copy_source(bucket_name: &str, key: &str) -> String {
+    utf8_percent_encode(&format!("{bucket_name}/{key}"), COPY_SOURCE).to_string()
+}
+
+// Builds the Content-Disposition header for downloading a file
+// Old clients only read filename, so it gets an ASCII version of the name,
+// newer clients read filename* which keeps the real name in UTF-8
+pub fn content_disposition(full_filename: &str) -> Result<HeaderValue> {
+    let fallback = full_filename
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    let encoded = utf8_percent_encode(full_filename, ATTR_CHAR);
+    let value = format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}");
+    HeaderValue::from_str(&value).map_err(|e| e.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_every_regex_metacharacter() {
+        assert_eq!(escape_regex("alice/Q1 (draft)/"), r"alice/Q1 \(draft\)/");
+        assert_eq!(
+            escape_regex(r"a.b*c+d?e|f^g$h[i]j{k}l\m"),
+            r"a\.b\*c\+d\?e\|f\^g\$h\[i\]j\{k\}l\\m"
+        );
+        // Everything else stays as it is, the non-ASCII names too
+        assert_eq!(escape_regex("résumé/日本語/"), "résumé/日本語/");
+    }
+
+    #[test]
+    fn keeps_quotes_and_line_breaks_out_of_the_header() {
+        let header = content_disposition("a\"b\\c\r\nSet-Cookie: x=1.txt").unwrap();
+        assert_eq!(
+            header.to_str().unwrap(),
+            "attachment; filename=\"a_b_c__Set-Cookie: x=1.txt\"; \
+             filename*=UTF-8''a%22b%5Cc%0D%0ASet-Cookie%3A%20x%3D1.txt"
+        );
+    }
+
+    #[test]
+    fn keeps_the_real_name_in_utf8() {
+        let header = content_disposition("résumé 日本.pdf").unwrap();
+        assert_eq!(
+            header.to_str().unwrap(),
+            "attachment; filename=\"r_sum_ __.pdf\"; \
+             filename*=UTF-8''r%C3%A9sum%C3%A9%20%E6%97%A5%E6%9C%AC.pdf"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+use crate::base::event::Event;
+
+// How many events a slow client can fall behind before it misses some
+const CAPACITY: usize = 1024;
+
+// The services publish what changed here, and every open /events stream subscribes to it
+// It lives in memory, so the clients only hear about the changes made on the same instance
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: Sender<Event>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Self { sender }
+    }
+}
+
+impl EventBus {
+    pub fn publish(&self, event: Event) {
+        // Nobody listening is not an error, the event is simply dropped
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+
+use crate::{config::UploadsConfig, Result};
+
+// Decides which file extensions can be uploaded
+// Both lists come from the config, or the environment, like ALLOWED_EXTENSIONS=pdf,docx,csv
+// Without an allow list, everything that is not denied is allowed
+// Files without an extension are written as an empty entry, so ALLOWED_EXTENSIONS=txt, also allows them
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionPolicy {
+    allowed: Option<HashSet<String>>,
+    denied: HashSet<String>,
+}
+
+fn parse_list(list: &[String]) -> HashSet<String> {
+    list.iter()
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .collect()
+}
+
+impl ExtensionPolicy {
+    pub fn init(config: &UploadsConfig) -> Self {
+        Self {
+            allowed: config.allowed_extensions.as_deref().map(parse_list),
+            denied: parse_list(&config.denied_extensions)
+                .into_iter()
+                .filter(|e| !e.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_allowed(&self, extension: &str) -> bool {
+        let extension = extension.to_lowercase();
+        if self.denied.contains(&extension) {
+            return false;
+        }
+        match &self.allowed {
+            Some(allowed) => allowed.contains(&extension),
+            None => true,
+        }
+    }
+
+    // The extension from the name is checked against both lists
+    // The extension detected from the content is only checked against the deny list,
+    // so renaming a denied file does not get it through
+    pub fn check(&self, extension: &str, detected_extension: Option<&str>) -> Result<()> {
+        if !self.is_allowed(extension) {
+            return Err(match extension.is_empty() {
+                true => "Files without an extension are not allowed".into(),
+                false => format!("The extension {extension} is not allowed").into(),
+            });
+        }
+        if let Some(detected) = detected_extension {
+            if self.denied.contains(&detected.to_lowercase()) {
+                return Err(format!("The content of a {detected} file is not allowed").into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::file::split_full_filename;
+
+    fn policy(allowed: Option<&[&str]>, denied: &[&str]) -> ExtensionPolicy {
+        let list = |l: &[&str]| l.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        ExtensionPolicy::init(&UploadsConfig {
+            allowed_extensions: allowed.map(list),
+            denied_extensions: list(denied),
+        })
+    }
+
+    #[test]
+    fn allows_everything_that_is_not_denied_without_an_allow_list() {
+        let policy = policy(None, &["exe", ".BAT"]);
+
+        assert!(policy.is_allowed("pdf"));
+        assert!(policy.is_allowed(""));
+        assert!(!policy.is_allowed("exe"));
+        // The lists and the names are compared without the case or a leading dot
+        assert!(!policy.is_allowed("EXE"));
+        assert!(!policy.is_allowed("bat"));
+    }
+
+    #[test]
+    fn only_allows_the_listed_extensions() {
+        let policy = policy(Some(&["pdf", " Docx ", ""]), &["pdf"]);
+
+        assert!(policy.is_allowed("DOCX"));
+        assert!(policy.is_allowed(""));
+        assert!(!policy.is_allowed("csv"));
+        // Denied wins over allowed
+        assert!(!policy.is_allowed("pdf"));
+
+        assert!(policy.check("docx", None).is_ok());
+        assert!(policy.check("csv", None).is_err());
+        assert!(self::policy(Some(&["pdf"]), &[]).check("", None).is_err());
+    }
+
+    // A renamed executable is caught by its content
+    #[test]
+    fn checks_the_detected_extension_against_the_deny_list() {
+        let policy = policy(Some(&["txt"]), &["exe"]);
+
+        assert!(policy.check("txt", Some("EXE")).is_err());
+        assert!(policy.check("txt", Some("pdf")).is_ok());
+    }
+
+    #[test]
+    fn splits_the_extension_off_the_name() {
+        assert_eq!(split_full_filename("report.pdf"), ("report", "pdf"));
+        assert_eq!(split_full_filename("archive.tar.gz"), ("archive.tar", "gz"));
+        assert_eq!(split_full_filename("Photo.JPG"), ("Photo", "JPG"));
+        assert_eq!(split_full_filename("README"), ("README", ""));
+        assert_eq!(split_full_filename(".env"), (".env", ""));
+        assert_eq!(split_full_filename("trailing."), ("trailing.", ""));
+        assert_eq!(
+            split_full_filename("Meeting notes v1.final draft"),
+            ("Meeting notes v1.final draft", "")
+        );
+        assert_eq!(
+            split_full_filename("backup.abcdefghijklmnopq"),
+            ("backup.abcdefghijklmnopq", "")
+        );
+    }
+}
@@ -1,9 +1,7 @@
 use crate::Result;
 use salvo::{http::form::FilePart, Request};
 
-pub async fn get_file_from_req<'a>(
-    req: &'_ mut Request,
-) -> Result<&'_ FilePart> {
+pub async fn get_file_from_req(req: &'_ mut Request) -> Result<&'_ FilePart> {
     let file = req
         .file("file")
         .await
@@ -11,8 +9,6 @@ pub async fn get_file_from_req<'a>(
     Ok(file)
 }
 
-pub async fn get_file_from_req_option<'a>(
-    req: &'_ mut Request,
-) -> Option<&'_ FilePart> {
+pub async fn get_file_from_req_option(req: &'_ mut Request) -> Option<&'_ FilePart> {
     req.file("file").await
 }
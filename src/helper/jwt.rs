@@ -1,9 +1,20 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::OnceLock};
 
-use crate::{base::user::User, error::Error, Result};
+use crate::{
+    base::user::User,
+    config::{JwtConfig, TokenConfig},
+    error::Error,
+    helper::into_string,
+    response::jwks::PublicJwk,
+    Result,
+};
 use chrono::Utc;
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use mongodb::bson::oid::ObjectId;
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -11,59 +22,439 @@ pub struct Claims {
     sub: String,
     name: String,
     exp: usize,
+    // The kind of token, so that one kind never passes as another, even if the keys were the same
+    // The tokens without it are refused, which logs out the sessions from before it was added
+    typ: String,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum JwtType {
     Access,
     Refresh,
+    // Proves that the password was right, while the second factor is still missing
+    Challenge,
 }
 
-pub fn encode_jwt(user: &User, token_type: JwtType) -> Result<String> {
-    let jwt_secret = match token_type {
-        JwtType::Access => std::env::var("JWT_ACCESS").map_err(Error::Var)?,
-        JwtType::Refresh => std::env::var("JWT_REFRESH").map_err(Error::Var)?,
-    };
+impl JwtType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JwtType::Access => "access",
+            JwtType::Refresh => "refresh",
+            JwtType::Challenge => "challenge",
+        }
+    }
 
-    let duration = match token_type {
-        JwtType::Access => chrono::Duration::minutes(30),
-        JwtType::Refresh => chrono::Duration::hours(2),
-    };
+    pub fn env_name(self) -> &'static str {
+        match self {
+            JwtType::Access => "JWT_ACCESS",
+            JwtType::Refresh => "JWT_REFRESH",
+            JwtType::Challenge => "JWT_CHALLENGE",
+        }
+    }
+}
+
+// One signing key, found by the kid in the header of the tokens
+struct JwtKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    // Only the asymmetric keys can be shared with the other services
+    public: Option<PublicJwk>,
+}
+
+impl JwtKey {
+    fn secret(kid: &str, algorithm: Algorithm, secret: &str) -> Self {
+        Self {
+            kid: kid.to_string(),
+            algorithm,
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            public: None,
+        }
+    }
+
+    // kid:algorithm:source, where the source is the secret for HS* and the path to a PEM private key otherwise
+    fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.splitn(3, ':');
+        let (Some(kid), Some(algorithm), Some(source)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("The jwt key {spec} is not kid:algorithm:source").into());
+        };
+        let algorithm = Algorithm::from_str(algorithm)?;
+
+        match algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                Ok(Self::secret(kid, algorithm, source))
+            }
+            Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512 => {
+                let bytes = std::fs::read(source)?;
+                let pem = pem::parse(&bytes).map_err(into_string)?;
+                let key_pair = match pem.tag.as_str() {
+                    "PRIVATE KEY" => RsaKeyPair::from_pkcs8(&pem.contents),
+                    _ => RsaKeyPair::from_der(&pem.contents),
+                }
+                .map_err(into_string)?;
+                let public_key = key_pair.public_key();
+                let modulus = public_key.modulus();
+                let exponent = public_key.exponent();
+                let (n, e) = (
+                    modulus.big_endian_without_leading_zero(),
+                    exponent.big_endian_without_leading_zero(),
+                );
+
+                Ok(Self {
+                    kid: kid.to_string(),
+                    algorithm,
+                    encoding: EncodingKey::from_rsa_pem(&bytes)?,
+                    decoding: DecodingKey::from_rsa_raw_components(n, e),
+                    public: Some(PublicJwk::rsa(
+                        kid,
+                        algorithm,
+                        &BASE64URL_NOPAD.encode(n),
+                        &BASE64URL_NOPAD.encode(e),
+                    )),
+                })
+            }
+            Algorithm::EdDSA => {
+                let bytes = std::fs::read(source)?;
+                let pem = pem::parse(&bytes).map_err(into_string)?;
+                // openssl writes the keys without the public part, which the checked parser refuses
+                let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pem.contents)
+                    .map_err(into_string)?;
+                let public_key = key_pair.public_key().as_ref();
+
+                Ok(Self {
+                    kid: kid.to_string(),
+                    algorithm,
+                    encoding: EncodingKey::from_ed_pem(&bytes)?,
+                    decoding: DecodingKey::from_ed_der(public_key),
+                    public: Some(PublicJwk::ed25519(kid, &BASE64URL_NOPAD.encode(public_key))),
+                })
+            }
+            _ => Err(format!("The jwt algorithm {algorithm:?} is not supported").into()),
+        }
+    }
+}
+
+// The first key signs the new tokens, the others are only there to verify the tokens they signed before
+// JWT_ACCESS_KEYS=2025:EdDSA:keys/access-2025.pem,2024:HS512:the-old-secret
+pub struct Keyring {
+    keys: Vec<JwtKey>,
+    // In seconds
+    lifetime: i64,
+}
+
+impl Keyring {
+    fn init(token_type: JwtType, config: &TokenConfig) -> Result<Self> {
+        let mut keys = config
+            .keys
+            .iter()
+            .map(|spec| spec.trim())
+            .filter(|spec| !spec.is_empty())
+            .map(|spec| {
+                JwtKey::parse(spec).map_err(|e| {
+                    let kid = spec.split(':').next().unwrap_or_default();
+                    format!("Cannot load the jwt key {kid}: {e}").into()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The single secret from before the keyring, it only signs when there are no other keys,
+        // and keeps the tokens from before the rotation valid until they expire
+        if let Some(secret) = &config.secret {
+            keys.push(JwtKey::secret("default", Algorithm::HS512, secret));
+        }
 
-    let expiration = Utc::now()
-        .checked_add_signed(duration)
-        .ok_or("Cannot create the duration for jwt")?
-        .timestamp();
+        if keys.is_empty() {
+            let name = token_type.env_name();
+            return Err(format!("{name}_KEYS does not have any key").into());
+        }
 
-    let claims = Claims {
-        sub: user.id.to_string(),
-        name: user.username.clone(),
-        exp: expiration as usize,
+        Ok(Self {
+            keys,
+            lifetime: config.lifetime(token_type),
+        })
+    }
+
+    fn signing_key(&self) -> &JwtKey {
+        &self.keys[0]
+    }
+
+    fn find(&self, kid: Option<&str>) -> Option<&JwtKey> {
+        match kid {
+            Some(kid) => self.keys.iter().find(|key| key.kid == kid),
+            // The tokens from before the keyring have no kid
+            None => self.keys.iter().find(|key| key.kid == "default"),
+        }
+    }
+}
+
+pub struct Keyrings {
+    access: Keyring,
+    refresh: Keyring,
+    challenge: Keyring,
+}
+
+impl Keyrings {
+    fn get(&self, token_type: JwtType) -> &Keyring {
+        match token_type {
+            JwtType::Access => &self.access,
+            JwtType::Refresh => &self.refresh,
+            JwtType::Challenge => &self.challenge,
+        }
+    }
+}
+
+static KEYRINGS: OnceLock<Keyrings> = OnceLock::new();
+
+// Loads the keys once at the start, so a broken key stops the server instead of every login
+pub fn init_keyrings(config: &JwtConfig) -> Result<()> {
+    let keyrings = Keyrings {
+        access: Keyring::init(JwtType::Access, &config.access)?,
+        refresh: Keyring::init(JwtType::Refresh, &config.refresh)?,
+        challenge: Keyring::init(JwtType::Challenge, &config.challenge)?,
     };
 
-    let header = Header::new(Algorithm::HS512);
+    KEYRINGS
+        .set(keyrings)
+        .map_err(|_| "The jwt keys are already loaded")?;
+    Ok(())
+}
+
+fn get_keyring(token_type: JwtType) -> Result<&'static Keyring> {
+    Ok(KEYRINGS
+        .get()
+        .ok_or("The jwt keys are not loaded")?
+        .get(token_type))
+}
+
+// How long the tokens stay valid, in seconds, the cookies live as long as them
+pub fn get_lifetime(token_type: JwtType) -> Result<i64> {
+    Ok(get_keyring(token_type)?.lifetime)
+}
 
-    let jwt = encode(
-        &header,
-        &claims,
-        &EncodingKey::from_secret(jwt_secret.as_bytes()),
-    )?;
+// The public part of the access keys, so the other services can verify the access tokens
+pub fn get_access_jwks() -> Result<Vec<PublicJwk>> {
+    Ok(get_keyring(JwtType::Access)?
+        .keys
+        .iter()
+        .filter_map(|key| key.public.clone())
+        .collect())
+}
 
-    Ok(jwt)
+pub fn encode_jwt(user: &User, token_type: JwtType) -> Result<String> {
+    get_keyring(token_type)?.encode(user, token_type)
 }
 
 pub fn decode_jwt(jwt: String, token_type: JwtType) -> Result<ObjectId> {
-    let jwt_secret = match token_type {
-        JwtType::Access => std::env::var("JWT_ACCESS").map_err(Error::Var)?,
-        JwtType::Refresh => std::env::var("JWT_REFRESH").map_err(Error::Var)?,
-    };
+    get_keyring(token_type)?.decode(&jwt, token_type)
+}
+
+impl Keyring {
+    fn encode(&self, user: &User, token_type: JwtType) -> Result<String> {
+        let key = self.signing_key();
+
+        let duration = chrono::Duration::seconds(self.lifetime);
+
+        let expiration = Utc::now()
+            .checked_add_signed(duration)
+            .ok_or("Cannot create the duration for jwt")?
+            .timestamp();
+
+        let claims = Claims {
+            sub: user.id.to_string(),
+            name: user.username.clone(),
+            exp: expiration as usize,
+            typ: token_type.as_str().to_string(),
+        };
+
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+
+        let jwt = encode(&header, &claims, &key.encoding)?;
+
+        Ok(jwt)
+    }
+
+    fn decode(&self, jwt: &str, token_type: JwtType) -> Result<ObjectId> {
+        let invalid = || Error::Jwt(jsonwebtoken::errors::ErrorKind::InvalidToken.into());
+
+        let header = decode_header(jwt).map_err(Error::Jwt)?;
+        let key = self.find(header.kid.as_deref()).ok_or_else(invalid)?;
+
+        // The algorithm comes from the key, never from the header of the token
+        let decoded = decode::<Claims>(jwt, &key.decoding, &Validation::new(key.algorithm))
+            .map_err(Error::Jwt)?;
+        if decoded.claims.typ != token_type.as_str() {
+            return Err(invalid());
+        }
+
+        let oid = ObjectId::from_str(&decoded.claims.sub)?;
+        Ok(oid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> User {
+        User::new(
+            ObjectId::new(),
+            "alice",
+            "alice@example.com",
+            "Passw0rd!",
+            "",
+            None,
+        )
+        .unwrap()
+    }
+
+    fn keyring(token_type: JwtType, keys: &[&str]) -> Keyring {
+        let config = TokenConfig {
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            ..Default::default()
+        };
+        Keyring::init(token_type, &config).unwrap()
+    }
+
+    // Even with the same key, which the config refuses anyway, a challenge is not an access token
+    #[test]
+    fn refuses_a_token_of_another_kind() {
+        let user = user();
+        let challenge = keyring(JwtType::Challenge, &["k1:HS256:shared-secret"]);
+        let access = keyring(JwtType::Access, &["k1:HS256:shared-secret"]);
+
+        let token = challenge.encode(&user, JwtType::Challenge).unwrap();
+
+        assert_eq!(
+            challenge.decode(&token, JwtType::Challenge).unwrap(),
+            user.id
+        );
+        assert!(access.decode(&token, JwtType::Access).is_err());
+    }
+
+    const RSA_KEY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/oidc-rsa.pem");
+
+    #[test]
+    fn parses_the_keys_of_every_kind() {
+        let hmac = JwtKey::parse("2024:HS512:a:secret:with:colons").unwrap();
+        assert_eq!(
+            (hmac.kid.as_str(), hmac.algorithm),
+            ("2024", Algorithm::HS512)
+        );
+        assert!(hmac.public.is_none());
+
+        let rsa = JwtKey::parse(&format!("2025:RS256:{RSA_KEY}")).unwrap();
+        assert_eq!(rsa.algorithm, Algorithm::RS256);
+        assert!(rsa.public.is_some());
+
+        // openssl genpkey -algorithm ed25519 gives the same PKCS#8 document
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            pem::encode(&pem::Pem {
+                tag: "PRIVATE KEY".to_string(),
+                contents: pkcs8.as_ref().to_vec(),
+            }),
+        )
+        .unwrap();
+        let ed = JwtKey::parse(&format!("2026:EdDSA:{}", file.path().display())).unwrap();
+        assert_eq!(ed.algorithm, Algorithm::EdDSA);
+        assert!(ed.public.is_some());
+    }
+
+    #[test]
+    fn refuses_broken_keys() {
+        assert!(JwtKey::parse("only-a-kid").is_err());
+        assert!(JwtKey::parse("k1:HS999:secret").is_err());
+        assert!(JwtKey::parse("k1:ES256:keys/ec.pem").is_err());
+        assert!(JwtKey::parse("k1:RS256:/does/not/exist.pem").is_err());
+
+        let config = TokenConfig {
+            keys: vec![" ".to_string()],
+            ..Default::default()
+        };
+        assert!(Keyring::init(JwtType::Access, &config).is_err());
+    }
+
+    #[test]
+    fn signs_with_the_first_key_and_keeps_the_old_secret_last() {
+        let config = TokenConfig {
+            keys: vec![
+                "new:HS256:new-secret".to_string(),
+                format!("rsa:RS256:{RSA_KEY}"),
+            ],
+            secret: Some("legacy-secret".to_string()),
+            ..Default::default()
+        };
+        let keyring = Keyring::init(JwtType::Access, &config).unwrap();
+
+        let kids = keyring
+            .keys
+            .iter()
+            .map(|k| k.kid.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(kids, ["new", "rsa", "default"]);
+        assert_eq!(keyring.signing_key().kid, "new");
+    }
+
+    // The token of the old key stays valid after the new key is put in front of it
+    #[test]
+    fn verifies_a_token_signed_with_a_rotated_out_key() {
+        let user = user();
+        let before = keyring(JwtType::Access, &["2024:HS512:old-secret"]);
+        let token = before.encode(&user, JwtType::Access).unwrap();
+
+        let after = keyring(
+            JwtType::Access,
+            &["2025:HS512:new-secret", "2024:HS512:old-secret"],
+        );
+        assert_eq!(after.decode(&token, JwtType::Access).unwrap(), user.id);
+        assert_eq!(
+            decode_header(&after.encode(&user, JwtType::Access).unwrap())
+                .unwrap()
+                .kid
+                .as_deref(),
+            Some("2025")
+        );
+
+        // Once the old key is dropped, its tokens are done
+        let dropped = keyring(JwtType::Access, &["2025:HS512:new-secret"]);
+        assert!(dropped.decode(&token, JwtType::Access).is_err());
+    }
+
+    #[test]
+    fn refuses_an_unknown_kid() {
+        let user = user();
+        let keyring = keyring(JwtType::Access, &["k1:HS256:secret"]);
+        let claims = Claims {
+            sub: user.id.to_string(),
+            name: user.username.clone(),
+            exp: (Utc::now().timestamp() + 60) as usize,
+            typ: "access".to_string(),
+        };
 
-    let decoded = decode::<Claims>(
-        &jwt,
-        &DecodingKey::from_secret(jwt_secret.as_bytes()),
-        &Validation::new(Algorithm::HS512),
-    )
-    .map_err(Error::Jwt)?;
+        // Signed with the right secret, but under a kid that the keyring does not have
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("k2".to_string());
+        let token = encode(&header, &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+        assert!(keyring.decode(&token, JwtType::Access).is_err());
 
-    let oid = ObjectId::from_str(&decoded.claims.sub)?;
-    Ok(oid)
+        // Without a kid, only the old secret is tried, and there is none here
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        assert!(keyring.decode(&token, JwtType::Access).is_err());
+    }
 }
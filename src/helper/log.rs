@@ -0,0 +1,11 @@
+use tracing_subscriber::EnvFilter;
+
+// Everything that the server and the admin tool log goes through tracing, to the standard error
+// The level comes from RUST_LOG, like RUST_LOG=final_project=debug, and is info otherwise
+pub fn init_logging() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
@@ -0,0 +1,20 @@
+// Works out the MIME type of a file
+// The content is trusted first, since the name can be anything the user wants
+// Text files have no magic bytes, so the name is used for them, like csv or json
+pub fn detect_mime(full_filename: &str, data: &[u8]) -> String {
+    if let Some(kind) = infer::get(data) {
+        return kind.mime_type().to_string();
+    }
+    if let Some(mime) = mime_guess::from_path(full_filename).first() {
+        return mime.to_string();
+    }
+    if !data.is_empty() && std::str::from_utf8(data).is_ok() {
+        return "text/plain".to_string();
+    }
+    "application/octet-stream".to_string()
+}
+
+// The extension that the content really has, whatever the name says
+pub fn detect_extension(data: &[u8]) -> Option<&'static str> {
+    infer::get(data).map(|kind| kind.extension())
+}
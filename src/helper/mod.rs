@@ -1,13 +1,24 @@
 pub mod body;
 pub mod cookie;
 pub mod depot;
+pub mod escape;
+pub mod event_bus;
+pub mod extension_policy;
 pub mod file;
 pub mod form;
+pub mod hash;
 pub mod jwt;
+pub mod log;
 pub mod make_error;
+pub mod mime;
+pub mod oidc;
 pub mod param;
 pub mod position;
 pub mod print_validation;
+pub mod rate_limit;
+pub mod snippet;
+pub mod totp;
+pub mod webhook;
 
 pub fn into_string<T: ToString>(item: T) -> String {
     item.to_string()
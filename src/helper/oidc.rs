@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
+use reqwest::Url;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{config::OidcProviderConfig, helper::into_string, Result};
+
+// One identity provider, from the oidc section of the config
+// The issuer can be a local mock provider, it only has to serve the discovery document
+#[derive(Debug, Clone)]
+pub struct OidcProvider {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    // Public clients rely on PKCE alone and have no secret
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: String,
+}
+
+impl OidcProvider {
+    fn init(name: &str, config: &OidcProviderConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            issuer: config.issuer.clone(),
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+            redirect_uri: config.redirect_uri.clone(),
+            scopes: config
+                .scopes
+                .clone()
+                .unwrap_or_else(|| "openid email profile".to_string()),
+        }
+    }
+}
+
+// Without any provider, single sign-on is turned off
+pub fn init_providers(
+    config: &HashMap<String, OidcProviderConfig>,
+) -> HashMap<String, OidcProvider> {
+    config
+        .iter()
+        .map(|(name, provider)| (name.clone(), OidcProvider::init(name, provider)))
+        .collect()
+}
+
+// The endpoints of the provider, from its discovery document
+#[derive(Debug, Clone, Deserialize)]
+pub struct Discovery {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Jwk {
+    kid: Option<String>,
+    kty: String,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+// The claims of the ID token that are used to find or create the user
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+    pub nonce: Option<String>,
+}
+
+// PKCE with S256, the challenge goes to the provider now, the verifier with the code later
+pub fn code_challenge(code_verifier: &str) -> String {
+    BASE64URL_NOPAD.encode(&Sha256::digest(code_verifier.as_bytes()))
+}
+
+pub async fn discover(provider: &OidcProvider) -> Result<Discovery> {
+    // The issuer has to match the token exactly, so the slash is only trimmed here
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        provider.issuer.trim_end_matches('/')
+    );
+    reqwest::get(url)
+        .await
+        .map_err(into_string)?
+        .error_for_status()
+        .map_err(into_string)?
+        .json()
+        .await
+        .map_err(|e| e.to_string().into())
+}
+
+pub fn authorization_url(
+    provider: &OidcProvider,
+    discovery: &Discovery,
+    state: &str,
+    nonce: &str,
+    code_verifier: &str,
+) -> Result<String> {
+    let url = Url::parse_with_params(
+        &discovery.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", &provider.client_id),
+            ("redirect_uri", &provider.redirect_uri),
+            ("scope", &provider.scopes),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", &code_challenge(code_verifier)),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(into_string)?;
+    Ok(url.to_string())
+}
+
+// Trades the authorization code for the ID token
+pub async fn exchange_code(
+    provider: &OidcProvider,
+    discovery: &Discovery,
+    code: &str,
+    code_verifier: &str,
+) -> Result<String> {
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", &provider.redirect_uri),
+        ("client_id", &provider.client_id),
+        ("code_verifier", code_verifier),
+    ];
+    if let Some(client_secret) = &provider.client_secret {
+        form.push(("client_secret", client_secret));
+    }
+
+    let token = reqwest::Client::new()
+        .post(&discovery.token_endpoint)
+        .form(&form)
+        .send()
+        .await
+        .map_err(into_string)?
+        .error_for_status()
+        .map_err(into_string)?
+        .json::<TokenResponse>()
+        .await
+        .map_err(into_string)?;
+    Ok(token.id_token)
+}
+
+// Checks the signature against the keys of the provider, then the issuer, the audience and the expiry,
+// and that the token was made for the login with this nonce
+pub async fn verify_id_token(
+    provider: &OidcProvider,
+    discovery: &Discovery,
+    id_token: &str,
+    nonce: &str,
+) -> Result<IdClaims> {
+    let header = decode_header(id_token)?;
+
+    let jwks = reqwest::get(&discovery.jwks_uri)
+        .await
+        .map_err(into_string)?
+        .error_for_status()
+        .map_err(into_string)?
+        .json::<Jwks>()
+        .await
+        .map_err(into_string)?;
+
+    // Only RSA keys are supported, which is what the providers sign with by default
+    let jwk = jwks
+        .keys
+        .iter()
+        .filter(|k| k.kty == "RSA")
+        .find(|k| header.kid.is_none() || k.kid == header.kid)
+        .ok_or("Cannot find the key that signed the ID token")?;
+    let (Some(n), Some(e)) = (&jwk.n, &jwk.e) else {
+        return Err("The key of the provider is malformed".into());
+    };
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[&provider.client_id]);
+    validation.set_issuer(&[&provider.issuer]);
+
+    let claims = decode::<IdClaims>(
+        id_token,
+        &DecodingKey::from_rsa_components(n, e)?,
+        &validation,
+    )?
+    .claims;
+
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err("The ID token does not belong to this login".into());
+    }
+    Ok(claims)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::Utc;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use ring::signature::{KeyPair, RsaKeyPair};
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    const KEY_PEM: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/testdata/oidc-rsa.pem"
+    ));
+    const KID: &str = "mock";
+    pub const CLIENT_ID: &str = "file-manager";
+
+    // A provider on 127.0.0.1 with the discovery document, the token endpoint and the keys,
+    // the token endpoint answers with whatever ID token was given to it last
+    pub struct MockIdp {
+        pub issuer: String,
+        id_token: Arc<Mutex<String>>,
+    }
+
+    impl MockIdp {
+        pub async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let issuer = format!("http://{}", listener.local_addr().unwrap());
+            let id_token = Arc::new(Mutex::new(String::new()));
+
+            let (server_issuer, server_id_token) = (issuer.clone(), id_token.clone());
+            tokio::spawn(async move {
+                loop {
+                    let (socket, _) = listener.accept().await.unwrap();
+                    let (issuer, id_token) = (server_issuer.clone(), server_id_token.clone());
+                    tokio::spawn(async move { answer(socket, &issuer, &id_token).await });
+                }
+            });
+            Self { issuer, id_token }
+        }
+
+        pub fn provider(&self) -> OidcProvider {
+            OidcProvider {
+                name: "mock".into(),
+                issuer: self.issuer.clone(),
+                client_id: CLIENT_ID.into(),
+                client_secret: None,
+                redirect_uri: "http://localhost:8000/auth/oidc/mock/callback".into(),
+                scopes: "openid email".into(),
+            }
+        }
+
+        // The claims that a real login would have, the test changes the ones it is about
+        pub fn claims(&self, nonce: &str) -> Value {
+            json!({
+                "iss": self.issuer,
+                "aud": CLIENT_ID,
+                "sub": "mock-user-1",
+                "email": "mock@example.com",
+                "email_verified": true,
+                "nonce": nonce,
+                "iat": Utc::now().timestamp(),
+                "exp": Utc::now().timestamp() + 300,
+            })
+        }
+
+        pub fn sign(&self, claims: &Value) -> String {
+            let mut header = Header::new(Algorithm::RS256);
+            header.kid = Some(KID.into());
+            let key = EncodingKey::from_rsa_pem(KEY_PEM.as_bytes()).unwrap();
+            encode(&header, claims, &key).unwrap()
+        }
+
+        pub fn issue(&self, claims: &Value) {
+            *self.id_token.lock().unwrap() = self.sign(claims);
+        }
+    }
+
+    fn jwks() -> Value {
+        let pem = pem::parse(KEY_PEM).unwrap();
+        let key_pair = RsaKeyPair::from_der(&pem.contents).unwrap();
+        let public_key = key_pair.public_key();
+        json!({"keys": [{
+            "kid": KID,
+            "kty": "RSA",
+            "alg": "RS256",
+            "use": "sig",
+            "n": BASE64URL_NOPAD.encode(public_key.modulus().big_endian_without_leading_zero()),
+            "e": BASE64URL_NOPAD.encode(public_key.exponent().big_endian_without_leading_zero()),
+        }]})
+    }
+
+    async fn answer(mut socket: TcpStream, issuer: &str, id_token: &Mutex<String>) {
+        // Only the request line matters, the body of the token request is read and left alone
+        let mut request = Vec::new();
+        let mut buffer = [0; 4096];
+        while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+            let read = socket.read(&mut buffer).await.unwrap();
+            if read == 0 {
+                return;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        let request = String::from_utf8_lossy(&request).to_string();
+        let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+        let body = match path {
+            "/.well-known/openid-configuration" => json!({
+                "issuer": issuer,
+                "authorization_endpoint": format!("{issuer}/authorize"),
+                "token_endpoint": format!("{issuer}/token"),
+                "jwks_uri": format!("{issuer}/jwks"),
+            }),
+            "/token" => json!({"id_token": *id_token.lock().unwrap(), "token_type": "Bearer"}),
+            "/jwks" => jwks(),
+            _ => json!({}),
+        }
+        .to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    }
+
+    // Goes through the discovery, the token endpoint and the keys, like the callback does
+    async fn verify(idp: &MockIdp, claims: &Value, nonce: &str) -> Result<IdClaims> {
+        idp.issue(claims);
+        let provider = idp.provider();
+        let discovery = discover(&provider).await?;
+        let id_token = exchange_code(&provider, &discovery, "code", "verifier").await?;
+        verify_id_token(&provider, &discovery, &id_token, nonce).await
+    }
+
+    #[tokio::test]
+    async fn accepts_a_token_from_the_provider() {
+        let idp = MockIdp::start().await;
+
+        let claims = verify(&idp, &idp.claims("nonce-1"), "nonce-1")
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "mock-user-1");
+        assert_eq!(claims.email.as_deref(), Some("mock@example.com"));
+        assert!(claims.email_verified);
+    }
+
+    #[tokio::test]
+    async fn refuses_a_token_for_another_login() {
+        let idp = MockIdp::start().await;
+
+        assert!(verify(&idp, &idp.claims("nonce-1"), "nonce-2")
+            .await
+            .is_err());
+
+        let mut claims = idp.claims("nonce-1");
+        claims.as_object_mut().unwrap().remove("nonce");
+        assert!(verify(&idp, &claims, "nonce-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refuses_a_token_for_another_client() {
+        let idp = MockIdp::start().await;
+
+        let mut claims = idp.claims("nonce-1");
+        claims["aud"] = json!("another-client");
+        assert!(verify(&idp, &claims, "nonce-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refuses_a_token_from_another_issuer() {
+        let idp = MockIdp::start().await;
+
+        let mut claims = idp.claims("nonce-1");
+        claims["iss"] = json!("https://attacker.example.com");
+        assert!(verify(&idp, &claims, "nonce-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refuses_an_expired_token() {
+        let idp = MockIdp::start().await;
+
+        let mut claims = idp.claims("nonce-1");
+        claims["exp"] = json!(Utc::now().timestamp() - 3600);
+        assert!(verify(&idp, &claims, "nonce-1").await.is_err());
+    }
+}
@@ -24,6 +24,46 @@ pub fn get_param_folder_id(req: &mut Request) -> Result<ObjectId> {
     Ok(param_folder_id)
 }
 
+pub fn get_param_team_id(req: &mut Request) -> Result<ObjectId> {
+    let param_team_id = extract_from_param(req, "param_team_id")?;
+    Ok(param_team_id)
+}
+
+pub fn get_param_link_id(req: &mut Request) -> Result<ObjectId> {
+    let param_link_id = extract_from_param(req, "param_link_id")?;
+    Ok(param_link_id)
+}
+
+pub fn get_param_link_token(req: &mut Request) -> Result<String> {
+    let link_token = extract_from_param(req, "link_token")?;
+    Ok(link_token)
+}
+
+pub fn get_param_file_request_id(req: &mut Request) -> Result<ObjectId> {
+    let param_file_request_id = extract_from_param(req, "param_file_request_id")?;
+    Ok(param_file_request_id)
+}
+
+pub fn get_param_webhook_id(req: &mut Request) -> Result<ObjectId> {
+    let param_webhook_id = extract_from_param(req, "param_webhook_id")?;
+    Ok(param_webhook_id)
+}
+
+pub fn get_param_delivery_id(req: &mut Request) -> Result<ObjectId> {
+    let param_delivery_id = extract_from_param(req, "param_delivery_id")?;
+    Ok(param_delivery_id)
+}
+
+pub fn get_param_drop_token(req: &mut Request) -> Result<String> {
+    let drop_token = extract_from_param(req, "drop_token")?;
+    Ok(drop_token)
+}
+
+pub fn get_param_metadata_key(req: &mut Request) -> Result<String> {
+    let metadata_key = extract_from_param(req, "metadata_key")?;
+    Ok(metadata_key)
+}
+
 pub fn get_param_version_number(req: &mut Request) -> Result<i64> {
     let version_number = extract_from_param(req, "version_number")?;
     Ok(version_number)
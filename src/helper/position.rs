@@ -1,3 +1,5 @@
+use unicode_normalization::UnicodeNormalization;
+
 use crate::validation::file::{check_dir, check_fullpath};
 use crate::Result;
 
@@ -24,3 +26,17 @@ pub fn get_file_position(str: &str) -> Result<String> {
     result += "/";
     Ok(result)
 }
+
+// Names typed on macOS usually come decomposed, while Windows and Linux compose them
+// Everything is stored in NFC, so both of them point to the same file
+pub fn normalize_path(str: &str) -> String {
+    str.nfc().collect()
+}
+
+// Every folder above and including the dir, from the root down
+// alice/a/b/ gives alice/, alice/a/ and alice/a/b/
+pub fn get_ancestor_dirs(dir: &str) -> Vec<String> {
+    dir.match_indices('/')
+        .map(|(i, _)| dir[..=i].to_string())
+        .collect()
+}
@@ -1,20 +1,25 @@
+use serde_json::{json, Map, Value};
 use validator::ValidationErrors;
 
-pub fn extract_validation_error(e: &ValidationErrors) -> String {
-    let mut message = "".to_string();
-    message.insert(0, 'F');
-    for field in e.field_errors() {
-        message.insert_str(message.len(), format!("ield {}: ", field.0).as_str());
-        for field_error in field.1 {
-            if let Some(msg) = &field_error.message {
-                message.insert_str(message.len(), format!("{msg}; and f").as_str())
-            } else {
-                message.insert_str(
-                    message.len(),
-                    format!("{}; and f", field_error.code).as_str(),
-                )
-            }
-        }
-    }
-    message[0..message.len() - 6].to_string()
+// The problems of each field, like {"username": [{"code": "length", "message": "..."}]}
+// The params of the errors are left out, since they hold the value that was sent, like the password
+pub fn extract_validation_error(e: &ValidationErrors) -> Value {
+    let fields = e
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let errors = errors
+                .iter()
+                .map(|error| {
+                    json!({
+                        "code": error.code,
+                        "message": error.message.as_ref().unwrap_or(&error.code),
+                    })
+                })
+                .collect();
+            (field.to_string(), Value::Array(errors))
+        })
+        .collect::<Map<_, _>>();
+
+    Value::Object(fields)
 }
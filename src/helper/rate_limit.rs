@@ -0,0 +1,317 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use salvo::Request;
+
+use crate::{error::Error, helper::into_string, Result};
+
+// Past this many keys, the ones that have not been seen for a while are dropped
+const MAX_KEYS: usize = 10_000;
+
+// Counts the hits of every key over the last window, and refuses the ones over the limit
+// The state lives in memory, so every instance of the server counts on its own
+#[derive(Debug, Clone)]
+pub struct SlidingWindow {
+    limit: usize,
+    window: Duration,
+    hits: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+impl SlidingWindow {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            hits: Arc::default(),
+        }
+    }
+
+    // Records a hit, or tells how many seconds are left until the key can try again
+    pub fn hit(&self, key: &str) -> Result<()> {
+        self.hit_at(key, Instant::now())
+    }
+
+    fn hit_at(&self, key: &str, now: Instant) -> Result<()> {
+        let mut hits = self.hits.lock().map_err(into_string)?;
+
+        if hits.len() > MAX_KEYS {
+            hits.retain(|_, h| h.back().is_some_and(|t| now - *t < self.window));
+        }
+
+        let key_hits = hits.entry(key.to_string()).or_default();
+        while key_hits.front().is_some_and(|t| now - *t >= self.window) {
+            key_hits.pop_front();
+        }
+
+        if let Some(oldest) = key_hits.front().filter(|_| key_hits.len() >= self.limit) {
+            let retry_after = self.window - (now - *oldest);
+            return Err(Error::TooManyRequests(retry_after.as_secs() + 1));
+        }
+
+        key_hits.push_back(now);
+        Ok(())
+    }
+}
+
+// The first failures are free, after that every failure doubles the lockout
+const FREE_ATTEMPTS: u32 = 5;
+const BASE_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+// The failures are forgotten after a day without any
+const FAILURE_MEMORY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone)]
+struct Failures {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+// Locks an account out for longer and longer after repeated failed logins,
+// whichever IP the attempts come from
+#[derive(Debug, Clone, Default)]
+pub struct LoginThrottle {
+    failures: Arc<Mutex<HashMap<String, Failures>>>,
+}
+
+impl LoginThrottle {
+    pub fn check(&self, account: &str) -> Result<()> {
+        self.check_at(account, Instant::now())
+    }
+
+    fn check_at(&self, account: &str, now: Instant) -> Result<()> {
+        let failures = self.failures.lock().map_err(into_string)?;
+
+        match failures.get(account).and_then(|f| f.locked_until) {
+            Some(locked_until) if locked_until > now => {
+                Err(Error::TooManyRequests((locked_until - now).as_secs() + 1))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_failure(&self, account: &str) -> Result<()> {
+        self.record_failure_at(account, Instant::now())
+    }
+
+    fn record_failure_at(&self, account: &str, now: Instant) -> Result<()> {
+        let mut failures = self.failures.lock().map_err(into_string)?;
+
+        if failures.len() > MAX_KEYS {
+            failures.retain(|_, f| now - f.last_failure < FAILURE_MEMORY);
+        }
+
+        let account_failures = failures
+            .entry(account.to_string())
+            .and_modify(|f| {
+                if now - f.last_failure >= FAILURE_MEMORY {
+                    f.count = 0;
+                }
+            })
+            .or_insert(Failures {
+                count: 0,
+                last_failure: now,
+                locked_until: None,
+            });
+
+        account_failures.count += 1;
+        account_failures.last_failure = now;
+
+        if account_failures.count >= FREE_ATTEMPTS {
+            let doublings = (account_failures.count - FREE_ATTEMPTS).min(16);
+            let lockout = (BASE_LOCKOUT * 2u32.pow(doublings)).min(MAX_LOCKOUT);
+            account_failures.locked_until = Some(now + lockout);
+        }
+        Ok(())
+    }
+
+    // A successful login starts the count over
+    pub fn reset(&self, account: &str) -> Result<()> {
+        self.failures.lock().map_err(into_string)?.remove(account);
+        Ok(())
+    }
+}
+
+// Where the address of a client comes from, it is put in the depot for the rate limits
+// Behind a reverse proxy every connection comes from the proxy,
+// so the address is read from the header that the proxy sets instead
+#[derive(Debug, Clone, Default)]
+pub struct ClientIp {
+    trusted_proxy_header: Option<String>,
+}
+
+impl ClientIp {
+    pub fn new(trusted_proxy_header: Option<&str>) -> Self {
+        Self {
+            trusted_proxy_header: trusted_proxy_header.map(|h| h.to_string()),
+        }
+    }
+
+    // The address of the connection when there is no proxy, or the proxy did not set the header
+    pub fn of(&self, req: &Request) -> String {
+        let from_header = self.trusted_proxy_header.as_deref().and_then(|header| {
+            let value = req.header::<String>(header)?;
+            ip_from_header(header, &value)
+        });
+        if let Some(ip) = from_header {
+            return ip.to_string();
+        }
+
+        // The port changes with every connection, only the address is counted
+        match req.remote_addr() {
+            Some(addr) => match (addr.as_ipv4(), addr.as_ipv6()) {
+                (Some(v4), _) => v4.ip().to_string(),
+                (_, Some(v6)) => v6.ip().to_string(),
+                _ => String::new(),
+            },
+            None => String::new(),
+        }
+    }
+}
+
+// The client can put anything in the header before it reaches the proxy, and the proxy appends to it,
+// so only the last address is trusted, the one that the proxy in front of the server added
+// X-Forwarded-For: 203.0.113.7, 198.51.100.2 gives 198.51.100.2
+// Forwarded: for=203.0.113.7, for="[2001:db8::1]:4711" gives 2001:db8::1
+fn ip_from_header(header: &str, value: &str) -> Option<IpAddr> {
+    let last = value.rsplit(',').next()?.trim();
+    let address = match header.eq_ignore_ascii_case("forwarded") {
+        true => last
+            .split(';')
+            .find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("for").then_some(value)
+            })?
+            .trim_matches('"'),
+        false => last,
+    };
+    address
+        .parse::<IpAddr>()
+        .or_else(|_| address.parse::<SocketAddr>().map(|a| a.ip()))
+        .or_else(|_| {
+            address
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse()
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry_after(result: Result<()>) -> u64 {
+        match result {
+            Err(Error::TooManyRequests(seconds)) => seconds,
+            other => panic!("expected too many requests, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn refuses_the_hits_over_the_limit_until_the_window_slides() {
+        let window = SlidingWindow::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        window.hit_at("a", start).unwrap();
+        window.hit_at("a", start + Duration::from_secs(10)).unwrap();
+        let seconds = retry_after(window.hit_at("a", start + Duration::from_secs(20)));
+        assert_eq!(seconds, 41);
+
+        // Every key has its own window
+        window.hit_at("b", start + Duration::from_secs(20)).unwrap();
+
+        // The first hit is out of the window, so there is room for one more
+        window.hit_at("a", start + Duration::from_secs(60)).unwrap();
+        assert!(window.hit_at("a", start + Duration::from_secs(61)).is_err());
+    }
+
+    #[test]
+    fn locks_out_for_longer_after_every_failure() {
+        let throttle = LoginThrottle::default();
+        let start = Instant::now();
+
+        for _ in 1..FREE_ATTEMPTS {
+            throttle.record_failure_at("alice", start).unwrap();
+            throttle.check_at("alice", start).unwrap();
+        }
+
+        throttle.record_failure_at("alice", start).unwrap();
+        assert_eq!(retry_after(throttle.check_at("alice", start)), 31);
+        throttle.check_at("alice", start + BASE_LOCKOUT).unwrap();
+        throttle.check_at("bob", start).unwrap();
+
+        throttle.record_failure_at("alice", start).unwrap();
+        assert_eq!(retry_after(throttle.check_at("alice", start)), 61);
+
+        throttle.record_failure_at("alice", start).unwrap();
+        assert_eq!(retry_after(throttle.check_at("alice", start)), 121);
+    }
+
+    #[test]
+    fn caps_the_lockout() {
+        let throttle = LoginThrottle::default();
+        let start = Instant::now();
+
+        for _ in 0..50 {
+            throttle.record_failure_at("alice", start).unwrap();
+        }
+        assert_eq!(
+            retry_after(throttle.check_at("alice", start)),
+            MAX_LOCKOUT.as_secs() + 1
+        );
+
+        // A successful login starts over
+        throttle.reset("alice").unwrap();
+        throttle.check_at("alice", start).unwrap();
+    }
+
+    #[test]
+    fn forgets_the_failures_after_a_day() {
+        let throttle = LoginThrottle::default();
+        let start = Instant::now();
+
+        for _ in 0..FREE_ATTEMPTS {
+            throttle.record_failure_at("alice", start).unwrap();
+        }
+        let later = start + FAILURE_MEMORY;
+        throttle.record_failure_at("alice", later).unwrap();
+        throttle.check_at("alice", later).unwrap();
+    }
+
+    #[test]
+    fn reads_the_address_that_the_proxy_added() {
+        let ip = |header, value| ip_from_header(header, value).map(|ip| ip.to_string());
+
+        assert_eq!(
+            ip("X-Forwarded-For", "203.0.113.7, 198.51.100.2"),
+            Some("198.51.100.2".into())
+        );
+        assert_eq!(
+            ip("X-Real-IP", "198.51.100.2:4711"),
+            Some("198.51.100.2".into())
+        );
+        assert_eq!(
+            ip("x-forwarded-for", "2001:db8::1"),
+            Some("2001:db8::1".into())
+        );
+        assert_eq!(
+            ip(
+                "Forwarded",
+                r#"for=203.0.113.7;proto=https, for="[2001:db8::1]:4711";proto=https"#
+            ),
+            Some("2001:db8::1".into())
+        );
+        assert_eq!(
+            ip("Forwarded", "for=198.51.100.2"),
+            Some("198.51.100.2".into())
+        );
+        assert_eq!(ip("X-Forwarded-For", "unknown"), None);
+        assert_eq!(ip("Forwarded", "proto=https"), None);
+    }
+}
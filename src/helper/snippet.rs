@@ -0,0 +1,64 @@
+// How many characters are kept around the first match in a snippet
+const SNIPPET_RADIUS: usize = 60;
+
+// Splits the search query into the words that should be highlighted
+pub fn get_search_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|t| t.trim_matches('"').to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+// Cuts a short piece of text around the first search term it can find,
+// and wraps every search term inside that piece with <mark></mark>
+// Returns None if none of the terms appear in the text
+pub fn highlight(text: &str, terms: &[String]) -> Option<String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let lowered = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect::<Vec<_>>();
+    let terms = terms
+        .iter()
+        .map(|t| t.chars().collect::<Vec<_>>())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>();
+
+    let matches_at = |i: usize| {
+        terms
+            .iter()
+            .find(|t| lowered.len() >= i + t.len() && lowered[i..i + t.len()] == t[..])
+            .map(|t| t.len())
+    };
+
+    let first = (0..lowered.len()).find(|i| matches_at(*i).is_some())?;
+
+    let start = first.saturating_sub(SNIPPET_RADIUS);
+    let end = (first + SNIPPET_RADIUS).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut i = start;
+    while i < end {
+        match matches_at(i) {
+            Some(len) => {
+                let len = len.min(chars.len() - i);
+                snippet.push_str("<mark>");
+                snippet.extend(&chars[i..i + len]);
+                snippet.push_str("</mark>");
+                i += len;
+            }
+            None => {
+                snippet.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+    if i < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
@@ -0,0 +1,153 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use rand::RngCore;
+use sha1::Sha1;
+
+use crate::{
+    helper::{hash::random_token, into_string},
+    Result,
+};
+
+// The defaults of every authenticator app, anything else is poorly supported
+const SECRET_BYTES: usize = 20;
+const DIGITS: u32 = 6;
+const STEP_SECONDS: i64 = 30;
+
+// A code from the step before or after is still accepted, since the clocks drift
+const ALLOWED_DRIFT: i64 = 1;
+
+const ISSUER: &str = "File Manager";
+
+// The secret is shared with the authenticator app in base32
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut secret);
+    BASE32_NOPAD.encode(&secret)
+}
+
+// The URI that the authenticator apps read from the QR code
+pub fn otpauth_uri(secret: &str, account: &str) -> String {
+    let issuer = utf8_percent_encode(ISSUER, NON_ALPHANUMERIC);
+    let account = utf8_percent_encode(account, NON_ALPHANUMERIC);
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECONDS}"
+    )
+}
+
+pub fn current_step(timestamp_secs: i64) -> i64 {
+    timestamp_secs / STEP_SECONDS
+}
+
+// HOTP from RFC 4226, TOTP is the same thing with the time step as the counter
+fn code_at_step(secret: &str, step: i64) -> Result<String> {
+    let key = BASE32_NOPAD
+        .decode(secret.as_bytes())
+        .map_err(into_string)?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).map_err(into_string)?;
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    Ok(format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    ))
+}
+
+// Returns the step that the code belongs to, or None if the code is wrong
+// Steps up to last_step are rejected, so a code cannot be used twice
+pub fn verify_code(
+    secret: &str,
+    code: &str,
+    timestamp_secs: i64,
+    last_step: i64,
+) -> Result<Option<i64>> {
+    let step = current_step(timestamp_secs);
+    for candidate in (step - ALLOWED_DRIFT)..=(step + ALLOWED_DRIFT) {
+        if candidate > last_step && code_at_step(secret, candidate)? == code {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+const RECOVERY_CODES: usize = 10;
+
+// Recovery codes look like k3j9x-0qp2m, so they are easy to type from a printout
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let code = random_token(10).to_lowercase();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The SHA1 secret of RFC 6238, the ASCII of 12345678901234567890
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_the_rfc_6238_vectors() {
+        // The RFC lists 8 digit codes, the 6 digit ones are their last 6 digits
+        let vectors = [
+            (59, "94287082"),
+            (1111111109, "07081804"),
+            (1111111111, "14050471"),
+            (1234567890, "89005924"),
+            (2000000000, "69279037"),
+            (20000000000, "65353130"),
+        ];
+        for (time, code) in vectors {
+            assert_eq!(
+                code_at_step(RFC_SECRET, current_step(time)).unwrap(),
+                code[2..],
+                "at {time}"
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_the_codes_of_the_steps_around_now() {
+        let now = 1111111111;
+        let step = current_step(now);
+
+        for drift in -ALLOWED_DRIFT..=ALLOWED_DRIFT {
+            let code = code_at_step(RFC_SECRET, step + drift).unwrap();
+            assert_eq!(
+                verify_code(RFC_SECRET, &code, now, 0).unwrap(),
+                Some(step + drift)
+            );
+        }
+        for drift in [-ALLOWED_DRIFT - 1, ALLOWED_DRIFT + 1] {
+            let code = code_at_step(RFC_SECRET, step + drift).unwrap();
+            assert_eq!(verify_code(RFC_SECRET, &code, now, 0).unwrap(), None);
+        }
+        assert_eq!(verify_code(RFC_SECRET, "000000", now, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_a_code_that_was_already_used() {
+        let now = 1234567890;
+        let code = code_at_step(RFC_SECRET, current_step(now)).unwrap();
+
+        let step = verify_code(RFC_SECRET, &code, now, 0).unwrap().unwrap();
+        assert_eq!(verify_code(RFC_SECRET, &code, now, step).unwrap(), None);
+
+        // Nor an older code that is still inside the drift window
+        let older = code_at_step(RFC_SECRET, step - 1).unwrap();
+        assert_eq!(verify_code(RFC_SECRET, &older, now, step).unwrap(), None);
+    }
+}
@@ -0,0 +1,332 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use data_encoding::HEXLOWER;
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, redirect::Policy, Client, Url};
+use sha2::Sha256;
+
+use crate::{
+    base::webhook_delivery::{DeliveryAttempt, MAX_RESPONSE_BODY},
+    helper::into_string,
+    Result,
+};
+
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+// t=<unix seconds>,v1=<hex of the HMAC-SHA256 of "<unix seconds>.<body>" with the secret>
+// The timestamp is signed as well, so that an old delivery cannot be replayed later
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+pub fn sign_payload(secret: &str, timestamp: i64, payload: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(into_string)?;
+    mac.update(format!("{timestamp}.{payload}").as_bytes());
+    let signature = HEXLOWER.encode(&mac.finalize().into_bytes());
+    Ok(format!("t={timestamp},v1={signature}"))
+}
+
+// The addresses that are not on the internet, a webhook pointing there could reach
+// the database or anything else next to the server
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private_ipv4(ip),
+            None => is_private_ipv6(ip),
+        },
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // The shared address space of the carriers, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+}
+
+fn is_private_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+}
+
+// Checked on every delivery, not only when the webhook is saved,
+// since the name can point somewhere else by then
+// Gives back the addresses that passed, the delivery has to go to one of them,
+// resolving the name again could give a private address this time
+pub async fn check_webhook_host(url: &str, allow_private_hosts: bool) -> Result<Vec<SocketAddr>> {
+    if allow_private_hosts {
+        return Ok(Vec::new());
+    }
+
+    let url = Url::parse(url).map_err(into_string)?;
+    let host = url
+        .host_str()
+        .ok_or("The webhook URL does not have a host")?;
+    let port = url
+        .port_or_known_default()
+        .ok_or("The webhook URL does not have a port")?;
+    // An IPv6 host comes in brackets
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    let addresses = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Cannot resolve the webhook host {host}: {e}"))?
+        .collect::<Vec<_>>();
+    if addresses.is_empty() {
+        return Err(format!("Cannot resolve the webhook host {host}").into());
+    }
+    if addresses.iter().any(|address| is_private_ip(address.ip())) {
+        return Err(format!("The webhook host {host} is not a public address").into());
+    }
+    Ok(addresses)
+}
+
+// A client for one delivery, which only connects to the checked addresses
+// A URL with an IP address is left alone, there is nothing to resolve then
+pub fn webhook_client(url: &str, addresses: &[SocketAddr], timeout: Duration) -> Result<Client> {
+    // A redirect could lead the delivery to a host that was never checked
+    let mut builder = Client::builder().redirect(Policy::none()).timeout(timeout);
+    let url = Url::parse(url).map_err(into_string)?;
+    if let Some(domain) = url.domain().filter(|_| !addresses.is_empty()) {
+        builder = builder.resolve_to_addrs(domain, addresses);
+    }
+    Ok(builder.build().map_err(into_string)?)
+}
+
+async fn post_signed(
+    client: &Client,
+    url: &str,
+    secret: &str,
+    event: &str,
+    delivery_id: &str,
+    payload: &str,
+    timestamp: i64,
+) -> Result<(i32, String)> {
+    let signature = sign_payload(secret, timestamp, payload)?;
+    let mut response = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event)
+        .header(DELIVERY_HEADER, delivery_id)
+        .header(SIGNATURE_HEADER, signature)
+        .body(payload.to_string())
+        .send()
+        .await
+        .map_err(into_string)?;
+    let status_code = response.status().as_u16() as i32;
+
+    // Only the start of the body is kept, the rest is not even read
+    // A character takes up to 4 bytes
+    let mut body = Vec::new();
+    while body.len() < MAX_RESPONSE_BODY * 4 {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    body.truncate(MAX_RESPONSE_BODY * 4);
+    Ok((status_code, String::from_utf8_lossy(&body).to_string()))
+}
+
+// Sends one delivery, whatever happens ends up in the attempt instead of an error
+pub async fn send_webhook(
+    client: &Client,
+    url: &str,
+    secret: &str,
+    event: &str,
+    delivery_id: &str,
+    payload: &str,
+) -> DeliveryAttempt {
+    let attempted_at = Utc::now();
+    let started = Instant::now();
+
+    let result = post_signed(
+        client,
+        url,
+        secret,
+        event,
+        delivery_id,
+        payload,
+        attempted_at.timestamp(),
+    )
+    .await;
+
+    let (status_code, response_body, error) = match result {
+        Ok((status_code, body)) => {
+            let body = body.chars().take(MAX_RESPONSE_BODY).collect::<String>();
+            (Some(status_code), Some(body), None)
+        }
+        Err(e) => (None, None, Some(e.to_string())),
+    };
+
+    DeliveryAttempt {
+        attempted_at: attempted_at.timestamp_millis(),
+        duration: started.elapsed().as_millis() as i64,
+        status_code,
+        response_body,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    // A receiver that answers every request with the given status and body, and hands the request back
+    async fn receiver(
+        status: &'static str,
+        body: String,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            // The body is small enough to come along with the headers
+            loop {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if read == 0 || text.ends_with('}') {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            // The sender can hang up before a long body is through
+            socket.write_all(response.as_bytes()).await.ok();
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn delivers_a_signed_payload() {
+        let (url, handle) = receiver("200 OK", "ok".into()).await;
+        let payload = r#"{"event":"file.created"}"#;
+
+        let attempt = send_webhook(
+            &Client::new(),
+            &url,
+            "whsec_test",
+            "file.created",
+            "delivery-1",
+            payload,
+        )
+        .await;
+        let request = handle.await.unwrap().to_lowercase();
+
+        assert!(attempt.is_success(), "{attempt:?}");
+        assert_eq!(attempt.response_body.as_deref(), Some("ok"));
+        assert!(request.contains("x-webhook-event: file.created"));
+        assert!(request.contains("x-webhook-delivery: delivery-1"));
+
+        // The receiver gets the same signature by signing the body with the timestamp from the header
+        let header = request
+            .lines()
+            .find_map(|line| line.strip_prefix("x-webhook-signature: "))
+            .unwrap()
+            .trim()
+            .to_string();
+        let timestamp = header
+            .strip_prefix("t=")
+            .and_then(|h| h.split(',').next())
+            .unwrap()
+            .parse::<i64>()
+            .unwrap();
+        assert_eq!(
+            header,
+            sign_payload("whsec_test", timestamp, payload).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn records_a_failed_answer() {
+        let (url, handle) = receiver("500 Internal Server Error", "ok".into()).await;
+
+        let attempt = send_webhook(
+            &Client::new(),
+            &url,
+            "whsec_test",
+            "file.deleted",
+            "2",
+            "{}",
+        )
+        .await;
+        handle.await.unwrap();
+
+        assert!(!attempt.is_success());
+        assert_eq!(attempt.status_code, Some(500));
+    }
+
+    #[tokio::test]
+    async fn refuses_private_hosts() {
+        assert!(check_webhook_host("http://127.0.0.1:9000/hook", false)
+            .await
+            .is_err());
+        assert!(check_webhook_host("http://[::1]/hook", false)
+            .await
+            .is_err());
+        assert!(check_webhook_host("http://10.0.0.1/hook", false)
+            .await
+            .is_err());
+        assert!(check_webhook_host("http://127.0.0.1:9000/hook", true)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn keeps_only_the_start_of_a_long_answer() {
+        let (url, _handle) = receiver("200 OK", "é".repeat(1024 * 1024)).await;
+
+        let attempt = send_webhook(
+            &Client::new(),
+            &url,
+            "whsec_test",
+            "file.created",
+            "3",
+            "{}",
+        )
+        .await;
+
+        assert!(attempt.is_success(), "{attempt:?}");
+        assert_eq!(attempt.response_body, Some("é".repeat(MAX_RESPONSE_BODY)));
+    }
+
+    #[tokio::test]
+    async fn delivers_to_the_checked_address() {
+        let (url, handle) = receiver("200 OK", "ok".into()).await;
+        let address = Url::parse(&url).unwrap().socket_addrs(|| None).unwrap();
+        // The name does not resolve anywhere, only the pinned address is used
+        let url = url.replace("127.0.0.1", "webhook.invalid");
+
+        let client = webhook_client(&url, &address, Duration::from_secs(5)).unwrap();
+        let attempt = send_webhook(&client, &url, "whsec_test", "file.created", "4", "{}").await;
+        handle.await.unwrap();
+
+        assert!(attempt.is_success(), "{attempt:?}");
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use mongodb::bson::oid::ObjectId;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, warn};
+
+use crate::{
+    helper::event_bus::EventBus,
+    service::{
+        acl_service::AclService, user_service::UserService, webhook_service::WebhookService,
+    },
+};
+
+// How often the accounts waiting for deletion are checked
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// How often the failed webhook deliveries are looked at for a retry
+const WEBHOOK_RETRY_INTERVAL: Duration = Duration::from_secs(15);
+
+// Runs for as long as the server does, the first run happens right at startup
+pub async fn purge_deleted_users(user_service: UserService) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = user_service.purge_due_users().await {
+            error!("Cannot purge the deleted users: {e}");
+        }
+    }
+}
+
+// Turns the events on the bus into webhook deliveries, and makes the first try right away
+pub async fn queue_webhook_deliveries(
+    webhook_service: WebhookService,
+    acl_service: AclService,
+    event_bus: EventBus,
+) {
+    let mut receiver = event_bus.subscribe();
+    loop {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                warn!("The webhooks missed {missed} events");
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        let webhooks = match webhook_service.get_webhooks_for_event(&event).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                error!("Cannot get the webhooks for {}: {e}", event.name());
+                continue;
+            }
+        };
+
+        let event_id = ObjectId::new();
+        for webhook in webhooks {
+            // The owner only hears about what they could see themselves
+            if !webhook.global
+                && !acl_service
+                    .can_see_event(&webhook.owner, &event)
+                    .await
+                    .unwrap_or(false)
+            {
+                continue;
+            }
+
+            match webhook_service
+                .queue_delivery(&webhook, &event_id, &event)
+                .await
+            {
+                Ok(delivery) => {
+                    let webhook_service = webhook_service.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = webhook_service.deliver(&delivery.id).await {
+                            error!("Cannot deliver {}: {e}", delivery.id);
+                        }
+                    });
+                }
+                Err(e) => error!("Cannot queue a delivery for {}: {e}", webhook.id),
+            }
+        }
+    }
+}
+
+// The deliveries that failed are tried again here once they are due,
+// along with the ones that were left behind by a restart
+pub async fn retry_webhook_deliveries(webhook_service: WebhookService) {
+    let mut interval = tokio::time::interval(WEBHOOK_RETRY_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = webhook_service.deliver_due().await {
+            error!("Cannot retry the webhook deliveries: {e}");
+        }
+    }
+}
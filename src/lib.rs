@@ -0,0 +1,24 @@
+#![allow(dead_code, unused_variables)]
+
+// Everything the server is made of, shared by the server and the admin binaries
+
+pub mod aws;
+pub mod base;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod handler;
+pub mod helper;
+pub mod job;
+pub mod mailer;
+pub mod middleware;
+pub mod openapi;
+pub mod request;
+pub mod response;
+pub mod routes;
+pub mod service;
+pub mod validation;
+pub mod web;
+
+pub type Result<T> = std::result::Result<T, error::Error>;
+pub type WebResult = Result<web::Web>;
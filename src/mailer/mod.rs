@@ -0,0 +1,36 @@
+pub mod outbox;
+pub mod smtp;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::{MailConfig, MailerKind},
+    Result,
+};
+
+use self::{outbox::OutboxMailer, smtp::SmtpMailer};
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Everything that sends mails to the users goes through this,
+// so that the development setup does not need a real mail server
+#[async_trait]
+pub trait Mailer: std::fmt::Debug + Send + Sync {
+    async fn send(&self, mail: Mail) -> Result<()>;
+}
+
+// The smtp mailer sends real mails, the outbox one writes them to a folder
+pub fn init_mailer(config: &MailConfig) -> Result<Arc<dyn Mailer>> {
+    let mailer: Arc<dyn Mailer> = match config.mailer {
+        MailerKind::Smtp => Arc::new(SmtpMailer::init(config)?),
+        MailerKind::Outbox => Arc::new(OutboxMailer::init(config)),
+    };
+    Ok(mailer)
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+
+use crate::{config::MailConfig, Result};
+
+use super::{Mail, Mailer};
+
+// Writes every mail to a text file instead of sending it, for development and tests
+#[derive(Debug, Clone)]
+pub struct OutboxMailer {
+    dir: PathBuf,
+}
+
+impl OutboxMailer {
+    pub fn init(config: &MailConfig) -> Self {
+        Self {
+            dir: config.outbox_dir.clone().into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for OutboxMailer {
+    async fn send(&self, mail: Mail) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        // The time comes first so that the mails are listed in the order they were sent
+        let path = self.dir.join(format!(
+            "{}-{}.txt",
+            Utc::now().timestamp_millis(),
+            ObjectId::new()
+        ));
+        let content = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            mail.to, mail.subject, mail.body
+        );
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+}
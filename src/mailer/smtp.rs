@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::{config::MailConfig, helper::into_string, Result};
+
+use super::{Mail, Mailer};
+
+#[derive(Debug, Clone)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    // The connection is made over TLS, the port defaults to the submission port
+    pub fn init(config: &MailConfig) -> Result<Self> {
+        let host = config
+            .smtp_host
+            .as_deref()
+            .ok_or("The smtp host is missing")?;
+        let credentials = Credentials::new(
+            config.smtp_username.clone().unwrap_or_default(),
+            config.smtp_password.clone().unwrap_or_default(),
+        );
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(into_string)?
+            .credentials(credentials);
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config
+                .from
+                .as_deref()
+                .ok_or("The sender of the mails is missing")?
+                .parse()
+                .map_err(into_string)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: Mail) -> Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(mail.to.parse().map_err(into_string)?)
+            .subject(mail.subject)
+            .body(mail.body)
+            .map_err(into_string)?;
+
+        self.transport.send(message).await.map_err(into_string)?;
+        Ok(())
+    }
+}
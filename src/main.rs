@@ -1,11 +1,31 @@
-#![allow(dead_code, unused_variables)]
-
-use aws::S3;
-use db::{
-    file_db::FileDB, file_version_db::FileVersionDB, folder_db::FolderDB, mongo::DB,
-    user_db::UserDB,
-};
 use dotenv::dotenv;
+use final_project::{
+    aws::S3,
+    config::Config,
+    db::{
+        self, acl_db::AclDB, change_db::ChangeDB, file_db::FileDB, file_request_db::FileRequestDB,
+        file_version_db::FileVersionDB, folder_db::FolderDB, mongo::DB, oidc_login_db::OidcLoginDB,
+        search_db::SearchDB, share_link_db::ShareLinkDB, team_db::TeamDB, user_db::UserDB,
+        user_token_db::UserTokenDB, webhook_db::WebhookDB, webhook_delivery_db::WebhookDeliveryDB,
+    },
+    helper::{
+        self,
+        event_bus::EventBus,
+        extension_policy::ExtensionPolicy,
+        oidc::init_providers,
+        rate_limit::{ClientIp, LoginThrottle},
+    },
+    job, mailer, routes,
+    service::{
+        account_service::AccountService, acl_service::AclService, change_service::ChangeService,
+        file_request_service::FileRequestService, file_service::FileService,
+        file_version_service::FileVersionService, folder_service::FolderService,
+        oidc_service::OidcService, search_service::SearchService,
+        share_link_service::ShareLinkService, team_service::TeamService, user_service::UserService,
+        webhook_service::WebhookService,
+    },
+    Result,
+};
 use salvo::{
     affix,
     cors::Cors,
@@ -13,43 +33,109 @@ use salvo::{
     size_limiter::max_size,
     Router, Server,
 };
-use service::{
-    file_service::FileService, file_version_service::FileVersionService,
-    folder_service::FolderService, user_service::UserService,
-};
-use web::Web;
-
-mod aws;
-mod base;
-mod db;
-mod error;
-mod handler;
-mod helper;
-mod middleware;
-mod request;
-mod response;
-mod routes;
-mod service;
-mod validation;
-mod web;
-
-type Result<T> = std::result::Result<T, error::Error>;
-type WebResult = Result<Web>;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    dotenv()?;
-    let db = DB::init().await?;
+    // The .env file is optional, the config file can hold everything
+    dotenv().ok();
+    helper::log::init_logging();
+    let config = Config::load()?;
+    helper::jwt::init_keyrings(&config.jwt)?;
+    let db = DB::init(&config.database).await?;
+    db::create_indexes(&db).await?;
     let file_db = FileDB::init(&db);
     let folder_db = FolderDB::init(&db);
     let user_db = UserDB::init(&db);
-    let s3 = S3::init()?;
+    let s3 = S3::init(&config.storage)?;
     let file_version_db = FileVersionDB::init(&db);
+    let search_db = SearchDB::init(&db);
+    let acl_db = AclDB::init(&db);
+    let team_db = TeamDB::init(&db);
+    let share_link_db = ShareLinkDB::init(&db);
+    let file_request_db = FileRequestDB::init(&db);
+    let user_token_db = UserTokenDB::init(&db);
+    let mailer = mailer::init_mailer(&config.mail)?;
+    let oidc_login_db = OidcLoginDB::init(&db);
+    let webhook_db = WebhookDB::init(&db);
+    let webhook_delivery_db = WebhookDeliveryDB::init(&db);
+    let change_db = ChangeDB::init(&db);
+
+    let event_bus = EventBus::default();
+
+    let user_service = UserService::init(
+        &user_db,
+        &file_db,
+        &folder_db,
+        &file_version_db,
+        &search_db,
+        &acl_db,
+        &team_db,
+        &share_link_db,
+        &file_request_db,
+        &user_token_db,
+        &webhook_db,
+        &webhook_delivery_db,
+        &change_db,
+        &s3,
+        &event_bus,
+        config.accounts.deletion_grace_days,
+    );
+    let extension_policy = ExtensionPolicy::init(&config.uploads);
 
-    let user_service = UserService::init(&user_db, &file_db, &folder_db, &file_version_db, &s3);
-    let file_service = FileService::init(&file_db, &folder_db, &file_version_db, &s3);
-    let folder_service = FolderService::init(&file_db, &folder_db, &s3);
+    let file_service = FileService::init(
+        &file_db,
+        &folder_db,
+        &file_version_db,
+        &search_db,
+        &acl_db,
+        &s3,
+        &extension_policy,
+        &change_db,
+        &event_bus,
+    );
+    let folder_service = FolderService::init(
+        &file_db,
+        &folder_db,
+        &search_db,
+        &acl_db,
+        &share_link_db,
+        &file_request_db,
+        &s3,
+        &change_db,
+        &event_bus,
+    );
+    let change_service = ChangeService::init(&change_db);
     let file_version_service = FileVersionService::init(&file_version_db, &s3);
+    let search_service = SearchService::init(&search_db, &file_db, &folder_db, &acl_db, &team_db);
+    let acl_service = AclService::init(&acl_db, &file_db, &folder_db, &user_db, &team_db);
+    let account_service =
+        AccountService::init(&user_db, &user_token_db, &mailer, &config.server.app_url);
+    let oidc_service = OidcService::init(&oidc_login_db, &init_providers(&config.oidc));
+    let share_link_service = ShareLinkService::init(&share_link_db, &file_db, &folder_db);
+    let file_request_service = FileRequestService::init(&file_request_db, &folder_db);
+    let team_service = TeamService::init(
+        &team_db,
+        &user_db,
+        &file_db,
+        &folder_db,
+        &file_version_db,
+        &search_db,
+        &acl_db,
+        &share_link_db,
+        &file_request_db,
+        &change_db,
+        &s3,
+        &event_bus,
+    );
+    let webhook_service = WebhookService::init(&webhook_db, &webhook_delivery_db, &config.webhooks);
+
+    tokio::spawn(job::purge_deleted_users(user_service.clone()));
+    tokio::spawn(job::queue_webhook_deliveries(
+        webhook_service.clone(),
+        acl_service.clone(),
+        event_bus.clone(),
+    ));
+    tokio::spawn(job::retry_webhook_deliveries(webhook_service.clone()));
 
     let cors_builder = Cors::builder()
         .allow_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
@@ -64,6 +150,7 @@ async fn main() -> Result<()> {
             "Access-Control-Allow-Methods",
             "Access-Control-Max-Age",
             "Authorization",
+            "X-Link-Password",
         ])
         .build();
 
@@ -72,16 +159,29 @@ async fn main() -> Result<()> {
             .insert("folder_service", folder_service)
             .insert("file_service", file_service)
             .insert("file_version_service", file_version_service)
+            .insert("search_service", search_service)
+            .insert("acl_service", acl_service)
+            .insert("team_service", team_service)
+            .insert("share_link_service", share_link_service)
+            .insert("file_request_service", file_request_service)
+            .insert("webhook_service", webhook_service)
+            .insert("change_service", change_service)
+            .insert("account_service", account_service)
+            .insert("oidc_service", oidc_service)
+            .insert("login_throttle", LoginThrottle::default())
+            .insert(
+                "client_ip",
+                ClientIp::new(config.server.trusted_proxy_header.as_deref()),
+            )
+            .insert("event_bus", event_bus)
             .insert("storage", s3),
     )
-    .hoop(max_size(1024 * 1024 * 100)) // limit to 100MBs per request
+    .hoop(max_size(config.server.max_upload_size)) // limit the size of every request
     .hoop(cors_builder)
     .push(Router::with_path("/<**>").options(empty_handler)) // Dealing with the browser
     .push(routes::routes());
 
-    let port = std::env::var("PORT")?;
-
-    let listener = TcpListener::bind(&format!("127.0.0.1:{port}"));
+    let listener = TcpListener::bind(config.server.address()?);
     Server::new(listener).serve(router).await;
     Ok(())
 }
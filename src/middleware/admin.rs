@@ -0,0 +1,22 @@
+use salvo::{handler, Depot, FlowCtrl, Request, Response};
+
+use crate::{error::Error, helper::cookie::get_cookie_user, Result};
+
+// Has to come after check_login_middleware, since it reads the cookie user
+#[handler]
+pub async fn check_admin_middleware(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> Result<()> {
+    if !get_cookie_user(depot)?.is_admin() {
+        return Err(Error::Permissions(
+            "Only the admins can access this resource".into(),
+        ));
+    }
+
+    ctrl.call_next(req, depot, res).await;
+
+    Ok(())
+}
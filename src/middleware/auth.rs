@@ -44,12 +44,15 @@ pub async fn check_login_middleware(
     // Because this means that the user id in the cookie is gibberish data
     let cookie_user = user_service.get_user_by_id(&cookie_user_id).await?;
 
+    // The sessions of a locked user are rejected, even if the tokens are still valid
+    cookie_user.check_active()?;
+
     // Get the refresh token from the cookie
     let refresh_jwt = 
             req
                 .cookie("refreshToken")
                 .ok_or_else(|| {
-                    Error::Permissions(
+                    Error::Unauthorized(
                         "There is no refresh token going along with the access token. Cannot be authenticated".into(),
                     )
                 })?
@@ -58,7 +61,7 @@ pub async fn check_login_middleware(
 
     // If the refresh token is empty, reject the request
     if refresh_jwt.is_empty() {
-        return Err(Error::Permissions(
+        return Err(Error::Unauthorized(
             "The refresh token cannot be empty".into(),
         ));
     }
@@ -70,7 +73,7 @@ pub async fn check_login_middleware(
     // We also checked for null token input in case that is a workaround our logout mechanism
 
     if refresh_jwt != *cookie_user.refresh_token {
-        return Err(Error::Permissions(
+        return Err(Error::Unauthorized(
                     "The refresh token in the cookie does not match the user's refresh token. Please login and try again"
                         .to_string(),
                 ));
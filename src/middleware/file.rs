@@ -1,6 +1,10 @@
 use crate::{
+    base::acl::Access,
+    error::Error,
     helper::{
-        cookie::get_cookie_user_id_option, depot::get_file_service, param::get_param_file_id,
+        cookie::get_cookie_user_id_option,
+        depot::{get_acl_service, get_file_service},
+        param::get_param_file_id,
     },
     Result,
 };
@@ -45,6 +49,7 @@ pub async fn get_file_by_id_middleware(
             let param_file = param_file.await?;
         
             depot.insert("param_file", param_file);
+            depot.insert("param_access", Access::Public);
             ctrl.call_next(req, depot, res).await;
             return Ok(());
         };
@@ -59,24 +64,28 @@ pub async fn get_file_by_id_middleware(
             let param_file = param_file.await?;
 
             depot.insert("param_file", param_file);
+            depot.insert("param_access", Access::Public);
             ctrl.call_next(req, depot, res).await;
             return Ok(());
         }
         
         depot.insert("param_file", file);
+        depot.insert("param_access", Access::Public);
         ctrl.call_next(req, depot, res).await;
         return Ok(());
     };
 
     let file = file.await?;
 
-    let param_file = if *cookie_user_id == file.owner {
-        file
-    } else {
-        param_file.await?
-    };
+    // The owner and the users that the file is shared with can get the file even if it is private
+    // Everyone else can only get the file if it is public
+    let access = get_acl_service(depot)?
+        .get_file_access(&file, Some(cookie_user_id))
+        .await?
+        .ok_or_else(|| Error::NotFound("Cannot find the file with the provided information".into()))?;
 
-    depot.insert("param_file", param_file);
+    depot.insert("param_file", file);
+    depot.insert("param_access", access);
     ctrl.call_next(req, depot, res).await;
 
     Ok(())
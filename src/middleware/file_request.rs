@@ -0,0 +1,31 @@
+use crate::{
+    helper::{depot::get_file_request_service, param::get_param_drop_token},
+    Result,
+};
+use salvo::{handler, Depot, FlowCtrl, Request, Response};
+
+#[handler]
+pub async fn get_file_request_by_token_middleware(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> Result<()> {
+    let file_request_service = get_file_request_service(depot)?;
+
+    let drop_token = get_param_drop_token(req)?;
+
+    let file_request = file_request_service
+        .get_request_by_token(&drop_token)
+        .await?;
+
+    let folder = file_request_service
+        .get_request_folder(&file_request)
+        .await?;
+
+    depot.insert("param_file_request", file_request);
+    depot.insert("param_file_request_folder", folder);
+    ctrl.call_next(req, depot, res).await;
+
+    Ok(())
+}
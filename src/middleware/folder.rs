@@ -1,6 +1,9 @@
 use crate::{
+    error::Error,
     helper::{
-        cookie::get_cookie_user_id_option, depot::get_folder_service, param::get_param_folder_id,
+        cookie::get_cookie_user_id_option,
+        depot::{get_acl_service, get_folder_service},
+        param::get_param_folder_id,
     },
     Result,
 };
@@ -20,23 +23,17 @@ pub async fn get_folder_by_id_middleware(
     let folder = folder_service.get_folder_by_id(&param_folder_id).await?;
 
     // Get the user (optional, we have to handle two user cases, logged in, and not logged in)
-    let param_folder = match get_cookie_user_id_option(depot) {
-        Some(cookie_user_id) => match *cookie_user_id == folder.owner {
-            true => folder,
-            false => {
-                folder_service
-                    .get_public_folder_by_id(&param_folder_id)
-                    .await?
-            }
-        },
-        None => {
-            folder_service
-                .get_public_folder_by_id(&param_folder_id)
-                .await?
-        }
-    };
+    // The owner and the users that the folder is shared with can get the folder even if it is private
+    // Everyone else can only get the folder if it is public
+    let access = get_acl_service(depot)?
+        .get_folder_access(&folder, get_cookie_user_id_option(depot))
+        .await?
+        .ok_or_else(|| {
+            Error::NotFound("Cannot find the folder with the provided information".into())
+        })?;
 
-    depot.insert("param_folder", param_folder);
+    depot.insert("param_folder", folder);
+    depot.insert("param_access", access);
     ctrl.call_next(req, depot, res).await;
 
     Ok(())
@@ -1,3 +1,9 @@
+pub mod admin;
 pub mod auth;
 pub mod file;
+pub mod file_request;
 pub mod folder;
+pub mod rate_limit;
+pub mod share_link;
+pub mod team;
+pub mod webhook;
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use salvo::{Depot, FlowCtrl, Handler, Request, Response};
+
+use crate::helper::{depot::get_client_ip, rate_limit::SlidingWindow};
+
+// Limits how often one IP can go through the routes behind it
+// The IP is the one of the client, not of the reverse proxy in front, see ClientIp
+// Every hoop counts on its own, so the limits of two routes never add up
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    window: SlidingWindow,
+}
+
+impl RateLimit {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            window: SlidingWindow::new(limit, window),
+        }
+    }
+
+    pub fn per_minute(limit: usize) -> Self {
+        Self::new(limit, Duration::from_secs(60))
+    }
+
+    pub fn per_hour(limit: usize) -> Self {
+        Self::new(limit, Duration::from_secs(60 * 60))
+    }
+}
+
+#[async_trait]
+impl Handler for RateLimit {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let hit = get_client_ip(depot).and_then(|client_ip| self.window.hit(&client_ip.of(req)));
+        if let Err(e) = hit {
+            res.render(e);
+            ctrl.skip_rest();
+            return;
+        }
+
+        ctrl.call_next(req, depot, res).await;
+    }
+}
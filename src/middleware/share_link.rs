@@ -0,0 +1,75 @@
+use crate::{
+    base::acl::Access,
+    helper::{
+        depot::{get_login_throttle, get_param_link_folder, get_share_link_service},
+        param::{get_param_file_id, get_param_link_token},
+    },
+    Result,
+};
+use salvo::{handler, Depot, FlowCtrl, Request, Response};
+
+// The header that carries the password of a protected link
+// Only the header is read, a password in the query string would end up in the logs, the history and the referers
+pub const LINK_PASSWORD_HEADER: &str = "X-Link-Password";
+
+#[handler]
+pub async fn get_link_by_token_middleware(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> Result<()> {
+    let share_link_service = get_share_link_service(depot)?;
+
+    let link_token = get_param_link_token(req)?;
+
+    let link = share_link_service.get_link_by_token(&link_token).await?;
+
+    // A protected link is locked for a while after too many wrong passwords,
+    // the same way as an account, whichever IP the guesses come from
+    let password = req.header::<String>(LINK_PASSWORD_HEADER);
+    if link.password_hash.is_some() {
+        let login_throttle = get_login_throttle(depot)?;
+        let throttle_key = format!("link:{}", link.id);
+        login_throttle.check(&throttle_key)?;
+        if let Err(e) = link.check_password(password.as_deref()) {
+            // Without a password, the guest is only being asked for one
+            if password.is_some() {
+                login_throttle.record_failure(&throttle_key)?;
+            }
+            return Err(e);
+        }
+        login_throttle.reset(&throttle_key)?;
+    }
+
+    let folder = share_link_service.get_link_folder(&link).await?;
+
+    depot.insert("param_link", link);
+    depot.insert("param_link_folder", folder);
+    ctrl.call_next(req, depot, res).await;
+
+    Ok(())
+}
+
+// Has to come after get_link_by_token_middleware
+// Puts the file in the depot the same way as get_file_by_id_middleware,
+// so that the content handlers can be reused
+#[handler]
+pub async fn get_link_file_middleware(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> Result<()> {
+    let param_file_id = get_param_file_id(req)?;
+
+    let file = get_share_link_service(depot)?
+        .get_link_file(get_param_link_folder(depot)?, &param_file_id)
+        .await?;
+
+    depot.insert("param_file", file);
+    depot.insert("param_access", Access::Viewer);
+    ctrl.call_next(req, depot, res).await;
+
+    Ok(())
+}
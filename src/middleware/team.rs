@@ -0,0 +1,27 @@
+use crate::{
+    helper::{cookie::get_cookie_user_id, depot::get_team_service, param::get_param_team_id},
+    Result,
+};
+use salvo::{handler, Depot, FlowCtrl, Request, Response};
+
+#[handler]
+pub async fn get_team_by_id_middleware(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> Result<()> {
+    let team_service = get_team_service(depot)?;
+
+    let param_team_id = get_param_team_id(req)?;
+
+    // Only the members of the team can get the team
+    let team = team_service
+        .get_team_by_id_member(&param_team_id, get_cookie_user_id(depot)?)
+        .await?;
+
+    depot.insert("param_team", team);
+    ctrl.call_next(req, depot, res).await;
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::{
+    helper::{cookie::get_cookie_user_id, depot::get_webhook_service, param::get_param_webhook_id},
+    Result,
+};
+use salvo::{handler, Depot, FlowCtrl, Request, Response};
+
+#[handler]
+pub async fn get_webhook_by_id_middleware(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) -> Result<()> {
+    let webhook_service = get_webhook_service(depot)?;
+
+    let param_webhook_id = get_param_webhook_id(req)?;
+
+    // Only the one who registered the webhook can see it, it holds the secret after all
+    let webhook = webhook_service
+        .get_webhook_by_id_owner(&param_webhook_id, get_cookie_user_id(depot)?)
+        .await?;
+
+    depot.insert("param_webhook", webhook);
+    ctrl.call_next(req, depot, res).await;
+
+    Ok(())
+}
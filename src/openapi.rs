@@ -0,0 +1,331 @@
+use std::collections::BTreeSet;
+
+use salvo::Router;
+use utoipa::{
+    openapi::{
+        security::{ApiKey, ApiKeyValue, SecurityScheme},
+        ContentBuilder, Ref, ResponseBuilder,
+    },
+    Modify, OpenApi,
+};
+
+use crate::{base, handler, request, response, web::Web};
+
+// Every JSON response is wrapped in the Web envelope, the schemas of the operations only describe its data
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Final Project API",
+        description = "Every JSON response is wrapped in an envelope of {code, message, data, error}, \
+the responses below describe its data. The errors also have a stable errorCode, \
+and the validation errors list the problems of each field in the details."
+    ),
+    paths(
+        get_link_file_content,
+        handler::admin::user::get_users_handler,
+        handler::admin::user::get_user_handler,
+        handler::admin::user::suspend_user_handler,
+        handler::admin::user::unsuspend_user_handler,
+        handler::admin::user::reset_password_handler,
+        handler::admin::user::get_user_usage_handler,
+        handler::admin::user::delete_user_handler,
+        handler::auth::jwks::get_jwks_handler,
+        handler::auth::login::login_handler,
+        handler::auth::logout::logout_handler,
+        handler::auth::refresh::refresh_handler,
+        handler::auth::two_factor::two_factor_login_handler,
+        handler::auth::oidc::get_oidc_providers_handler,
+        handler::auth::oidc::oidc_login_handler,
+        handler::auth::oidc::oidc_callback_handler,
+        handler::content::get_content_handler,
+        handler::content::get_content_with_version_handler,
+        handler::drop::get_drop_handler,
+        handler::drop::upload_drop_handler,
+        handler::file::create::create_file_handler,
+        handler::file::delete::delete_file_handler,
+        handler::file::get::get_files_handler,
+        handler::file::get::get_file_by_id_handler,
+        handler::file::restore::restore_file_handler,
+        handler::file::share::share_file_handler,
+        handler::file::share::get_file_shares_handler,
+        handler::file::share::unshare_file_handler,
+        handler::file::tag::add_file_tags_handler,
+        handler::file::tag::remove_file_tags_handler,
+        handler::file::tag::update_file_metadata_handler,
+        handler::file::tag::delete_file_metadata_handler,
+        handler::file::update::update_file_handler,
+        handler::folder::create::create_folder_handler,
+        handler::folder::delete::delete_folder_handler,
+        handler::folder::file_request::create_file_request_handler,
+        handler::folder::file_request::get_file_requests_handler,
+        handler::folder::file_request::delete_file_request_handler,
+        handler::folder::get::get_folders_handler,
+        handler::folder::get::get_folder_by_id_handler,
+        handler::folder::link::create_folder_link_handler,
+        handler::folder::link::get_folder_links_handler,
+        handler::folder::link::delete_folder_link_handler,
+        handler::folder::share::share_folder_handler,
+        handler::folder::share::get_folder_shares_handler,
+        handler::folder::share::unshare_folder_handler,
+        handler::folder::tag::add_folder_tags_handler,
+        handler::folder::tag::remove_folder_tags_handler,
+        handler::folder::tag::update_folder_metadata_handler,
+        handler::folder::tag::delete_folder_metadata_handler,
+        handler::folder::update::update_folder_handler,
+        handler::folder::visibility::update_folder_visibility_handler,
+        handler::link::get_link_content_handler,
+        handler::link::upload_link_file_handler,
+        handler::search::search_handler,
+        handler::shared::get_shared_handler,
+        handler::team::create::create_team_handler,
+        handler::team::delete::delete_team_handler,
+        handler::team::get::get_teams_handler,
+        handler::team::get::get_team_by_id_handler,
+        handler::team::member::add_member_handler,
+        handler::team::member::update_member_handler,
+        handler::team::member::remove_member_handler,
+        handler::user::create::create_user_handler,
+        handler::user::delete::delete_user_handler,
+        handler::user::get::get_user_handler,
+        handler::user::password::forgot_password_handler,
+        handler::user::password::reset_password_handler,
+        handler::user::profile::profile_handler,
+        handler::user::restore::restore_user_handler,
+        handler::user::tag::get_user_tags_handler,
+        handler::user::two_factor::enroll_two_factor_handler,
+        handler::user::two_factor::enable_two_factor_handler,
+        handler::user::two_factor::disable_two_factor_handler,
+        handler::user::update::update_user_handler,
+        handler::user::verify::verify_email_handler,
+        handler::user::verify::resend_verification_handler,
+        handler::version::delete::delete_file_version_handler,
+        handler::version::get::get_versions_handler,
+        handler::version::get::get_version_handler,
+        handler::event::events_handler,
+        handler::change::get_changes_handler,
+        handler::webhook::create::create_webhook_handler,
+        handler::webhook::get::get_webhooks_handler,
+        handler::webhook::get::get_webhook_by_id_handler,
+        handler::webhook::update::update_webhook_handler,
+        handler::webhook::delete::delete_webhook_handler,
+        handler::webhook::delivery::get_deliveries_handler,
+        handler::webhook::delivery::redeliver_handler,
+        handler::docs::get_openapi_handler,
+        handler::docs::get_docs_handler,
+    ),
+    components(schemas(
+        Web,
+        request::admin::password::ResetPasswordRequest,
+        request::file::create::CreateFileRequest,
+        request::file::update::UpdateFileRequest,
+        request::file_request::create::CreateFileRequestRequest,
+        request::file_request::upload::DropFileRequest,
+        request::folder::create::CreateFolderRequest,
+        request::folder::update::UpdateFolderRequest,
+        request::folder::visibility::UpdateVisibilityRequest,
+        request::share::create::ShareRequest,
+        request::share_link::create::CreateShareLinkRequest,
+        request::tag::metadata::MetadataRequest,
+        request::tag::tags::TagsRequest,
+        request::team::create::CreateTeamRequest,
+        request::team::member::AddMemberRequest,
+        request::team::member::UpdateMemberRequest,
+        request::user::create::CreateUserRequest,
+        request::user::delete::DeleteUserRequest,
+        request::user::login::LoginRequest,
+        request::user::password::ForgotPasswordRequest,
+        request::user::password::ResetPasswordRequest,
+        request::user::restore::RestoreUserRequest,
+        request::user::two_factor::TwoFactorCodeRequest,
+        request::user::two_factor::TwoFactorLoginRequest,
+        request::user::two_factor::DisableTwoFactorRequest,
+        request::user::update::UpdateUserRequest,
+        request::user::verify::VerifyEmailRequest,
+        request::webhook::create::CreateWebhookRequest,
+        request::webhook::update::UpdateWebhookRequest,
+        response::file::FileResponse,
+        response::file::FinalFileResponse,
+        response::file_request::FileRequestResponse,
+        response::file_request::DropInfoResponse,
+        response::file_request::DropUploadResponse,
+        response::folder::FolderResponse,
+        response::folder::FinalFolderResponse,
+        response::jwks::PublicJwk,
+        response::jwks::JwksResponse,
+        response::search::SearchResponse,
+        response::share::ShareResponse,
+        response::share::SharedResponse,
+        response::share_link::ShareLinkResponse,
+        response::share_link::LinkContentResponse,
+        response::tag::TagCountResponse,
+        response::team::TeamResponse,
+        response::team::TeamMemberResponse,
+        response::team::FinalTeamResponse,
+        response::two_factor::TwoFactorSetupResponse,
+        response::two_factor::RecoveryCodesResponse,
+        response::two_factor::TwoFactorChallengeResponse,
+        response::user::UserResponse,
+        response::user::UsageResponse,
+        response::user::FinalUserResponse,
+        response::event::EventResponse,
+        response::change::ChangeResponse,
+        response::change::ChangesResponse,
+        response::webhook::WebhookResponse,
+        response::webhook::WebhookDeliveryResponse,
+        response::webhook::DeliveryAttemptResponse,
+        response::webhook::WebhookPayload,
+        base::file::Uploader,
+    )),
+    modifiers(&SecurityAddon, &ErrorResponses),
+    tags(
+        (name = "user", description = "Accounts"),
+        (name = "auth", description = "Logins and tokens"),
+        (name = "admin", description = "Managing the users, only for the admins"),
+        (name = "file", description = "Files"),
+        (name = "version", description = "The versions of the files"),
+        (name = "content", description = "Downloads"),
+        (name = "folder", description = "Folders"),
+        (name = "share", description = "Sharing with other users"),
+        (name = "link", description = "Share links for the guests"),
+        (name = "drop", description = "File requests that the guests upload into"),
+        (name = "team", description = "Teams"),
+        (name = "search", description = "Search"),
+        (name = "events", description = "Changes pushed to the clients"),
+        (name = "changes", description = "The change log for the sync clients"),
+        (name = "webhook", description = "Changes pushed to other servers"),
+        (name = "docs", description = "This documentation"),
+    )
+)]
+pub struct ApiDoc;
+
+// The logged in routes read the access token from its cookie
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "access_token",
+                SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("accessToken"))),
+            );
+        }
+    }
+}
+
+// Every operation can fail the same way, so the error envelope is added to all of them here
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for path_item in openapi.paths.paths.values_mut() {
+            for operation in path_item.operations.values_mut() {
+                let response = ResponseBuilder::new()
+                    .description("The error, with its errorCode and the status as the code")
+                    .content(
+                        "application/json",
+                        ContentBuilder::new()
+                            .schema(Ref::from_schema_name("Web"))
+                            .build(),
+                    )
+                    .build();
+                operation
+                    .responses
+                    .responses
+                    .insert("default".to_string(), response.into());
+            }
+        }
+    }
+}
+
+/// Download a file through a share link
+// The handler is shared with /content, and a handler can only document one path
+#[utoipa::path(
+    get,
+    path = "/link/{link_token}/content/{param_file_id}",
+    tag = "link",
+    params(
+        ("link_token" = String, Path, description = "The token of the share link"),
+        ("param_file_id" = String, Path, description = "The id of the file"),
+    ),
+    responses(
+        (status = 200, description = "The content of the file", content_type = "application/octet-stream"),
+    )
+)]
+#[allow(dead_code)]
+fn get_link_file_content() {}
+
+// Every method and path that the router answers, like ("GET", "/file/{param_file_id}")
+// The params are written the OpenAPI way, so they can be compared with the spec
+pub fn router_operations(router: &Router) -> BTreeSet<(String, String)> {
+    fn walk(router: &Router, prefix: &str, operations: &mut BTreeSet<(String, String)>) {
+        let mut path = prefix.to_string();
+        let mut methods = Vec::new();
+        for filter in router.filters() {
+            let info = format!("{filter:?}");
+            if let Some(segment) = info.strip_prefix("path:") {
+                let segment = segment.trim_matches('/');
+                if !segment.is_empty() {
+                    path = format!("{path}/{}", segment.replace('<', "{").replace('>', "}"));
+                }
+            } else if let Some(method) = info.strip_prefix("method:") {
+                methods.push(method.to_string());
+            }
+        }
+        for method in methods {
+            let path = if path.is_empty() {
+                "/".to_string()
+            } else {
+                path.clone()
+            };
+            operations.insert((method, path));
+        }
+        for child in router.routers() {
+            walk(child, &path, operations);
+        }
+    }
+
+    let mut operations = BTreeSet::new();
+    walk(router, "", &mut operations);
+    operations
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    // Every method and path in the spec, like ("GET", "/file/{param_file_id}")
+    fn spec_operations() -> BTreeSet<(String, String)> {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut operations = BTreeSet::new();
+        if let Some(Value::Object(paths)) = spec.get("paths") {
+            for (path, item) in paths {
+                if let Value::Object(methods) = item {
+                    for method in methods.keys() {
+                        operations.insert((method.to_uppercase(), path.clone()));
+                    }
+                }
+            }
+        }
+        operations
+    }
+
+    #[test]
+    fn spec_matches_the_router() {
+        let router = router_operations(&crate::routes::routes());
+        let spec = spec_operations();
+
+        let undocumented = router.difference(&spec).collect::<Vec<_>>();
+        let missing = spec.difference(&router).collect::<Vec<_>>();
+
+        assert!(
+            undocumented.is_empty(),
+            "These routes are not in the OpenAPI spec: {undocumented:?}"
+        );
+        assert!(
+            missing.is_empty(),
+            "These operations of the OpenAPI spec have no route: {missing:?}"
+        );
+    }
+}
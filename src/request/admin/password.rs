@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::validation::user::check_password;
+use crate::Result;
+
+#[derive(Debug, Clone, Validate, Deserialize, Serialize, ToSchema)]
+// Named apart from the reset with the mail token in the OpenAPI spec
+#[schema(as = AdminResetPasswordRequest)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordRequest {
+    #[validate(custom(function = "check_password"))]
+    pub new_password: String,
+
+    #[validate(must_match(
+        other = "new_password",
+        message = "The newPassword must match with confirmPassword"
+    ))]
+    pub confirm_password: String,
+}
+
+impl ResetPasswordRequest {
+    pub fn into_password(self) -> Result<String> {
+        self.validate()?;
+        Ok(self.new_password)
+    }
+}
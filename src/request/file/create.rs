@@ -1,6 +1,7 @@
 use crate::{
     base::{
         file::{File, Visibility},
+        team::Team,
         user::User,
     },
     helper::into_string,
@@ -9,31 +10,38 @@ use crate::{
 };
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFileRequest {
     #[validate(custom = "check_dir")]
     pub position: String,
+    // Without a visibility, the file gets the default of the folder it is created in
     #[validate(custom = "check_visibility")]
-    pub visibility: String,
+    pub visibility: Option<String>,
+    // The id of the team, when creating the file in the folders of a team
+    pub team: Option<String>,
 }
 
 impl CreateFileRequest {
-    pub fn into_file(self, owner: &User, full_filename: &str) -> Result<File> {
+    pub fn into_file(self, owner: &User, team: Option<&Team>, full_filename: &str) -> Result<File> {
         self.validate()?;
         check_full_filename(full_filename).map_err(into_string)?;
 
-        let visibility = match self.visibility.as_str() {
-            "public" => Visibility::Public,
-            "private" => Visibility::Private,
+        // The handler replaces inherit with the default of the folder when it was not asked for
+        let visibility = match self.visibility.as_deref() {
+            Some("public") => Visibility::Public,
+            Some("private") => Visibility::Private,
+            Some("inherit") | None => Visibility::Inherit,
             _ => return Err("Invalid visibility type".into()),
         };
 
         let file = File::new(
             ObjectId::new(),
             owner,
+            team,
             full_filename,
             visibility,
             &self.position,
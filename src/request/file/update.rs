@@ -1,15 +1,17 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::{
     base::{
         file::{File, Visibility},
+        team::Team,
         user::User,
     },
     validation::file::{check_dir, check_visibility},
     Result,
 };
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateFileRequest {
     #[validate(custom = "check_dir")]
@@ -24,18 +26,21 @@ impl UpdateFileRequest {
         full_filename: Option<&str>,
         old_file: File,
         owner: &User,
+        team: Option<&Team>,
     ) -> Result<File> {
         self.validate()?;
 
         let visibility = match self.visibility.as_str() {
             "public" => Visibility::Public,
             "private" => Visibility::Private,
+            "inherit" => Visibility::Inherit,
             _ => return Err("Invalid visibility type".into()),
         };
 
         File::new(
             old_file.id,
             owner,
+            team,
             full_filename.unwrap_or(&old_file.full_filename),
             visibility,
             &self.position.unwrap_or(old_file.position),
@@ -0,0 +1,31 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{base::file_request::FileRequest, Result};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFileRequestRequest {
+    // In bytes, for every uploaded file
+    pub max_file_size: Option<i64>,
+    // How many files can be uploaded through the link in total
+    pub max_files: Option<i64>,
+    // In milliseconds, the link never expires if this is omitted
+    pub expires_at: Option<i64>,
+}
+
+impl CreateFileRequestRequest {
+    pub fn into_file_request(self, folder: &ObjectId, creator: &ObjectId) -> Result<FileRequest> {
+        self.validate()?;
+
+        FileRequest::new(
+            *folder,
+            *creator,
+            self.max_file_size,
+            self.max_files,
+            self.expires_at,
+        )
+    }
+}
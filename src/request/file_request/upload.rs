@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{base::file::Uploader, validation::file::check_uploader_name, Result};
+
+// The form fields sent along with the file to a drop link
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DropFileRequest {
+    #[validate(custom = "check_uploader_name")]
+    pub uploader_name: Option<String>,
+    #[validate(email(message = "The email must be in correct form"))]
+    pub uploader_email: Option<String>,
+}
+
+impl DropFileRequest {
+    pub fn into_uploader(self) -> Result<Uploader> {
+        self.validate()?;
+
+        if self.uploader_name.is_none() && self.uploader_email.is_none() {
+            return Err("Please leave your name or your email".into());
+        }
+
+        Ok(Uploader {
+            name: self.uploader_name.map(|n| n.trim().to_string()),
+            email: self.uploader_email,
+        })
+    }
+}
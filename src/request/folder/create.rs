@@ -1,44 +1,68 @@
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::{
     base::{
         folder::{Folder, Visibility},
+        team::Team,
         user::User,
     },
     validation::file::{check_dir, check_folder_name, check_visibility},
     Result,
 };
 
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+pub fn into_visibility(visibility: &str) -> Result<Visibility> {
+    match visibility {
+        "public" => Ok(Visibility::Public),
+        "private" => Ok(Visibility::Private),
+        "inherit" => Ok(Visibility::Inherit),
+        _ => Err("Invalid visibility".into()),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFolderRequest {
     #[validate(custom = "check_folder_name")]
     pub folder_name: String,
+    // Without a visibility, the folder gets the default of the folder it is created in
+    #[validate(custom = "check_visibility")]
+    pub visibility: Option<String>,
+    // The default visibility of the files and folders created directly inside this folder
     #[validate(custom = "check_visibility")]
-    pub visibility: String,
+    pub child_visibility: Option<String>,
     #[validate(custom = "check_dir")]
     pub position: String,
+    // The id of the team, when creating the folder in the tree of a team
+    pub team: Option<String>,
 }
 
 impl CreateFolderRequest {
-    pub fn into_folder(self, owner: &User) -> Result<Folder> {
+    pub fn into_folder(self, owner: &User, team: Option<&Team>) -> Result<Folder> {
         self.validate()?;
 
-        let visibility = match self.visibility.as_str() {
-            "public" => Visibility::Public,
-            "private" => Visibility::Private,
-            _ => return Err("Invalid visibility".into()),
+        // The handler replaces inherit with the default of the folder when it was not asked for
+        let visibility = match self.visibility.as_deref() {
+            Some(v) => into_visibility(v)?,
+            None => Visibility::Inherit,
         };
 
-        Folder::new(
+        let mut folder = Folder::new(
             ObjectId::new(),
             owner,
+            team,
             &self.folder_name,
             visibility,
             &self.position,
             None,
-        )
+        )?;
+        folder.child_visibility = self
+            .child_visibility
+            .as_deref()
+            .map(into_visibility)
+            .transpose()?;
+        Ok(folder)
     }
 }
@@ -1,2 +1,3 @@
 pub mod create;
 pub mod update;
+pub mod visibility;
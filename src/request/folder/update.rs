@@ -1,47 +1,56 @@
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::{
-    base::{
-        folder::{Folder, Visibility},
-        user::User,
-    },
+    base::{folder::Folder, team::Team, user::User},
+    request::folder::create::into_visibility,
     validation::file::{check_dir, check_folder_name, check_visibility},
     Result,
 };
 
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateFolderRequest {
     #[validate(custom = "check_folder_name")]
     pub folder_name: Option<String>,
     #[validate(custom = "check_visibility")]
     pub visibility: Option<String>,
+    #[validate(custom = "check_visibility")]
+    pub child_visibility: Option<String>,
     #[validate(custom = "check_dir")]
     pub position: String,
 }
 
 impl UpdateFolderRequest {
-    pub fn into_folder(self, owner: &User, old_folder: Folder) -> Result<Folder> {
+    pub fn into_folder(
+        self,
+        owner: &User,
+        team: Option<&Team>,
+        old_folder: Folder,
+    ) -> Result<Folder> {
         self.validate()?;
 
-        let visibility = match self.visibility {
-            Some(v) => match v.as_str() {
-                "public" => Visibility::Public,
-                "private" => Visibility::Private,
-                _ => return Err("Invalid visibility type".into()),
-            },
+        let visibility = match self.visibility.as_deref() {
+            Some(v) => into_visibility(v)?,
             None => old_folder.visibility,
         };
+        let child_visibility = match self.child_visibility.as_deref() {
+            Some(v) => Some(into_visibility(v)?),
+            None => old_folder.child_visibility,
+        };
 
-        Folder::new(
+        let mut folder = Folder::new(
             ObjectId::new(),
             owner,
+            team,
             &self.folder_name.unwrap_or(old_folder.folder_name),
             visibility,
             &self.position,
             Some(old_folder.created_at),
-        )
+        )?;
+        folder.child_visibility = child_visibility;
+        Ok(folder)
     }
 }
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::folder::Visibility, request::folder::create::into_visibility,
+    validation::file::check_visibility, Result,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVisibilityRequest {
+    #[validate(custom = "check_visibility")]
+    pub visibility: String,
+    // Applies the visibility to everything inside the folder as well, false by default
+    pub recursive: Option<bool>,
+}
+
+impl UpdateVisibilityRequest {
+    pub fn into_visibility(self) -> Result<(Visibility, bool)> {
+        self.validate()?;
+        Ok((
+            into_visibility(&self.visibility)?,
+            self.recursive.unwrap_or(false),
+        ))
+    }
+}
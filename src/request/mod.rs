@@ -1,3 +1,10 @@
+pub mod admin;
 pub mod file;
+pub mod file_request;
 pub mod folder;
+pub mod share;
+pub mod share_link;
+pub mod tag;
+pub mod team;
 pub mod user;
+pub mod webhook;
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::acl::Role,
+    validation::{file::check_role, user::check_username},
+    Result,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareRequest {
+    // The user to share with
+    #[validate(custom = "check_username")]
+    pub username: String,
+    #[validate(custom = "check_role")]
+    pub role: String,
+}
+
+impl ShareRequest {
+    pub fn into_share(self) -> Result<(String, Role)> {
+        self.validate()?;
+
+        let role = match self.role.as_str() {
+            "viewer" => Role::Viewer,
+            "editor" => Role::Editor,
+            _ => return Err("Invalid role".into()),
+        };
+
+        Ok((self.username, role))
+    }
+}
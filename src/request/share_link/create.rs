@@ -0,0 +1,31 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{base::share_link::ShareLink, validation::file::check_link_password, Result};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShareLinkRequest {
+    #[validate(custom = "check_link_password")]
+    pub password: Option<String>,
+    // Whether the visitors can upload files into the folder, false by default
+    pub allow_upload: Option<bool>,
+    // In milliseconds, the link never expires if this is omitted
+    pub expires_at: Option<i64>,
+}
+
+impl CreateShareLinkRequest {
+    pub fn into_link(self, folder: &ObjectId, creator: &ObjectId) -> Result<ShareLink> {
+        self.validate()?;
+
+        ShareLink::new(
+            *folder,
+            *creator,
+            self.password.as_deref(),
+            self.allow_upload.unwrap_or(false),
+            self.expires_at,
+        )
+    }
+}
@@ -0,0 +1,23 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{helper::into_string, validation::file::check_metadata, Result};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataRequest {
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl MetadataRequest {
+    pub fn into_metadata(self) -> Result<BTreeMap<String, String>> {
+        if self.metadata.is_empty() {
+            return Err("Please provide at least one metadata entry".into());
+        }
+        check_metadata(&self.metadata).map_err(into_string)?;
+        Ok(self.metadata)
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{helper::into_string, validation::file::check_tags, Result};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagsRequest {
+    pub tags: Vec<String>,
+}
+
+impl TagsRequest {
+    // Tags are case insensitive, so they are stored in lowercase
+    pub fn into_tags(self) -> Result<Vec<String>> {
+        let mut tags = self
+            .tags
+            .into_iter()
+            .map(|t| t.trim().to_lowercase())
+            .collect::<Vec<_>>();
+        tags.sort();
+        tags.dedup();
+
+        if tags.is_empty() {
+            return Err("Please provide at least one tag".into());
+        }
+        check_tags(&tags).map_err(into_string)?;
+        Ok(tags)
+    }
+}
@@ -0,0 +1,24 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::{team::Team, user::User},
+    validation::team::check_team_name,
+    Result,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTeamRequest {
+    #[validate(custom = "check_team_name")]
+    pub name: String,
+}
+
+impl CreateTeamRequest {
+    pub fn into_team(self, owner: &User) -> Result<Team> {
+        self.validate()?;
+        Team::new(ObjectId::new(), &self.name, owner)
+    }
+}
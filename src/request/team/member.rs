@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::team::TeamRole,
+    validation::{team::check_team_role, user::check_username},
+    Result,
+};
+
+fn into_team_role(role: &str) -> Result<TeamRole> {
+    match role {
+        "admin" => Ok(TeamRole::Admin),
+        "member" => Ok(TeamRole::Member),
+        _ => Err("Invalid team role".into()),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMemberRequest {
+    // The user to add to the team
+    #[validate(custom = "check_username")]
+    pub username: String,
+    #[validate(custom = "check_team_role")]
+    pub role: String,
+}
+
+impl AddMemberRequest {
+    pub fn into_member(self) -> Result<(String, TeamRole)> {
+        self.validate()?;
+        let role = into_team_role(&self.role)?;
+        Ok((self.username, role))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMemberRequest {
+    #[validate(custom = "check_team_role")]
+    pub role: String,
+}
+
+impl UpdateMemberRequest {
+    pub fn into_role(self) -> Result<TeamRole> {
+        self.validate()?;
+        into_team_role(&self.role)
+    }
+}
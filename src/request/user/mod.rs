@@ -1,4 +1,8 @@
 pub mod create;
 pub mod delete;
 pub mod login;
+pub mod password;
+pub mod restore;
+pub mod two_factor;
 pub mod update;
+pub mod verify;
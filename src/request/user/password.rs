@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::validation::user::check_password;
+use crate::Result;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordRequest {
+    #[validate(email(message = "The email must be in correct form"))]
+    pub email: String,
+}
+
+impl ForgotPasswordRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+// Confirms the reset with the token from the mail
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1, message = "The token cannot be empty"))]
+    pub token: String,
+
+    #[validate(custom(function = "check_password"))]
+    pub new_password: String,
+
+    #[validate(must_match(
+        other = "new_password",
+        message = "The newPassword must match with confirmPassword"
+    ))]
+    pub confirm_password: String,
+}
+
+impl ResetPasswordRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::validation::user::{check_password, check_two_factor_code, check_username};
+use crate::Result;
+
+// The account cannot log in while it is pending deletion, so it proves itself like a login does
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+pub struct RestoreUserRequest {
+    #[validate(custom = "check_username")]
+    pub username: String,
+
+    #[validate(custom = "check_password")]
+    pub password: String,
+
+    // Only for the accounts with two-factor authentication, a code or a recovery code
+    #[validate(custom = "check_two_factor_code")]
+    pub code: Option<String>,
+}
+
+impl RestoreUserRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
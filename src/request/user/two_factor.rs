@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::validation::user::{check_password, check_two_factor_code};
+use crate::Result;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeRequest {
+    #[validate(custom = "check_two_factor_code")]
+    pub code: String,
+}
+
+impl TwoFactorCodeRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+// The second step of a login, with the token returned by the first one
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorLoginRequest {
+    pub challenge_token: String,
+
+    #[validate(custom = "check_two_factor_code")]
+    pub code: String,
+}
+
+impl TwoFactorLoginRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DisableTwoFactorRequest {
+    #[validate(custom = "check_password")]
+    pub password: String,
+
+    #[validate(custom = "check_two_factor_code")]
+    pub code: String,
+}
+
+impl DisableTwoFactorRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
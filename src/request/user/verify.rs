@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::Result;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1, message = "The token cannot be empty"))]
+    pub token: String,
+}
+
+impl VerifyEmailRequest {
+    pub fn validate_self(self) -> Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::{user::User, webhook::Webhook},
+    error::Error,
+    validation::webhook::{check_webhook_events, check_webhook_url},
+    Result,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    #[validate(custom = "check_webhook_url")]
+    pub url: String,
+    // Like file.created or version.restored
+    #[validate(custom = "check_webhook_events")]
+    pub events: Vec<String>,
+    // Only for the admins, a global webhook hears about every file and folder on the server
+    pub global: Option<bool>,
+}
+
+impl CreateWebhookRequest {
+    pub fn into_webhook(mut self, owner: &User) -> Result<Webhook> {
+        self.validate()?;
+
+        let global = self.global.unwrap_or(false);
+        if global && !owner.is_admin() {
+            return Err(Error::Permissions(
+                "Only the admins can create a global webhook".into(),
+            ));
+        }
+
+        self.events.sort();
+        self.events.dedup();
+        Ok(Webhook::new(owner.id, &self.url, self.events, global))
+    }
+}
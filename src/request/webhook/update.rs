@@ -0,0 +1,42 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::webhook::Webhook,
+    validation::webhook::{check_webhook_events, check_webhook_url},
+    Result,
+};
+
+// Everything that is left out stays the same
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookRequest {
+    #[validate(custom = "check_webhook_url")]
+    pub url: Option<String>,
+    #[validate(custom = "check_webhook_events")]
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+impl UpdateWebhookRequest {
+    pub fn into_webhook(self, old_webhook: &Webhook) -> Result<Webhook> {
+        self.validate()?;
+
+        let mut webhook = old_webhook.clone();
+        if let Some(url) = self.url {
+            webhook.url = url;
+        }
+        if let Some(mut events) = self.events {
+            events.sort();
+            events.dedup();
+            webhook.events = events;
+        }
+        if let Some(active) = self.active {
+            webhook.active = active;
+        }
+        webhook.updated_at = Utc::now().timestamp_millis();
+        Ok(webhook)
+    }
+}
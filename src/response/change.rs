@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::base::{change::Change, search_entry::ResourceKind};
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeResponse {
+    pub seq: i64,
+    // created, updated, moved or deleted
+    pub action: String,
+    // file or folder
+    pub kind: String,
+    // The id of the file or folder
+    pub id: String,
+    pub owner: String,
+    pub team: Option<String>,
+    pub position: String,
+    pub fullpath: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_fullpath: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<Change> for ChangeResponse {
+    fn from(c: Change) -> Self {
+        let kind = match c.kind {
+            ResourceKind::File => "file",
+            ResourceKind::Folder => "folder",
+        };
+        Self {
+            seq: c.seq,
+            action: c.action.as_str().to_string(),
+            kind: kind.to_string(),
+            id: c.resource.to_string(),
+            owner: c.owner.to_string(),
+            team: c.team.map(|t| t.to_string()),
+            position: c.position,
+            fullpath: c.fullpath,
+            previous_fullpath: c.previous_fullpath,
+            created_at: c.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangesResponse {
+    pub changes: Vec<ChangeResponse>,
+    // Where to continue from next time
+    pub cursor: String,
+    // There are more changes right away, ask again with the new cursor
+    pub has_more: bool,
+}
+
+impl ChangesResponse {
+    pub fn new(changes: Vec<Change>, cursor: i64, has_more: bool) -> Self {
+        Self {
+            changes: changes.into_iter().map(|c| c.into_response()).collect(),
+            cursor: cursor.to_string(),
+            has_more,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::base::event::Event;
+
+// The data of one event in the /events stream, the name of the event is like file.created
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventResponse {
+    pub event: String,
+    // What happened, like created
+    pub action: String,
+    // file or folder
+    pub kind: String,
+    // The id of the file or folder
+    pub id: String,
+    pub owner: String,
+    pub team: Option<String>,
+    pub position: String,
+    pub fullpath: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_fullpath: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<Event> for EventResponse {
+    fn from(e: Event) -> Self {
+        Self {
+            event: e.name(),
+            action: e.kind.as_str().to_string(),
+            kind: e.resource_kind_to_str().to_string(),
+            id: e.resource.to_string(),
+            owner: e.owner.to_string(),
+            team: e.team.map(|t| t.to_string()),
+            position: e.position,
+            fullpath: e.fullpath,
+            previous_fullpath: e.previous_fullpath,
+            created_at: e.created_at,
+        }
+    }
+}
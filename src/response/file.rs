@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
-use crate::base::file::File;
+use crate::base::file::{File, Uploader};
 use crate::base::file_version::FileVersion;
 use crate::base::user::User;
 use crate::error::Error;
@@ -10,15 +13,18 @@ use crate::Result;
 
 use super::user::UserResponse;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FileResponse {
     pub id: String,
     pub owner: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
     #[validate(custom = "check_filename")]
     pub filename: String,
     #[validate(custom = "check_extension")]
     pub extension: String,
+    pub mime_type: String,
     #[validate(custom = "check_visibility")]
     pub visibility: String,
     #[validate(custom = "check_full_filename")]
@@ -28,6 +34,11 @@ pub struct FileResponse {
     #[validate(custom = "check_fullpath")]
     pub fullpath: String,
 
+    pub tags: Vec<String>,
+    pub metadata: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uploaded_by: Option<Uploader>,
+
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -41,12 +52,17 @@ impl TryFrom<File> for FileResponse {
         let file_res = Self {
             id: f.id.to_string(),
             owner: f.owner.to_string(),
+            team: f.team.map(|t| t.to_string()),
             filename: f.filename,
             extension,
+            mime_type: f.mime_type,
             visibility,
             full_filename: f.full_filename,
             position: f.position,
             fullpath: f.fullpath,
+            tags: f.tags,
+            metadata: f.metadata,
+            uploaded_by: f.uploaded_by,
             created_at: f.created_at,
             updated_at: f.updated_at,
         };
@@ -55,7 +71,7 @@ impl TryFrom<File> for FileResponse {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FinalFileResponse {
     #[serde(flatten)]
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    base::{file::File, file_request::FileRequest, folder::Folder},
+    error::Error,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRequestResponse {
+    pub id: String,
+    pub token: String,
+    pub folder: String,
+    pub creator: String,
+    pub max_file_size: Option<i64>,
+    pub max_files: Option<i64>,
+    pub upload_count: i64,
+    pub expires_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<FileRequest> for FileRequestResponse {
+    type Error = Error;
+    fn try_from(r: FileRequest) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            id: r.id.to_string(),
+            token: r.token,
+            folder: r.folder.to_string(),
+            creator: r.creator.to_string(),
+            max_file_size: r.max_file_size,
+            max_files: r.max_files,
+            upload_count: r.upload_count,
+            expires_at: r.expires_at,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+    }
+}
+
+// What a visitor of a drop link sees, nothing about the content of the folder
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DropInfoResponse {
+    pub folder_name: String,
+    pub max_file_size: Option<i64>,
+    // None when there is no limit on the number of files
+    pub remaining_files: Option<i64>,
+    pub expires_at: Option<i64>,
+}
+
+impl DropInfoResponse {
+    pub fn new(request: &FileRequest, folder: &Folder) -> Self {
+        Self {
+            folder_name: folder.folder_name.clone(),
+            max_file_size: request.max_file_size,
+            remaining_files: request.max_files.map(|m| (m - request.upload_count).max(0)),
+            expires_at: request.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DropUploadResponse {
+    pub full_filename: String,
+    pub created_at: i64,
+    pub remaining_files: Option<i64>,
+}
+
+impl DropUploadResponse {
+    // The name is the one that was uploaded, the number that a taken name gets is not shown to the visitor
+    pub fn new(full_filename: &str, file: &File, request: &FileRequest) -> Self {
+        Self {
+            full_filename: full_filename.to_string(),
+            created_at: file.created_at,
+            remaining_files: request.max_files.map(|m| (m - request.upload_count).max(0)),
+        }
+    }
+}
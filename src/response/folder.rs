@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::{
@@ -10,24 +13,31 @@ use crate::{
 
 use super::user::UserResponse;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FolderResponse {
     pub id: String,
 
     pub owner: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
 
     #[validate(custom = "check_folder_name")]
     pub folder_name: String,
 
     #[validate(custom = "check_visibility")]
     pub visibility: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub child_visibility: Option<String>,
 
     #[validate(custom = "check_dir")]
     pub position: String,
     #[validate(custom = "check_dir")]
     pub fullpath: String,
 
+    pub tags: Vec<String>,
+    pub metadata: BTreeMap<String, String>,
+
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -40,10 +50,14 @@ impl TryFrom<Folder> for FolderResponse {
         let folder_res = Self {
             id: f.id.to_string(),
             owner: f.owner.to_string(),
+            team: f.team.map(|t| t.to_string()),
             folder_name: f.folder_name,
             visibility,
+            child_visibility: f.child_visibility.map(|v| v.as_str().to_string()),
             position: f.position,
             fullpath: f.fullpath,
+            tags: f.tags,
+            metadata: f.metadata,
             created_at: f.created_at,
             updated_at: f.updated_at,
         };
@@ -53,7 +67,7 @@ impl TryFrom<Folder> for FolderResponse {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FinalFolderResponse {
     #[serde(flatten)]
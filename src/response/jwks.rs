@@ -0,0 +1,56 @@
+use jsonwebtoken::Algorithm;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// A public key in the JWK format, https://www.rfc-editor.org/rfc/rfc7517
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct PublicJwk {
+    pub kty: String,
+    pub kid: String,
+    #[schema(value_type = String)]
+    pub alg: Algorithm,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+}
+
+impl PublicJwk {
+    pub fn rsa(kid: &str, alg: Algorithm, n: &str, e: &str) -> Self {
+        Self {
+            kty: "RSA".to_string(),
+            kid: kid.to_string(),
+            alg,
+            key_use: "sig".to_string(),
+            n: Some(n.to_string()),
+            e: Some(e.to_string()),
+            crv: None,
+            x: None,
+        }
+    }
+
+    pub fn ed25519(kid: &str, x: &str) -> Self {
+        Self {
+            kty: "OKP".to_string(),
+            kid: kid.to_string(),
+            alg: Algorithm::EdDSA,
+            key_use: "sig".to_string(),
+            n: None,
+            e: None,
+            crv: Some("Ed25519".to_string()),
+            x: Some(x.to_string()),
+        }
+    }
+}
+
+// Served as is, since the other services expect the standard shape and not the Web wrapper
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct JwksResponse {
+    pub keys: Vec<PublicJwk>,
+}
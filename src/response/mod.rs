@@ -1,7 +1,19 @@
+pub mod change;
+pub mod event;
 pub mod file;
+pub mod file_request;
 pub mod folder;
+pub mod jwks;
+pub mod search;
+pub mod share;
+pub mod share_link;
+pub mod tag;
+pub mod team;
+pub mod two_factor;
 pub mod user;
+pub mod webhook;
 
 pub use self::file::FinalFileResponse;
 pub use self::folder::FinalFolderResponse;
+pub use self::team::FinalTeamResponse;
 pub use self::user::FinalUserResponse;
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    helper::snippet::highlight,
+    service::search_service::{SearchResource, SearchResult},
+    Result,
+};
+
+use super::{file::FileResponse, folder::FolderResponse};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    pub kind: String,
+    pub score: f64,
+    // A piece of the name or the content with the matches wrapped in <mark></mark>
+    pub snippet: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<FileResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderResponse>,
+}
+
+impl SearchResponse {
+    pub fn new(result: SearchResult, terms: &[String]) -> Result<Self> {
+        let entry = &result.hit.entry;
+
+        // Prefer showing where the match is in the content, otherwise show the name
+        let snippet = highlight(&entry.content, terms)
+            .or_else(|| highlight(&entry.name, terms))
+            .unwrap_or_else(|| entry.name.clone());
+
+        let kind = entry.kind_to_str().to_string();
+
+        let (file, folder) = match result.resource {
+            SearchResource::File(f) => (Some(f.into_response()?), None),
+            SearchResource::Folder(f) => (None, Some(f.into_response()?)),
+        };
+
+        Ok(Self {
+            kind,
+            score: result.hit.score,
+            snippet,
+            file,
+            folder,
+        })
+    }
+}
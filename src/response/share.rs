@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    base::{
+        acl::{AclEntry, Role},
+        file::File,
+        folder::Folder,
+        user::User,
+    },
+    Result,
+};
+
+use super::{file::FileResponse, folder::FolderResponse, user::UserResponse};
+
+// A user that a file or folder is shared with
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareResponse {
+    pub user: UserResponse,
+    pub role: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl ShareResponse {
+    pub fn new(entry: AclEntry, grantee: User) -> Result<Self> {
+        Ok(Self {
+            user: grantee.into_response()?,
+            role: entry.role.as_str().to_string(),
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+        })
+    }
+}
+
+// A file or folder that has been shared with the logged in user
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedResponse {
+    pub kind: String,
+    pub role: String,
+    pub owner: UserResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<FileResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<FolderResponse>,
+}
+
+impl SharedResponse {
+    pub fn from_file(file: File, role: Role, owner: User) -> Result<Self> {
+        Ok(Self {
+            kind: "file".to_string(),
+            role: role.as_str().to_string(),
+            owner: owner.into_response()?,
+            file: Some(file.into_response()?),
+            folder: None,
+        })
+    }
+
+    pub fn from_folder(folder: Folder, role: Role, owner: User) -> Result<Self> {
+        Ok(Self {
+            kind: "folder".to_string(),
+            role: role.as_str().to_string(),
+            owner: owner.into_response()?,
+            file: None,
+            folder: Some(folder.into_response()?),
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    base::{file::File, folder::Folder, share_link::ShareLink},
+    error::Error,
+    Result,
+};
+
+use super::{file::FileResponse, folder::FolderResponse};
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLinkResponse {
+    pub id: String,
+    pub token: String,
+    pub folder: String,
+    pub creator: String,
+    pub has_password: bool,
+    pub allow_upload: bool,
+    pub expires_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<ShareLink> for ShareLinkResponse {
+    type Error = Error;
+    fn try_from(l: ShareLink) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            id: l.id.to_string(),
+            token: l.token,
+            folder: l.folder.to_string(),
+            creator: l.creator.to_string(),
+            has_password: l.password_hash.is_some(),
+            allow_upload: l.allow_upload,
+            expires_at: l.expires_at,
+            created_at: l.created_at,
+            updated_at: l.updated_at,
+        })
+    }
+}
+
+// What a visitor of a link sees at one position inside the shared folder
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkContentResponse {
+    // The shared folder
+    pub folder: FolderResponse,
+    // The position being listed, relative to the shared folder
+    pub path: String,
+    pub allow_upload: bool,
+    pub expires_at: Option<i64>,
+    pub folders: Vec<FolderResponse>,
+    pub files: Vec<FileResponse>,
+}
+
+impl LinkContentResponse {
+    pub fn new(
+        link: &ShareLink,
+        folder: Folder,
+        path: &str,
+        folders: Vec<Folder>,
+        files: Vec<File>,
+    ) -> Result<Self> {
+        let folders = folders
+            .into_iter()
+            .flat_map(|f| f.into_response())
+            .collect::<Vec<_>>();
+
+        let files = files
+            .into_iter()
+            .flat_map(|f| f.into_response())
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            folder: folder.into_response()?,
+            path: path.to_string(),
+            allow_upload: link.allow_upload,
+            expires_at: link.expires_at,
+            folders,
+            files,
+        })
+    }
+}
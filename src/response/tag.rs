@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCountResponse {
+    pub tag: String,
+    pub files: i64,
+    pub folders: i64,
+    pub total: i64,
+}
+
+impl TagCountResponse {
+    // Merges the tag counts of the files and the folders,
+    // the most used tags come first
+    pub fn from_counts(files: Vec<(String, i64)>, folders: Vec<(String, i64)>) -> Vec<Self> {
+        let mut counts: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        for (tag, count) in files {
+            counts.entry(tag).or_default().0 += count;
+        }
+        for (tag, count) in folders {
+            counts.entry(tag).or_default().1 += count;
+        }
+
+        let mut responses = counts
+            .into_iter()
+            .map(|(tag, (files, folders))| Self {
+                tag,
+                files,
+                folders,
+                total: files + folders,
+            })
+            .collect::<Vec<_>>();
+        responses.sort_by_key(|r| std::cmp::Reverse(r.total));
+        responses
+    }
+}
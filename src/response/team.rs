@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    base::{team::Team, user::User},
+    error::Error,
+    validation::team::check_team_name,
+    Result,
+};
+
+use super::user::UserResponse;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamResponse {
+    pub id: String,
+
+    #[validate(custom = "check_team_name")]
+    pub name: String,
+
+    // The fullpath of the root folder of the team
+    pub root: String,
+
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<Team> for TeamResponse {
+    type Error = Error;
+    fn try_from(t: Team) -> std::result::Result<Self, Self::Error> {
+        let team_res = Self {
+            id: t.id.to_string(),
+            root: format!("{}/", t.root_name()),
+            name: t.name,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+        };
+
+        team_res.validate()?;
+        Ok(team_res)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMemberResponse {
+    pub user: UserResponse,
+    pub role: String,
+    pub joined_at: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalTeamResponse {
+    #[serde(flatten)]
+    pub team: TeamResponse,
+    pub members: Vec<TeamMemberResponse>,
+}
+
+impl FinalTeamResponse {
+    // The members whose user cannot be found in the map are left out
+    pub fn new(team: Team, users: &HashMap<ObjectId, User>) -> Result<Self> {
+        let members = team
+            .members
+            .iter()
+            .filter_map(|m| {
+                let user = users.get(&m.user)?.clone().into_response().ok()?;
+                Some(TeamMemberResponse {
+                    user,
+                    role: m.role.as_str().to_string(),
+                    joined_at: m.joined_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            team: team.into_response()?,
+            members,
+        })
+    }
+}
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::base::file::File;
 use crate::base::folder::Folder;
-use crate::base::user::User;
+use crate::base::user::{Usage, User};
 use crate::error::Error;
 use crate::validation::user::check_username;
 use crate::Result;
@@ -11,7 +12,7 @@ use crate::Result;
 use super::file::FileResponse;
 use super::folder::FolderResponse;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserResponse {
     pub id: String,
@@ -20,6 +21,12 @@ pub struct UserResponse {
 
     #[validate(email(message = "The email must be in correct form"))]
     pub email: String,
+    pub email_verified: bool,
+    pub role: String,
+    pub status: String,
+    pub two_factor_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletion_scheduled_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -27,10 +34,16 @@ pub struct UserResponse {
 impl TryFrom<User> for UserResponse {
     type Error = Error;
     fn try_from(u: User) -> std::result::Result<Self, Self::Error> {
+        let two_factor_enabled = u.has_two_factor();
         let res = Self {
             id: u.id.to_string(),
             username: u.username,
             email: u.email,
+            email_verified: u.email_verified,
+            role: u.role.as_str().to_string(),
+            status: u.status.as_str().to_string(),
+            two_factor_enabled,
+            deletion_scheduled_at: u.deletion_scheduled_at,
             created_at: u.created_at,
             updated_at: u.updated_at,
         };
@@ -40,7 +53,29 @@ impl TryFrom<User> for UserResponse {
     }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageResponse {
+    pub user: String,
+    pub files: u64,
+    pub folders: u64,
+    pub versions: u64,
+    pub bytes: i64,
+}
+
+impl From<Usage> for UsageResponse {
+    fn from(u: Usage) -> Self {
+        Self {
+            user: u.user.to_string(),
+            files: u.files,
+            folders: u.folders,
+            versions: u.versions,
+            bytes: u.bytes,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FinalUserResponse {
     #[serde(flatten)]
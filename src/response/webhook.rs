@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    base::{
+        webhook::Webhook,
+        webhook_delivery::{DeliveryAttempt, WebhookDelivery},
+    },
+    error::Error,
+};
+
+use super::event::EventResponse;
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResponse {
+    pub id: String,
+    pub owner: String,
+    pub url: String,
+    // Only there right after the webhook is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+    pub global: bool,
+    pub active: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl TryFrom<Webhook> for WebhookResponse {
+    type Error = Error;
+    fn try_from(w: Webhook) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            id: w.id.to_string(),
+            owner: w.owner.to_string(),
+            url: w.url,
+            secret: None,
+            events: w.events,
+            global: w.global,
+            active: w.active,
+            created_at: w.created_at,
+            updated_at: w.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub webhook: String,
+    pub event_id: String,
+    pub event: String,
+    pub payload: String,
+    // pending, succeeded or failed
+    pub status: String,
+    pub attempts: Vec<DeliveryAttemptResponse>,
+    pub next_attempt_at: Option<i64>,
+    pub redelivery_of: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttemptResponse {
+    pub attempted_at: i64,
+    pub duration: i64,
+    pub status_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<DeliveryAttempt> for DeliveryAttemptResponse {
+    fn from(a: DeliveryAttempt) -> Self {
+        Self {
+            attempted_at: a.attempted_at,
+            duration: a.duration,
+            status_code: a.status_code,
+            response_body: a.response_body,
+            error: a.error,
+        }
+    }
+}
+
+impl TryFrom<WebhookDelivery> for WebhookDeliveryResponse {
+    type Error = Error;
+    fn try_from(d: WebhookDelivery) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            id: d.id.to_string(),
+            webhook: d.webhook.to_string(),
+            event_id: d.event_id.to_string(),
+            event: d.event,
+            payload: d.payload,
+            status: d.status.as_str().to_string(),
+            attempts: d.attempts.into_iter().map(|a| a.into()).collect(),
+            next_attempt_at: d.next_attempt_at,
+            redelivery_of: d.redelivery_of.map(|r| r.to_string()),
+            created_at: d.created_at,
+            updated_at: d.updated_at,
+        })
+    }
+}
+
+// The body of every delivery
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    // The same on every redelivery of the event
+    pub id: String,
+    pub event: String,
+    pub created_at: i64,
+    pub data: EventResponse,
+}
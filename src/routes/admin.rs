@@ -0,0 +1,57 @@
+use salvo::Router;
+
+use crate::{
+    handler::admin::user::{
+        delete_user_handler, get_user_handler, get_user_usage_handler, get_users_handler,
+        reset_password_handler, suspend_user_handler, unsuspend_user_handler,
+    },
+    middleware::{admin::check_admin_middleware, auth::check_login_middleware},
+};
+
+pub fn admin_routes() -> Router {
+    Router::with_path("admin")
+        .hoop(check_login_middleware)
+        .hoop(check_admin_middleware)
+        // /admin/users
+        .push(get_users_route())
+        // /admin/users/<param_user_id>/suspend
+        .push(suspend_user_route())
+        // /admin/users/<param_user_id>/unsuspend
+        .push(unsuspend_user_route())
+        // /admin/users/<param_user_id>/password
+        .push(reset_password_route())
+        // /admin/users/<param_user_id>/usage
+        .push(get_user_usage_route())
+        // /admin/users/<param_user_id>/delete
+        .push(delete_user_route())
+        // /admin/users/<param_user_id>
+        .push(get_user_route())
+}
+
+pub fn get_users_route() -> Router {
+    Router::with_path("users").get(get_users_handler)
+}
+
+pub fn get_user_route() -> Router {
+    Router::with_path("users/<param_user_id>").get(get_user_handler)
+}
+
+pub fn suspend_user_route() -> Router {
+    Router::with_path("users/<param_user_id>/suspend").put(suspend_user_handler)
+}
+
+pub fn unsuspend_user_route() -> Router {
+    Router::with_path("users/<param_user_id>/unsuspend").put(unsuspend_user_handler)
+}
+
+pub fn reset_password_route() -> Router {
+    Router::with_path("users/<param_user_id>/password").put(reset_password_handler)
+}
+
+pub fn get_user_usage_route() -> Router {
+    Router::with_path("users/<param_user_id>/usage").get(get_user_usage_handler)
+}
+
+pub fn delete_user_route() -> Router {
+    Router::with_path("users/<param_user_id>/delete").delete(delete_user_handler)
+}
@@ -0,0 +1,10 @@
+use salvo::Router;
+
+use crate::{handler::change::get_changes_handler, middleware::auth::check_login_middleware};
+
+pub fn change_routes() -> Router {
+    // /changes?cursor=
+    Router::with_path("changes")
+        .hoop(check_login_middleware)
+        .get(get_changes_handler)
+}
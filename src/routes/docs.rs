@@ -0,0 +1,11 @@
+use salvo::Router;
+
+use crate::handler::docs::{get_docs_handler, get_openapi_handler};
+
+pub fn docs_routes() -> Router {
+    Router::new()
+        // /openapi.json
+        .push(Router::with_path("openapi.json").get(get_openapi_handler))
+        // /docs
+        .push(Router::with_path("docs").get(get_docs_handler))
+}
@@ -0,0 +1,20 @@
+use salvo::Router;
+
+use crate::{
+    handler::drop::{get_drop_handler, upload_drop_handler},
+    middleware::{file_request::get_file_request_by_token_middleware, rate_limit::RateLimit},
+};
+
+// The drop routes do not need the user to be logged in, the token is enough
+pub fn drop_routes() -> Router {
+    // drop/<drop_token>
+    Router::with_path("drop/<drop_token>")
+        .hoop(get_file_request_by_token_middleware)
+        .get(get_drop_handler)
+        // Anyone with the token can upload, so the uploads are limited per IP
+        .push(
+            Router::new()
+                .hoop(RateLimit::per_minute(30))
+                .post(upload_drop_handler),
+        )
+}
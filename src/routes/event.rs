@@ -0,0 +1,10 @@
+use salvo::Router;
+
+use crate::{handler::event::events_handler, middleware::auth::check_login_middleware};
+
+pub fn event_routes() -> Router {
+    // /events
+    Router::with_path("events")
+        .hoop(check_login_middleware)
+        .get(events_handler)
+}
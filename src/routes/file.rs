@@ -7,6 +7,11 @@ use crate::{
             delete::delete_file_handler,
             get::{get_file_by_id_handler, get_files_handler},
             restore::restore_file_handler,
+            share::{get_file_shares_handler, share_file_handler, unshare_file_handler},
+            tag::{
+                add_file_tags_handler, delete_file_metadata_handler, remove_file_tags_handler,
+                update_file_metadata_handler,
+            },
             update::update_file_handler,
         },
         version::{
@@ -14,7 +19,9 @@ use crate::{
             get::{get_version_handler, get_versions_handler},
         },
     },
-    middleware::{auth::check_login_middleware, file::get_file_by_id_middleware},
+    middleware::{
+        auth::check_login_middleware, file::get_file_by_id_middleware, rate_limit::RateLimit,
+    },
 };
 
 pub fn file_routes() -> Router {
@@ -27,6 +34,12 @@ pub fn file_routes() -> Router {
         .push(delete_version_route()) // file/<param_file_id>/versions/delete/<version_number>
         .push(get_file_version_route()) // file/<param_file_id>/versions/<version_number>
         .push(get_file_versions_route()) // file/<param_file_id>/versions/
+        .push(add_file_tags_route()) // file/<param_file_id>/tags/add
+        .push(remove_file_tags_route()) // file/<param_file_id>/tags/remove
+        .push(update_file_metadata_route()) // file/<param_file_id>/metadata/update
+        .push(delete_file_metadata_route()) // file/<param_file_id>/metadata/delete/<metadata_key>
+        .push(share_file_route()) // file/<param_file_id>/share
+        .push(unshare_file_route()) // file/<param_file_id>/share/delete/<param_user_id>
         .push(get_file_route()) // file/<param_file_id>
 }
 
@@ -45,12 +58,14 @@ pub fn get_file_route() -> Router {
 
 pub fn create_file_route() -> Router {
     Router::with_path("create")
+        .hoop(RateLimit::per_minute(60))
         .hoop(check_login_middleware)
         .post(create_file_handler)
 }
 
 pub fn update_file_route() -> Router {
     Router::with_path("update/<param_file_id>")
+        .hoop(RateLimit::per_minute(60))
         .hoop(check_login_middleware)
         .hoop(get_file_by_id_middleware)
         .put(update_file_handler)
@@ -90,3 +105,46 @@ pub fn delete_version_route() -> Router {
         .hoop(get_file_by_id_middleware)
         .delete(delete_file_version_handler)
 }
+
+pub fn add_file_tags_route() -> Router {
+    Router::with_path("<param_file_id>/tags/add")
+        .hoop(check_login_middleware)
+        .hoop(get_file_by_id_middleware)
+        .put(add_file_tags_handler)
+}
+
+pub fn remove_file_tags_route() -> Router {
+    Router::with_path("<param_file_id>/tags/remove")
+        .hoop(check_login_middleware)
+        .hoop(get_file_by_id_middleware)
+        .put(remove_file_tags_handler)
+}
+
+pub fn update_file_metadata_route() -> Router {
+    Router::with_path("<param_file_id>/metadata/update")
+        .hoop(check_login_middleware)
+        .hoop(get_file_by_id_middleware)
+        .put(update_file_metadata_handler)
+}
+
+pub fn delete_file_metadata_route() -> Router {
+    Router::with_path("<param_file_id>/metadata/delete/<metadata_key>")
+        .hoop(check_login_middleware)
+        .hoop(get_file_by_id_middleware)
+        .delete(delete_file_metadata_handler)
+}
+
+pub fn share_file_route() -> Router {
+    Router::with_path("<param_file_id>/share")
+        .hoop(check_login_middleware)
+        .hoop(get_file_by_id_middleware)
+        .get(get_file_shares_handler)
+        .post(share_file_handler)
+}
+
+pub fn unshare_file_route() -> Router {
+    Router::with_path("<param_file_id>/share/delete/<param_user_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_file_by_id_middleware)
+        .delete(unshare_file_handler)
+}
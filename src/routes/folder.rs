@@ -4,8 +4,18 @@ use crate::{
     handler::folder::{
         create::create_folder_handler,
         delete::delete_folder_handler,
+        file_request::{
+            create_file_request_handler, delete_file_request_handler, get_file_requests_handler,
+        },
         get::{get_folder_by_id_handler, get_folders_handler},
+        link::{create_folder_link_handler, delete_folder_link_handler, get_folder_links_handler},
+        share::{get_folder_shares_handler, share_folder_handler, unshare_folder_handler},
+        tag::{
+            add_folder_tags_handler, delete_folder_metadata_handler, remove_folder_tags_handler,
+            update_folder_metadata_handler,
+        },
         update::update_folder_handler,
+        visibility::update_folder_visibility_handler,
     },
     middleware::{auth::check_login_middleware, folder::get_folder_by_id_middleware},
 };
@@ -16,6 +26,17 @@ pub fn folder_routes() -> Router {
         .push(create_folder_route()) // folder/create/
         .push(update_folder_route()) // folder/update/<param_folder_id>
         .push(delete_folder_route()) // folder/delete/<param_folder_id>
+        .push(add_folder_tags_route()) // folder/<param_folder_id>/tags/add
+        .push(remove_folder_tags_route()) // folder/<param_folder_id>/tags/remove
+        .push(update_folder_metadata_route()) // folder/<param_folder_id>/metadata/update
+        .push(delete_folder_metadata_route()) // folder/<param_folder_id>/metadata/delete/<metadata_key>
+        .push(share_folder_route()) // folder/<param_folder_id>/share
+        .push(unshare_folder_route()) // folder/<param_folder_id>/share/delete/<param_user_id>
+        .push(update_folder_visibility_route()) // folder/<param_folder_id>/visibility
+        .push(folder_links_route()) // folder/<param_folder_id>/links
+        .push(delete_folder_link_route()) // folder/<param_folder_id>/links/delete/<param_link_id>
+        .push(file_requests_route()) // folder/<param_folder_id>/requests
+        .push(delete_file_request_route()) // folder/<param_folder_id>/requests/delete/<param_file_request_id>
         .push(get_folder_route()) // folder/<param_folder_id>
 }
 
@@ -51,3 +72,83 @@ pub fn delete_folder_route() -> Router {
         .hoop(get_folder_by_id_middleware)
         .delete(delete_folder_handler)
 }
+
+pub fn add_folder_tags_route() -> Router {
+    Router::with_path("<param_folder_id>/tags/add")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .put(add_folder_tags_handler)
+}
+
+pub fn remove_folder_tags_route() -> Router {
+    Router::with_path("<param_folder_id>/tags/remove")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .put(remove_folder_tags_handler)
+}
+
+pub fn update_folder_metadata_route() -> Router {
+    Router::with_path("<param_folder_id>/metadata/update")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .put(update_folder_metadata_handler)
+}
+
+pub fn delete_folder_metadata_route() -> Router {
+    Router::with_path("<param_folder_id>/metadata/delete/<metadata_key>")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .delete(delete_folder_metadata_handler)
+}
+
+pub fn share_folder_route() -> Router {
+    Router::with_path("<param_folder_id>/share")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .get(get_folder_shares_handler)
+        .post(share_folder_handler)
+}
+
+pub fn unshare_folder_route() -> Router {
+    Router::with_path("<param_folder_id>/share/delete/<param_user_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .delete(unshare_folder_handler)
+}
+
+pub fn folder_links_route() -> Router {
+    Router::with_path("<param_folder_id>/links")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .get(get_folder_links_handler)
+        .post(create_folder_link_handler)
+}
+
+pub fn delete_folder_link_route() -> Router {
+    Router::with_path("<param_folder_id>/links/delete/<param_link_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .delete(delete_folder_link_handler)
+}
+
+pub fn file_requests_route() -> Router {
+    Router::with_path("<param_folder_id>/requests")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .get(get_file_requests_handler)
+        .post(create_file_request_handler)
+}
+
+pub fn delete_file_request_route() -> Router {
+    Router::with_path("<param_folder_id>/requests/delete/<param_file_request_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .delete(delete_file_request_handler)
+}
+
+pub fn update_folder_visibility_route() -> Router {
+    Router::with_path("<param_folder_id>/visibility")
+        .hoop(check_login_middleware)
+        .hoop(get_folder_by_id_middleware)
+        .put(update_folder_visibility_handler)
+}
@@ -0,0 +1,8 @@
+use salvo::Router;
+
+use crate::handler::auth::jwks::get_jwks_handler;
+
+pub fn jwks_routes() -> Router {
+    // /.well-known/jwks.json
+    Router::with_path(".well-known/jwks.json").get(get_jwks_handler)
+}
@@ -0,0 +1,33 @@
+use salvo::Router;
+
+use crate::{
+    handler::{
+        content::get_content_handler,
+        link::{get_link_content_handler, upload_link_file_handler},
+    },
+    middleware::{
+        rate_limit::RateLimit,
+        share_link::{get_link_by_token_middleware, get_link_file_middleware},
+    },
+};
+
+// The link routes do not need the user to be logged in, the token is enough
+// The limit is high enough to browse a shared folder, and slows down guessing the tokens and the passwords
+pub fn link_routes() -> Router {
+    Router::with_path("link/<link_token>")
+        .hoop(RateLimit::per_minute(120))
+        .hoop(get_link_by_token_middleware)
+        .get(get_link_content_handler) // link/<link_token>
+        .push(
+            // link/<link_token>/upload
+            Router::with_path("upload")
+                .hoop(RateLimit::per_minute(30))
+                .post(upload_link_file_handler),
+        )
+        .push(
+            // link/<link_token>/content/<param_file_id>
+            Router::with_path("content/<param_file_id>")
+                .hoop(get_link_file_middleware)
+                .get(get_content_handler),
+        )
+}
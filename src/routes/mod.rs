@@ -5,17 +5,46 @@ use crate::{
     middleware::{auth::check_login_middleware, file::get_file_by_id_middleware},
 };
 
-use self::{file::file_routes, folder::folder_routes, user::user_routes};
+use self::{
+    admin::admin_routes, change::change_routes, docs::docs_routes, drop::drop_routes,
+    event::event_routes, file::file_routes, folder::folder_routes, jwks::jwks_routes,
+    link::link_routes, oidc::oidc_routes, search::search_routes, shared::shared_routes,
+    team::team_routes, user::user_routes, webhook::webhook_routes,
+};
 
+pub mod admin;
+pub mod change;
+pub mod docs;
+pub mod drop;
+pub mod event;
 pub mod file;
 pub mod folder;
+pub mod jwks;
+pub mod link;
+pub mod oidc;
+pub mod search;
+pub mod shared;
+pub mod team;
 pub mod user;
+pub mod webhook;
 
 pub fn routes() -> Router {
     Router::new()
         .push(user_routes())
+        .push(admin_routes())
+        .push(oidc_routes())
+        .push(jwks_routes())
+        .push(docs_routes())
         .push(file_routes())
         .push(folder_routes())
+        .push(search_routes())
+        .push(shared_routes())
+        .push(team_routes())
+        .push(link_routes())
+        .push(drop_routes())
+        .push(event_routes())
+        .push(change_routes())
+        .push(webhook_routes())
         .push(
             Router::with_path("content/<param_file_id>")
                 .hoop(check_login_middleware)
@@ -0,0 +1,32 @@
+use salvo::Router;
+
+use crate::{
+    handler::auth::oidc::{get_oidc_providers_handler, oidc_callback_handler, oidc_login_handler},
+    middleware::rate_limit::RateLimit,
+};
+
+pub fn oidc_routes() -> Router {
+    Router::with_path("auth/oidc")
+        // /auth/oidc/<oidc_provider>/login
+        .push(oidc_login_route())
+        // /auth/oidc/<oidc_provider>/callback
+        .push(oidc_callback_route())
+        // /auth/oidc
+        .push(get_oidc_providers_route())
+}
+
+pub fn get_oidc_providers_route() -> Router {
+    Router::with_path("").get(get_oidc_providers_handler)
+}
+
+pub fn oidc_login_route() -> Router {
+    Router::with_path("<oidc_provider>/login")
+        .hoop(RateLimit::per_minute(10))
+        .get(oidc_login_handler)
+}
+
+pub fn oidc_callback_route() -> Router {
+    Router::with_path("<oidc_provider>/callback")
+        .hoop(RateLimit::per_minute(10))
+        .get(oidc_callback_handler)
+}
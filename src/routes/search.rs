@@ -0,0 +1,10 @@
+use salvo::Router;
+
+use crate::{handler::search::search_handler, middleware::auth::check_login_middleware};
+
+pub fn search_routes() -> Router {
+    // /search?q=
+    Router::with_path("search")
+        .hoop(check_login_middleware)
+        .get(search_handler)
+}
@@ -0,0 +1,10 @@
+use salvo::Router;
+
+use crate::{handler::shared::get_shared_handler, middleware::auth::check_login_middleware};
+
+pub fn shared_routes() -> Router {
+    // /shared
+    Router::with_path("shared")
+        .hoop(check_login_middleware)
+        .get(get_shared_handler)
+}
@@ -0,0 +1,69 @@
+use salvo::Router;
+
+use crate::{
+    handler::team::{
+        create::create_team_handler,
+        delete::delete_team_handler,
+        get::{get_team_by_id_handler, get_teams_handler},
+        member::{add_member_handler, remove_member_handler, update_member_handler},
+    },
+    middleware::{auth::check_login_middleware, team::get_team_by_id_middleware},
+};
+
+pub fn team_routes() -> Router {
+    Router::with_path("team")
+        .push(get_teams_route()) // team/
+        .push(create_team_route()) // team/create
+        .push(delete_team_route()) // team/delete/<param_team_id>
+        .push(add_member_route()) // team/<param_team_id>/members/add
+        .push(update_member_route()) // team/<param_team_id>/members/update/<param_user_id>
+        .push(remove_member_route()) // team/<param_team_id>/members/delete/<param_user_id>
+        .push(get_team_route()) // team/<param_team_id>
+}
+
+pub fn get_teams_route() -> Router {
+    Router::new()
+        .hoop(check_login_middleware)
+        .get(get_teams_handler)
+}
+
+pub fn get_team_route() -> Router {
+    Router::with_path("<param_team_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_team_by_id_middleware)
+        .get(get_team_by_id_handler)
+}
+
+pub fn create_team_route() -> Router {
+    Router::with_path("create")
+        .hoop(check_login_middleware)
+        .post(create_team_handler)
+}
+
+pub fn delete_team_route() -> Router {
+    Router::with_path("delete/<param_team_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_team_by_id_middleware)
+        .delete(delete_team_handler)
+}
+
+pub fn add_member_route() -> Router {
+    Router::with_path("<param_team_id>/members/add")
+        .hoop(check_login_middleware)
+        .hoop(get_team_by_id_middleware)
+        .put(add_member_handler)
+}
+
+pub fn update_member_route() -> Router {
+    Router::with_path("<param_team_id>/members/update/<param_user_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_team_by_id_middleware)
+        .put(update_member_handler)
+}
+
+pub fn remove_member_route() -> Router {
+    Router::with_path("<param_team_id>/members/delete/<param_user_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_team_by_id_middleware)
+        .delete(remove_member_handler)
+}
@@ -2,50 +2,82 @@ use salvo::Router;
 
 use crate::{
     handler::{
-        auth::{login::login_handler, logout::logout_handler, refresh::refresh_handler},
+        auth::{
+            login::login_handler, logout::logout_handler, refresh::refresh_handler,
+            two_factor::two_factor_login_handler,
+        },
         user::{
             create::create_user_handler,
             delete::delete_user_handler,
-            get::{get_user_handler, get_users_handler},
+            get::get_user_handler,
+            password::{forgot_password_handler, reset_password_handler},
             profile::profile_handler,
+            restore::restore_user_handler,
+            tag::get_user_tags_handler,
+            two_factor::{
+                disable_two_factor_handler, enable_two_factor_handler, enroll_two_factor_handler,
+            },
             update::update_user_handler,
+            verify::{resend_verification_handler, verify_email_handler},
         },
     },
-    middleware::auth::check_login_middleware,
+    middleware::{auth::check_login_middleware, rate_limit::RateLimit},
 };
 
 pub fn user_routes() -> Router {
     Router::with_path("user")
-        // /user/
-        .push(get_users_route())
         // /user/create
         .push(create_user_route())
         // /user/login
         .push(login_route())
+        // /user/login/2fa
+        .push(two_factor_login_route())
+        // /user/2fa/enroll
+        .push(enroll_two_factor_route())
+        // /user/2fa/enable
+        .push(enable_two_factor_route())
+        // /user/2fa/disable
+        .push(disable_two_factor_route())
+        // /user/verify
+        .push(verify_email_route())
+        // /user/verify/resend
+        .push(resend_verification_route())
+        // /user/password/forgot
+        .push(forgot_password_route())
+        // /user/password/reset
+        .push(reset_password_route())
         // /user/logout
         .push(logout_route())
         // /user/refresh
         .push(refresh_route())
+        // /user/restore
+        .push(restore_user_route())
         // /user/profile
         .push(profile_route())
         // /user/update/<param_user_id>
         .push(update_user_route())
         // /user/delete/<param_user_id>
         .push(delete_user_route())
+        // /user/<param_user_id>/tags
+        .push(get_user_tags_route())
         // /user/<param_user_id>
         .push(get_user_route())
 }
 
-pub fn get_users_route() -> Router {
-    Router::with_path("").get(get_users_handler)
-}
-
 pub fn get_user_route() -> Router {
     Router::with_path("<param_user_id>").get(get_user_handler)
 }
 
+pub fn get_user_tags_route() -> Router {
+    Router::with_path("<param_user_id>/tags")
+        .hoop(check_login_middleware)
+        .get(get_user_tags_handler)
+}
+
 pub fn create_user_route() -> Router {
-    Router::with_path("register").post(create_user_handler)
+    Router::with_path("register")
+        .hoop(RateLimit::per_hour(10))
+        .post(create_user_handler)
 }
 
 pub fn update_user_route() -> Router {
@@ -60,6 +92,12 @@ pub fn delete_user_route() -> Router {
         .delete(delete_user_handler)
 }
 
+pub fn restore_user_route() -> Router {
+    Router::with_path("restore")
+        .hoop(RateLimit::per_minute(10))
+        .post(restore_user_handler)
+}
+
 pub fn profile_route() -> Router {
     Router::with_path("profile")
         .hoop(check_login_middleware)
@@ -67,7 +105,54 @@ pub fn profile_route() -> Router {
 }
 
 pub fn login_route() -> Router {
-    Router::with_path("login").post(login_handler)
+    Router::with_path("login")
+        .hoop(RateLimit::per_minute(10))
+        .post(login_handler)
+}
+
+pub fn two_factor_login_route() -> Router {
+    Router::with_path("login/2fa")
+        .hoop(RateLimit::per_minute(10))
+        .post(two_factor_login_handler)
+}
+
+pub fn enroll_two_factor_route() -> Router {
+    Router::with_path("2fa/enroll")
+        .hoop(check_login_middleware)
+        .post(enroll_two_factor_handler)
+}
+
+pub fn enable_two_factor_route() -> Router {
+    Router::with_path("2fa/enable")
+        .hoop(check_login_middleware)
+        .post(enable_two_factor_handler)
+}
+
+pub fn disable_two_factor_route() -> Router {
+    Router::with_path("2fa/disable")
+        .hoop(check_login_middleware)
+        .post(disable_two_factor_handler)
+}
+
+pub fn verify_email_route() -> Router {
+    Router::with_path("verify").post(verify_email_handler)
+}
+
+pub fn resend_verification_route() -> Router {
+    Router::with_path("verify/resend")
+        .hoop(RateLimit::per_hour(5))
+        .hoop(check_login_middleware)
+        .post(resend_verification_handler)
+}
+
+pub fn forgot_password_route() -> Router {
+    Router::with_path("password/forgot")
+        .hoop(RateLimit::per_hour(5))
+        .post(forgot_password_handler)
+}
+
+pub fn reset_password_route() -> Router {
+    Router::with_path("password/reset").post(reset_password_handler)
 }
 
 pub fn refresh_route() -> Router {
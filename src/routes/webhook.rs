@@ -0,0 +1,70 @@
+use salvo::Router;
+
+use crate::{
+    handler::webhook::{
+        create::create_webhook_handler,
+        delete::delete_webhook_handler,
+        delivery::{get_deliveries_handler, redeliver_handler},
+        get::{get_webhook_by_id_handler, get_webhooks_handler},
+        update::update_webhook_handler,
+    },
+    middleware::{auth::check_login_middleware, webhook::get_webhook_by_id_middleware},
+};
+
+pub fn webhook_routes() -> Router {
+    Router::with_path("webhook")
+        .push(get_webhooks_route()) // webhook/
+        .push(create_webhook_route()) // webhook/create
+        .push(update_webhook_route()) // webhook/update/<param_webhook_id>
+        .push(delete_webhook_route()) // webhook/delete/<param_webhook_id>
+        .push(get_deliveries_route()) // webhook/<param_webhook_id>/deliveries
+        .push(redeliver_route()) // webhook/<param_webhook_id>/deliveries/<param_delivery_id>/redeliver
+        .push(get_webhook_route()) // webhook/<param_webhook_id>
+}
+
+pub fn get_webhooks_route() -> Router {
+    Router::new()
+        .hoop(check_login_middleware)
+        .get(get_webhooks_handler)
+}
+
+pub fn get_webhook_route() -> Router {
+    Router::with_path("<param_webhook_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_webhook_by_id_middleware)
+        .get(get_webhook_by_id_handler)
+}
+
+pub fn create_webhook_route() -> Router {
+    Router::with_path("create")
+        .hoop(check_login_middleware)
+        .post(create_webhook_handler)
+}
+
+pub fn update_webhook_route() -> Router {
+    Router::with_path("update/<param_webhook_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_webhook_by_id_middleware)
+        .put(update_webhook_handler)
+}
+
+pub fn delete_webhook_route() -> Router {
+    Router::with_path("delete/<param_webhook_id>")
+        .hoop(check_login_middleware)
+        .hoop(get_webhook_by_id_middleware)
+        .delete(delete_webhook_handler)
+}
+
+pub fn get_deliveries_route() -> Router {
+    Router::with_path("<param_webhook_id>/deliveries")
+        .hoop(check_login_middleware)
+        .hoop(get_webhook_by_id_middleware)
+        .get(get_deliveries_handler)
+}
+
+pub fn redeliver_route() -> Router {
+    Router::with_path("<param_webhook_id>/deliveries/<param_delivery_id>/redeliver")
+        .hoop(check_login_middleware)
+        .hoop(get_webhook_by_id_middleware)
+        .post(redeliver_handler)
+}
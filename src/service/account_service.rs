@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use crate::{
+    base::{
+        user::User,
+        user_token::{TokenKind, UserToken},
+    },
+    db::{user_db::UserDB, user_token_db::UserTokenDB},
+    error::Error,
+    mailer::{Mail, Mailer},
+    Result,
+};
+
+// The flows that prove that a user owns their email, by mailing them a token
+#[derive(Debug, Clone)]
+pub struct AccountService {
+    user_db: UserDB,
+    user_token_db: UserTokenDB,
+    mailer: Arc<dyn Mailer>,
+    // Where the links in the mails point to, the frontend reads the token from them
+    app_url: String,
+}
+
+impl AccountService {
+    pub fn init(
+        user_db: &UserDB,
+        user_token_db: &UserTokenDB,
+        mailer: &Arc<dyn Mailer>,
+        app_url: &str,
+    ) -> Self {
+        Self {
+            user_db: user_db.clone(),
+            user_token_db: user_token_db.clone(),
+            mailer: mailer.clone(),
+            app_url: app_url.to_string(),
+        }
+    }
+
+    // A new token replaces the ones that were sent before
+    async fn issue_token(&self, user: &User, kind: TokenKind) -> Result<String> {
+        self.user_token_db
+            .delete_tokens_by_user_kind(&user.id, kind)
+            .await?;
+        let (user_token, token) = UserToken::new(user.id, kind, &user.email);
+        self.user_token_db.create_token(user_token).await?;
+        Ok(token)
+    }
+
+    pub async fn send_verification(&self, user: &User) -> Result<()> {
+        if user.email_verified {
+            return Err(Error::Conflict("The email is already verified".into()));
+        }
+
+        let token = self.issue_token(user, TokenKind::VerifyEmail).await?;
+        self.mailer
+            .send(Mail {
+                to: user.email.clone(),
+                subject: "Verify your email".into(),
+                body: format!(
+                    "Hi {},\n\nOpen this link to verify your email:\n{}/verify-email?token={token}\n\nThe link expires in 24 hours.",
+                    user.username, self.app_url
+                ),
+            })
+            .await
+    }
+
+    pub async fn verify_email(&self, token: &str) -> Result<User> {
+        let user_token = self
+            .user_token_db
+            .consume_token(token, TokenKind::VerifyEmail)
+            .await?;
+        self.user_db
+            .update_email_verified(&user_token.user, &user_token.email, true)
+            .await
+    }
+
+    // Nothing tells the caller whether the email belongs to someone,
+    // otherwise this could be used to find out who has an account
+    pub async fn request_password_reset(&self, email: &str) -> Result<()> {
+        let Ok(user) = self.user_db.get_user_by_email(email).await else {
+            return Ok(());
+        };
+        if user.is_locked() {
+            return Ok(());
+        }
+
+        let token = self.issue_token(&user, TokenKind::ResetPassword).await?;
+        self.mailer
+            .send(Mail {
+                to: user.email.clone(),
+                subject: "Reset your password".into(),
+                body: format!(
+                    "Hi {},\n\nOpen this link to choose a new password:\n{}/reset-password?token={token}\n\nThe link expires in 1 hour. If you did not ask for this, you can ignore this mail.",
+                    user.username, self.app_url
+                ),
+            })
+            .await
+    }
+
+    // The user is logged out everywhere, like after an admin resets the password
+    pub async fn reset_password(&self, token: &str, password: &str) -> Result<User> {
+        let user_token = self
+            .user_token_db
+            .consume_token(token, TokenKind::ResetPassword)
+            .await?;
+        let user = self.user_db.get_user_by_id(&user_token.user).await?;
+        user.check_active()?;
+
+        // The old address cannot be used to take over the account after an email change
+        if user.email != user_token.email {
+            return Err("The token is invalid or has expired".into());
+        }
+
+        self.user_db.update_password(&user.id, password).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::oid::ObjectId;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{config::MailConfig, db::mongo::DB, mailer::outbox::OutboxMailer};
+
+    struct Setup {
+        db: DB,
+        user_db: UserDB,
+        user_token_db: UserTokenDB,
+        account_service: AccountService,
+        outbox: TempDir,
+    }
+
+    async fn setup() -> Setup {
+        let db = DB::init_test().await;
+        let user_db = UserDB::init(&db);
+        let user_token_db = UserTokenDB::init(&db);
+        user_token_db.create_indexes().await.unwrap();
+
+        let outbox = tempfile::tempdir().unwrap();
+        let mailer: Arc<dyn Mailer> = Arc::new(OutboxMailer::init(&MailConfig {
+            outbox_dir: outbox.path().to_string_lossy().to_string(),
+            ..MailConfig::default()
+        }));
+        let account_service =
+            AccountService::init(&user_db, &user_token_db, &mailer, "http://app.test");
+
+        Setup {
+            db,
+            user_db,
+            user_token_db,
+            account_service,
+            outbox,
+        }
+    }
+
+    async fn create_user(user_db: &UserDB, email: &str) -> User {
+        let user = User::new(ObjectId::new(), "tester", email, "Password1!", "", None).unwrap();
+        user_db.create_user(user).await.unwrap()
+    }
+
+    // The token of the only mail in the outbox, from the link in it
+    async fn read_mailed_token(outbox: &TempDir) -> String {
+        let mut mails = std::fs::read_dir(outbox.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(mails.len(), 1, "{mails:?}");
+        let mail = tokio::fs::read_to_string(mails.remove(0)).await.unwrap();
+        mail.split("token=")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn mails_a_verification_token_on_register() {
+        let setup = setup().await;
+        let user = create_user(&setup.user_db, "tester@example.com").await;
+
+        setup
+            .account_service
+            .send_verification(&user)
+            .await
+            .unwrap();
+        let token = read_mailed_token(&setup.outbox).await;
+
+        let verified_user = setup.account_service.verify_email(&token).await.unwrap();
+        assert_eq!(verified_user.id, user.id);
+        assert!(verified_user.email_verified);
+        setup.db.drop_test().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn consumes_a_token_only_once() {
+        let setup = setup().await;
+        let user = create_user(&setup.user_db, "tester@example.com").await;
+
+        setup
+            .account_service
+            .send_verification(&user)
+            .await
+            .unwrap();
+        let token = read_mailed_token(&setup.outbox).await;
+
+        assert!(setup.account_service.verify_email(&token).await.is_ok());
+        assert!(setup.account_service.verify_email(&token).await.is_err());
+        setup.db.drop_test().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn refuses_an_expired_token() {
+        let setup = setup().await;
+        let user = create_user(&setup.user_db, "tester@example.com").await;
+
+        let (mut user_token, token) = UserToken::new(user.id, TokenKind::VerifyEmail, &user.email);
+        user_token.expires_at = user_token.created_at - 1;
+        setup.user_token_db.create_token(user_token).await.unwrap();
+
+        assert!(setup.account_service.verify_email(&token).await.is_err());
+        assert!(
+            !setup
+                .user_db
+                .get_user_by_id(&user.id)
+                .await
+                .unwrap()
+                .email_verified
+        );
+        setup.db.drop_test().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn refuses_a_reset_token_for_an_old_email() {
+        let setup = setup().await;
+        let user = create_user(&setup.user_db, "old@example.com").await;
+
+        setup
+            .account_service
+            .request_password_reset("old@example.com")
+            .await
+            .unwrap();
+        let token = read_mailed_token(&setup.outbox).await;
+
+        setup
+            .user_db
+            .update_user(
+                &user.id,
+                User {
+                    email: "new@example.com".into(),
+                    ..user.clone()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(setup
+            .account_service
+            .reset_password(&token, "Changed1!")
+            .await
+            .is_err());
+        let user = setup.user_db.get_user_by_id(&user.id).await.unwrap();
+        assert_eq!(user.password, "Password1!");
+        setup.db.drop_test().await;
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{
+    base::{
+        acl::{Access, AclEntry, Role},
+        event::Event,
+        file::{File, Visibility as FileVisibility},
+        folder::{Folder, Visibility as FolderVisibility},
+        search_entry::ResourceKind,
+        team::TeamRole,
+    },
+    db::{acl_db::AclDB, file_db::FileDB, folder_db::FolderDB, team_db::TeamDB, user_db::UserDB},
+    helper::position::get_ancestor_dirs,
+    Result,
+};
+
+#[derive(Debug, Clone)]
+pub struct AclService {
+    acl_db: AclDB,
+    file_db: FileDB,
+    folder_db: FolderDB,
+    user_db: UserDB,
+    team_db: TeamDB,
+}
+
+impl AclService {
+    pub fn init(
+        acl_db: &AclDB,
+        file_db: &FileDB,
+        folder_db: &FolderDB,
+        user_db: &UserDB,
+        team_db: &TeamDB,
+    ) -> Self {
+        Self {
+            acl_db: acl_db.clone(),
+            file_db: file_db.clone(),
+            folder_db: folder_db.clone(),
+            user_db: user_db.clone(),
+            team_db: team_db.clone(),
+        }
+    }
+
+    // The highest role that the user has on the resource, or on any folder above it
+    async fn get_role(
+        &self,
+        user: &ObjectId,
+        resource: Option<&ObjectId>,
+        dir: &str,
+    ) -> Result<Option<Role>> {
+        let mut resources = self
+            .folder_db
+            .get_folders_by_fullpaths(&get_ancestor_dirs(dir))
+            .await?
+            .into_iter()
+            .map(|f| f.id)
+            .collect::<Vec<_>>();
+        resources.extend(resource.copied());
+
+        let role = self
+            .acl_db
+            .get_entries_by_grantee_resources(user, &resources)
+            .await?
+            .into_iter()
+            .map(|e| e.role)
+            .max();
+        Ok(role)
+    }
+
+    // What the user can do inside the tree of a team, based on their role in the team
+    // The dir is in the tree of a team when its root folder is @name
+    async fn get_team_access(&self, user: &ObjectId, dir: &str) -> Result<Option<Access>> {
+        let team_name = match dir.split('/').next().and_then(|r| r.strip_prefix('@')) {
+            Some(team_name) => team_name,
+            None => return Ok(None),
+        };
+
+        let access = self
+            .team_db
+            .get_teams_by(doc! {"name": team_name})
+            .await?
+            .first()
+            .and_then(|t| t.role_of(user))
+            .map(|role| match role {
+                TeamRole::Owner | TeamRole::Admin => Access::Owner,
+                TeamRole::Member => Access::Editor,
+            });
+        Ok(access)
+    }
+
+    // The highest access that the user has on everything inside the dir,
+    // either through a team or through sharing
+    pub async fn get_access_by_dir(&self, user: &ObjectId, dir: &str) -> Result<Option<Access>> {
+        let team_access = self.get_team_access(user, dir).await?;
+        let shared_access = self.get_role(user, None, dir).await?.map(Access::from);
+        Ok(team_access.max(shared_access))
+    }
+
+    // Whether the user should hear about the event, through the ownership, a team or a share
+    // The public files and folders are not pushed to everyone, only to the users who have them in their tree
+    pub async fn can_see_event(&self, user: &ObjectId, event: &Event) -> Result<bool> {
+        if event.team.is_none() && *user == event.owner {
+            return Ok(true);
+        }
+        if event.grantees.contains(user) {
+            return Ok(true);
+        }
+        for dir in event.dirs() {
+            if self.get_team_access(user, dir).await?.is_some() {
+                return Ok(true);
+            }
+            if self
+                .get_role(user, Some(&event.resource), dir)
+                .await?
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // None means that the user cannot see the file at all
+    pub async fn get_file_access(
+        &self,
+        file: &File,
+        user: Option<&ObjectId>,
+    ) -> Result<Option<Access>> {
+        if let Some(user) = user {
+            // The files and folders of a team are managed through the team, not by their creator
+            if file.team.is_none() && *user == file.owner {
+                return Ok(Some(Access::Owner));
+            }
+            if let Some(access) = self.get_team_access(user, &file.position).await? {
+                return Ok(Some(access));
+            }
+            if let Some(role) = self.get_role(user, Some(&file.id), &file.position).await? {
+                return Ok(Some(role.into()));
+            }
+        }
+        // An inherit file is public when the closest folder above it that decides is public
+        let dir_visibilities = match file.visibility {
+            FileVisibility::Inherit => {
+                self.folder_db
+                    .get_dir_visibilities(&[&file.position])
+                    .await?
+            }
+            _ => HashMap::new(),
+        };
+        Ok(file.is_public(&dir_visibilities).then_some(Access::Public))
+    }
+
+    pub async fn get_folder_access(
+        &self,
+        folder: &Folder,
+        user: Option<&ObjectId>,
+    ) -> Result<Option<Access>> {
+        if let Some(user) = user {
+            // The files and folders of a team are managed through the team, not by their creator
+            if folder.team.is_none() && *user == folder.owner {
+                return Ok(Some(Access::Owner));
+            }
+            if let Some(access) = self.get_team_access(user, &folder.position).await? {
+                return Ok(Some(access));
+            }
+            if let Some(role) = self
+                .get_role(user, Some(&folder.id), &folder.position)
+                .await?
+            {
+                return Ok(Some(role.into()));
+            }
+        }
+        // An inherit folder is public when the closest folder above it that decides is public
+        let dir_visibilities = match folder.visibility {
+            FolderVisibility::Inherit => {
+                self.folder_db
+                    .get_dir_visibilities(&[&folder.position])
+                    .await?
+            }
+            _ => HashMap::new(),
+        };
+        Ok(folder
+            .is_public(&dir_visibilities)
+            .then_some(Access::Public))
+    }
+
+    async fn share(
+        &self,
+        resource: ObjectId,
+        kind: ResourceKind,
+        owner: ObjectId,
+        username: &str,
+        role: Role,
+    ) -> Result<AclEntry> {
+        let grantee = self.user_db.get_user_by_username(username).await?;
+        if grantee.id == owner {
+            return Err("You cannot share with yourself".into());
+        }
+        self.acl_db
+            .upsert_entry(AclEntry::new(resource, kind, owner, grantee.id, role))
+            .await
+    }
+
+    pub async fn share_file(&self, file: &File, username: &str, role: Role) -> Result<AclEntry> {
+        self.share(file.id, ResourceKind::File, file.owner, username, role)
+            .await
+    }
+
+    pub async fn share_folder(
+        &self,
+        folder: &Folder,
+        username: &str,
+        role: Role,
+    ) -> Result<AclEntry> {
+        self.share(
+            folder.id,
+            ResourceKind::Folder,
+            folder.owner,
+            username,
+            role,
+        )
+        .await
+    }
+
+    pub async fn get_entries_by_resource(&self, resource: &ObjectId) -> Result<Vec<AclEntry>> {
+        self.acl_db.get_entries_by_resource(resource).await
+    }
+
+    pub async fn unshare(&self, resource: &ObjectId, grantee: &ObjectId) -> Result<AclEntry> {
+        self.acl_db
+            .delete_entry_by_resource_grantee(resource, grantee)
+            .await
+    }
+
+    // Everything that has been shared directly with the user
+    // The files and folders inside a shared folder are reached by browsing the folder
+    pub async fn get_shared_with(
+        &self,
+        user: &ObjectId,
+    ) -> Result<(Vec<(Role, File)>, Vec<(Role, Folder)>)> {
+        let entries = self.acl_db.get_entries_by_grantee(user).await?;
+
+        let mut file_roles = HashMap::new();
+        let mut folder_roles = HashMap::new();
+        for entry in entries {
+            match entry.kind {
+                ResourceKind::File => file_roles.insert(entry.resource, entry.role),
+                ResourceKind::Folder => folder_roles.insert(entry.resource, entry.role),
+            };
+        }
+
+        let file_ids = file_roles.keys().collect::<Vec<_>>();
+        let folder_ids = folder_roles.keys().collect::<Vec<_>>();
+
+        let files = self
+            .file_db
+            .get_files_by(doc! {"_id": {"$in": file_ids}})
+            .await?
+            .into_iter()
+            .filter_map(|f| Some((*file_roles.get(&f.id)?, f)))
+            .collect();
+
+        let folders = self
+            .folder_db
+            .get_folders_by(doc! {"_id": {"$in": folder_ids}})
+            .await?
+            .into_iter()
+            .filter_map(|f| Some((*folder_roles.get(&f.id)?, f)))
+            .collect();
+
+        Ok((files, folders))
+    }
+}
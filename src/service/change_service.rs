@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+
+use crate::{base::change::Change, db::change_db::ChangeDB, Result};
+
+// The newest changes are held back for this long, since a change with a smaller seq
+// can still be on its way to the database, and the cursor would skip it otherwise
+// It is only a guess, a write that takes longer than this is missed
+// by the clients that went past its seq in the meantime
+const SETTLE_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct ChangeService {
+    change_db: ChangeDB,
+}
+
+impl ChangeService {
+    pub fn init(change_db: &ChangeDB) -> Self {
+        Self {
+            change_db: change_db.clone(),
+        }
+    }
+
+    // Where a new sync client starts from, nothing before it is returned
+    pub async fn get_latest_cursor(&self) -> Result<i64> {
+        self.change_db.get_latest_seq().await
+    }
+
+    // The changes to the personal tree of the user
+    pub async fn get_user_changes(
+        &self,
+        owner: &ObjectId,
+        cursor: i64,
+        limit: i64,
+    ) -> Result<(Vec<Change>, i64, bool)> {
+        self.get_changes(doc! {"owner": owner, "team": null}, cursor, limit)
+            .await
+    }
+
+    pub async fn get_team_changes(
+        &self,
+        team: &ObjectId,
+        cursor: i64,
+        limit: i64,
+    ) -> Result<(Vec<Change>, i64, bool)> {
+        self.get_changes(doc! {"team": team}, cursor, limit).await
+    }
+
+    // Returns the changes, the cursor to continue from, and whether there are more right away
+    async fn get_changes(
+        &self,
+        filter: Document,
+        cursor: i64,
+        limit: i64,
+    ) -> Result<(Vec<Change>, i64, bool)> {
+        let until = Utc::now().timestamp_millis() - SETTLE_DELAY.as_millis() as i64;
+        self.get_changes_until(filter, cursor, until, limit).await
+    }
+
+    async fn get_changes_until(
+        &self,
+        filter: Document,
+        cursor: i64,
+        until: i64,
+        limit: i64,
+    ) -> Result<(Vec<Change>, i64, bool)> {
+        // One more than asked for, to know whether there are more
+        let changes = self
+            .change_db
+            .get_changes_after(filter, cursor, until, limit + 1)
+            .await?;
+        Ok(into_page(changes, cursor, limit))
+    }
+}
+
+// The cursor stays where it was when there is nothing new
+fn into_page(mut changes: Vec<Change>, cursor: i64, limit: i64) -> (Vec<Change>, i64, bool) {
+    let has_more = changes.len() as i64 > limit;
+    changes.truncate(limit as usize);
+
+    let next_cursor = changes.last().map(|c| c.seq).unwrap_or(cursor);
+    (changes, next_cursor, has_more)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        base::{
+            change::ChangeAction,
+            file::{File, Visibility},
+            user::User,
+        },
+        db::mongo::DB,
+    };
+
+    fn user(username: &str) -> User {
+        let email = format!("{username}@example.com");
+        User::new(ObjectId::new(), username, &email, "Passw0rd!", "", None).unwrap()
+    }
+
+    fn file(user: &User, name: &str) -> File {
+        File::new(
+            ObjectId::new(),
+            user,
+            None,
+            name,
+            Visibility::Inherit,
+            "",
+            None,
+        )
+        .unwrap()
+    }
+
+    fn seqs(changes: &[Change]) -> Vec<i64> {
+        changes.iter().map(|c| c.seq).collect()
+    }
+
+    #[test]
+    fn pages_the_changes() {
+        let user = user("alice");
+        let changes = (11..=13)
+            .map(|seq| {
+                let mut change = Change::from_file(ChangeAction::Created, &file(&user, "a.txt"));
+                change.seq = seq;
+                change
+            })
+            .collect::<Vec<_>>();
+
+        // The extra one only tells that there are more
+        let (page, cursor, has_more) = into_page(changes.clone(), 10, 2);
+        assert_eq!((seqs(&page), cursor, has_more), (vec![11, 12], 12, true));
+
+        let (page, cursor, has_more) = into_page(changes[2..].to_vec(), 12, 2);
+        assert_eq!((seqs(&page), cursor, has_more), (vec![13], 13, false));
+
+        let (page, cursor, has_more) = into_page(vec![], 13, 2);
+        assert_eq!((seqs(&page), cursor, has_more), (vec![], 13, false));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn follows_the_changes_of_a_user_in_order() {
+        let db = DB::init_test().await;
+        let change_db = ChangeDB::init(&db);
+        change_db.create_indexes().await.unwrap();
+        let change_service = ChangeService::init(&change_db);
+        let alice = user("alice");
+        let bob = user("bob");
+
+        let deleted = file(&alice, "a.txt");
+
+        let start = change_service.get_latest_cursor().await.unwrap();
+        change_db
+            .append_changes(vec![
+                Change::from_file(ChangeAction::Created, &deleted),
+                Change::from_file(ChangeAction::Created, &file(&bob, "b.txt")),
+                Change::from_file(ChangeAction::Moved, &file(&alice, "c.txt")),
+            ])
+            .await
+            .unwrap();
+        change_db
+            .append_changes(vec![
+                Change::from_file(ChangeAction::Deleted, &deleted),
+                Change::from_file(ChangeAction::Created, &file(&alice, "d.txt")),
+            ])
+            .await
+            .unwrap();
+
+        // Too new to be given out yet
+        let (page, cursor, has_more) = change_service
+            .get_user_changes(&alice.id, start, 10)
+            .await
+            .unwrap();
+        assert_eq!((page.len(), cursor, has_more), (0, start, false));
+
+        // As if they had settled, followed two at a time
+        let filter = doc! {"owner": alice.id, "team": null};
+        let mut cursor = start;
+        let mut followed = Vec::new();
+        loop {
+            let (page, next_cursor, has_more) = change_service
+                .get_changes_until(filter.clone(), cursor, i64::MAX, 2)
+                .await
+                .unwrap();
+            assert!(page.len() <= 2);
+            assert!(page.iter().all(|c| c.seq > cursor && c.owner == alice.id));
+            cursor = next_cursor;
+            followed.extend(page);
+            if !has_more {
+                break;
+            }
+        }
+
+        let actions = followed.iter().map(|c| c.action).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            [
+                ChangeAction::Created,
+                ChangeAction::Moved,
+                ChangeAction::Deleted,
+                ChangeAction::Created,
+            ]
+        );
+        assert!(followed.windows(2).all(|w| w[0].seq < w[1].seq));
+        // The tombstone is left for the file that is gone
+        assert_eq!(followed[2].resource, deleted.id);
+        assert_eq!(cursor, change_service.get_latest_cursor().await.unwrap());
+
+        db.drop_test().await;
+    }
+}
@@ -0,0 +1,78 @@
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    base::{file_request::FileRequest, folder::Folder},
+    db::{file_request_db::FileRequestDB, folder_db::FolderDB},
+    error::Error,
+    Result,
+};
+
+#[derive(Debug, Clone)]
+pub struct FileRequestService {
+    file_request_db: FileRequestDB,
+    folder_db: FolderDB,
+}
+
+impl FileRequestService {
+    pub fn init(file_request_db: &FileRequestDB, folder_db: &FolderDB) -> Self {
+        Self {
+            file_request_db: file_request_db.clone(),
+            folder_db: folder_db.clone(),
+        }
+    }
+
+    pub async fn create_request(&self, request: FileRequest) -> Result<FileRequest> {
+        self.file_request_db.create_request(request).await
+    }
+
+    pub async fn get_requests_by_folder(&self, folder: &ObjectId) -> Result<Vec<FileRequest>> {
+        self.file_request_db.get_requests_by_folder(folder).await
+    }
+
+    pub async fn delete_request_by_id_folder(
+        &self,
+        request_id: &ObjectId,
+        folder: &ObjectId,
+    ) -> Result<FileRequest> {
+        self.file_request_db
+            .delete_request_by_id_folder(request_id, folder)
+            .await
+    }
+
+    // An expired request is treated the same as a request that does not exist
+    pub async fn get_request_by_token(&self, token: &str) -> Result<FileRequest> {
+        let request = self.file_request_db.get_request_by_token(token).await?;
+        if request.is_expired() {
+            return Err(Error::NotFound("This link has expired".into()));
+        }
+        Ok(request)
+    }
+
+    // Nobody can upload to a locked user
+    pub async fn get_request_folder(&self, request: &FileRequest) -> Result<Folder> {
+        let folder = self.folder_db.get_folder_by_id(&request.folder).await?;
+        if folder.hidden {
+            return Err(Error::NotFound(
+                "Cannot find the file request with the provided information".into(),
+            ));
+        }
+        Ok(folder)
+    }
+
+    // Checks the limits of the request, and counts the upload against them
+    // The upload has to be released if the file cannot be created afterwards
+    pub async fn reserve_upload(&self, request: &FileRequest, size: usize) -> Result<FileRequest> {
+        if let Some(max_file_size) = request.max_file_size {
+            if size as i64 > max_file_size {
+                return Err(Error::PayloadTooLarge(format!(
+                    "The file is too large, the limit is {max_file_size} bytes"
+                )));
+            }
+        }
+        self.file_request_db.reserve_upload(&request.id).await
+    }
+
+    pub async fn release_upload(&self, request: &FileRequest) -> Result<()> {
+        self.file_request_db.release_upload(&request.id).await
+    }
+}
@@ -1,15 +1,34 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
 use chrono::Utc;
 use futures::try_join;
-use mongodb::bson::{oid::ObjectId, Bson, Document};
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
 
 use crate::{
     aws::S3,
-    base::{file::File, file_version::FileVersion},
-    db::{file_db::FileDB, file_version_db::FileVersionDB, folder_db::FolderDB},
-    helper::into_string,
-    validation::file::check_fullpath,
+    base::{
+        change::Change,
+        event::{Event, EventKind},
+        file::{File, Visibility},
+        file_version::FileVersion,
+        search_entry::SearchEntry,
+    },
+    db::{
+        acl_db::AclDB, change_db::ChangeDB, file_db::FileDB, file_version_db::FileVersionDB,
+        folder_db::FolderDB, search_db::SearchDB,
+    },
+    error::Error,
+    helper::{
+        event_bus::EventBus,
+        extension_policy::ExtensionPolicy,
+        into_string,
+        mime::{detect_extension, detect_mime},
+        position::normalize_path,
+    },
+    validation::file::{check_fullpath, MAX_METADATA, MAX_TAGS},
     Result,
 };
 
@@ -18,34 +37,81 @@ pub struct FileService {
     file_db: FileDB,
     folder_db: FolderDB,
     version_db: FileVersionDB,
+    search_db: SearchDB,
+    acl_db: AclDB,
     storage: S3,
+    extension_policy: ExtensionPolicy,
+    change_db: ChangeDB,
+    event_bus: EventBus,
 }
 
+// Only the beginning of large text files goes into the search index
+const MAX_INDEXED_CONTENT: usize = 256 * 1024;
+
 impl FileService {
+    #[allow(clippy::too_many_arguments)]
     pub fn init(
         file_db: &FileDB,
         folder_db: &FolderDB,
         version_db: &FileVersionDB,
+        search_db: &SearchDB,
+        acl_db: &AclDB,
         storage: &S3,
+        extension_policy: &ExtensionPolicy,
+        change_db: &ChangeDB,
+        event_bus: &EventBus,
     ) -> Self {
         Self {
             file_db: file_db.clone(),
             folder_db: folder_db.clone(),
             storage: storage.clone(),
             version_db: version_db.clone(),
+            search_db: search_db.clone(),
+            acl_db: acl_db.clone(),
+            extension_policy: extension_policy.clone(),
+            change_db: change_db.clone(),
+            event_bus: event_bus.clone(),
+        }
+    }
+
+    // Takes the text out of the file data so it can be searched
+    // Everything that is not a text file has nothing to index
+    pub fn searchable_content(file: &File, data: &[u8]) -> String {
+        if !file.has_extension("txt") {
+            return String::new();
+        }
+        let mut content = String::from_utf8_lossy(data).into_owned();
+        if content.len() > MAX_INDEXED_CONTENT {
+            let mut end = MAX_INDEXED_CONTENT;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            content.truncate(end);
         }
+        content
     }
 
     pub async fn get_files_by_map(&self, map: &HashMap<String, String>) -> Result<Vec<File>> {
         let mut document = HashMap::new();
         for i in map {
-            if *i.0 == "owner" {
-                document.insert(
-                    "owner".to_string(),
-                    Bson::ObjectId(ObjectId::from_str(i.1)?),
-                );
+            if *i.0 == "owner" || *i.0 == "team" {
+                document.insert(i.0.to_string(), Bson::ObjectId(ObjectId::from_str(i.1)?));
+            } else if *i.0 == "tags" {
+                // tags=invoice,final matches the files having all of the listed tags
+                let tags =
+                    i.1.split(',')
+                        .map(|t| t.trim().to_lowercase())
+                        .filter(|t| !t.is_empty())
+                        .collect::<Vec<_>>();
+                document.insert("tags".to_string(), Bson::Document(doc! {"$all": tags}));
             } else {
-                document.insert(i.0.to_string(), Bson::String(i.1.to_string()));
+                // Names and paths are stored in NFC, so the filter has to be as well
+                // This also covers metadata filters, like metadata.project=abc
+                let value = match i.0.as_str() {
+                    "filename" | "fullFilename" | "position" | "fullpath" => normalize_path(i.1),
+                    _ => i.1.to_string(),
+                };
+                document.insert(i.0.to_string(), Bson::String(value));
             }
         }
         let doc = Document::from_iter(document);
@@ -56,8 +122,27 @@ impl FileService {
         self.file_db.get_files_by_owner(owner).await
     }
 
+    // The public ones, including the ones that inherit a public visibility
     pub async fn get_public_files_by_owner(&self, owner: &ObjectId) -> Result<Vec<File>> {
-        self.file_db.get_public_files_by_owner(owner).await
+        let files = self
+            .file_db
+            .get_files_by(doc! {"owner": owner, "visibility": {"$ne": "private"}})
+            .await?;
+        self.filter_public_files(files).await
+    }
+
+    // Keeps the files that are public, either by themselves or through the folders above them
+    pub async fn filter_public_files(&self, files: Vec<File>) -> Result<Vec<File>> {
+        let dirs = files
+            .iter()
+            .filter(|f| f.visibility == Visibility::Inherit)
+            .map(|f| f.position.as_str())
+            .collect::<Vec<_>>();
+        let dir_visibilities = self.folder_db.get_dir_visibilities(&dirs).await?;
+        Ok(files
+            .into_iter()
+            .filter(|f| f.is_public(&dir_visibilities))
+            .collect())
     }
 
     // pub async fn get_public_files(&self) -> Result<Vec<File>> {
@@ -69,7 +154,13 @@ impl FileService {
     }
 
     pub async fn get_public_file_by_id(&self, file_id: &ObjectId) -> Result<File> {
-        self.file_db.get_public_file_by_id(file_id).await
+        let file = self.file_db.get_file_by_id(file_id).await?;
+        self.filter_public_files(vec![file])
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                Error::NotFound("Cannot find the file with the provided information".into())
+            })
     }
 
     pub async fn get_file_by_id_owner(&self, file_id: &ObjectId, owner: &ObjectId) -> Result<File> {
@@ -104,38 +195,48 @@ impl FileService {
         self.file_db.exists_file_by_fullpath(fullpath).await
     }
 
-    pub async fn create_file(&self, file: File, data: Vec<u8>) -> Result<File> {
+    pub async fn create_file(&self, mut file: File, data: Vec<u8>) -> Result<File> {
+        self.extension_policy
+            .check(&file.extension, detect_extension(&data))?;
         let exists_file = self.exists_file_by_fullpath(&file.fullpath).await?;
         let exists_position = self
             .folder_db
             .exists_folder_by_fullpath(&file.position)
             .await?;
         if exists_file {
-            return Err(
+            return Err(Error::Conflict(
                 "The file with this name already existed in this path. Please try another name"
                     .into(),
-            );
+            ));
         }
         if !exists_position {
             return Err("Cannot create a file at a virtual position".into());
         }
+        let content = Self::searchable_content(&file, &data);
         if !data.is_empty() {
-            let internal_full_filename = &format!("{}.{}", file.id, file.extension_to_str());
-            let version_folder_name = &format!("{}/", file.id);
+            file.mime_type = detect_mime(&file.full_filename, &data);
+            let internal_full_filename = &file.internal_path();
+            let version_folder_name = &file.internal_version_folder();
             try_join!(
-                self.storage.create_file(internal_full_filename, data),
+                self.storage
+                    .create_file(internal_full_filename, data, &file.mime_type),
                 self.storage.create_folder(version_folder_name)
             )?;
         }
 
         let file = self.file_db.create_file(file).await?;
+        self.search_db
+            .index_entry(SearchEntry::from_file(&file, content), true)
+            .await?;
+        self.publish(Event::from_file(EventKind::Created, &file))
+            .await?;
         Ok(file)
     }
 
     pub async fn update_file_by_id(
         &self,
         file_id: &ObjectId,
-        file: File,
+        mut file: File,
         data: Vec<u8>,
     ) -> Result<File> {
         let old_file = self.get_file_by_id(file_id).await?;
@@ -153,10 +254,10 @@ impl FileService {
                 .await?;
 
             if exists_file {
-                return Err(
+                return Err(Error::Conflict(
                     "There's a file with a same name at this position. Please try another name"
                         .into(),
-                );
+                ));
             }
 
             if !exists_position {
@@ -164,19 +265,32 @@ impl FileService {
             }
         }
 
+        let content = Self::searchable_content(&file, &data);
+        let has_new_content = !data.is_empty();
+
+        // Without new data, the content is the same, and so is the type detected from it
+        file.mime_type = match has_new_content {
+            true => {
+                self.extension_policy
+                    .check(&file.extension, detect_extension(&data))?;
+                detect_mime(&file.full_filename, &data)
+            }
+            false => old_file.mime_type,
+        };
+
         if !data.is_empty() {
             // Create a version number
             let version = Utc::now().timestamp_millis();
 
             // Get the file path on the versioning side
 
-            let file_version_path = format!("{}/{}.{}", file.id, version, file.extension_to_str());
+            let file_version_path = file.internal_version_path(version);
 
             self.version_db
                 .create_version_with_file_id(FileVersion::new(&file, version, None))
                 .await?;
 
-            let internal_full_filename = &format!("{}.{}", file.id, file.extension_to_str());
+            let internal_full_filename = &file.internal_path();
 
             // Move the old file to there
             self.storage
@@ -185,11 +299,24 @@ impl FileService {
 
             // Create a new file at the previous path
             self.storage
-                .create_file(internal_full_filename, data)
+                .create_file(internal_full_filename, data, &file.mime_type)
                 .await?;
         }
 
         let updated_file = self.file_db.update_file_by_id(file_id, file).await?;
+
+        // Refresh the search entry, the content only changes when a new file is uploaded
+        self.search_db
+            .index_entry(
+                SearchEntry::from_file(&updated_file, content),
+                has_new_content,
+            )
+            .await?;
+        self.publish(
+            Event::from_file(EventKind::Updated, &updated_file)
+                .moved_from(&old_file.position, &old_file.fullpath),
+        )
+        .await?;
         Ok(updated_file)
     }
 
@@ -206,7 +333,9 @@ impl FileService {
             .exists_version_by_file_id_version(file_id, version)
             .await?
         {
-            return Err("The provided version does not exists on this file".into());
+            return Err(Error::NotFound(
+                "The provided version does not exists on this file".into(),
+            ));
         }
 
         // First get the file
@@ -214,15 +343,14 @@ impl FileService {
 
         // Get its version path so we can replace the original
 
-        let restore_version_path = format!("{}/{}.{}", file.id, version, file.extension_to_str());
+        let restore_version_path = file.internal_version_path(version);
 
         // Now we need to create a new version path for that old file
         let new_version = Utc::now().timestamp_millis();
 
-        let new_file_version_path =
-            format!("{}/{}.{}", file.id, new_version, file.extension_to_str());
+        let new_file_version_path = file.internal_version_path(new_version);
 
-        let internal_full_filename = &format!("{}.{}", file.id, file.extension_to_str());
+        let internal_full_filename = &file.internal_path();
 
         // We move from the original path to the new path, in the version side
         self.storage
@@ -246,15 +374,40 @@ impl FileService {
             .await?;
 
         let file = self.file_db.update_file_time(file_id).await?;
+
+        // The restored data is now the current content, so the search entry has to follow
+        if file.has_extension("txt") {
+            let data = self
+                .storage
+                .get_data_by_key(internal_full_filename)
+                .await?
+                .collect()
+                .await?
+                .into_bytes();
+            let content = Self::searchable_content(&file, &data);
+            self.search_db
+                .index_entry(SearchEntry::from_file(&file, content), true)
+                .await?;
+        }
+        self.publish(Event::from_file(EventKind::VersionRestored, &file))
+            .await?;
         Ok(file)
     }
 
     pub async fn delete_file_by_id(&self, file_id: &ObjectId) -> Result<()> {
+        // The shares are deleted along with the file, so the users they reached are kept for the event
+        let grantees = self
+            .acl_db
+            .get_entries_by_resource(file_id)
+            .await?
+            .into_iter()
+            .map(|e| e.grantee)
+            .collect();
+
         let deleted_file = self.file_db.delete_file_by_id(file_id).await?;
 
-        let internal_full_filename =
-            &format!("{}.{}", deleted_file.id, deleted_file.extension_to_str());
-        let internal_file_version_path = &format!("{}/", deleted_file.id);
+        let internal_full_filename = &deleted_file.internal_path();
+        let internal_file_version_path = &deleted_file.internal_version_folder();
 
         self.storage.delete_file(internal_full_filename).await?;
         self.storage
@@ -262,6 +415,73 @@ impl FileService {
             .await?;
 
         self.version_db.delete_versions_by_file_id(file_id).await?;
+        self.search_db.delete_entry_by_resource(file_id).await?;
+        self.acl_db.delete_entries_by_resources(&[*file_id]).await?;
+        self.publish(Event::from_file(EventKind::Deleted, &deleted_file).with_grantees(grantees))
+            .await?;
         Ok(())
     }
+
+    pub async fn add_tags_by_id(&self, file_id: &ObjectId, tags: &[String]) -> Result<File> {
+        let file = self.get_file_by_id(file_id).await?;
+        let new_tags = tags.iter().filter(|t| !file.tags.contains(t)).count();
+        if file.tags.len() + new_tags > MAX_TAGS {
+            return Err(format!("A file can only have {MAX_TAGS} tags at most").into());
+        }
+        let file = self.file_db.add_tags(file_id, tags).await?;
+        self.publish_updated(&file).await?;
+        Ok(file)
+    }
+
+    pub async fn remove_tags_by_id(&self, file_id: &ObjectId, tags: &[String]) -> Result<File> {
+        let file = self.file_db.remove_tags(file_id, tags).await?;
+        self.publish_updated(&file).await?;
+        Ok(file)
+    }
+
+    pub async fn update_metadata_by_id(
+        &self,
+        file_id: &ObjectId,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<File> {
+        let file = self.get_file_by_id(file_id).await?;
+        let new_keys = metadata
+            .keys()
+            .filter(|k| !file.metadata.contains_key(*k))
+            .count();
+        if file.metadata.len() + new_keys > MAX_METADATA {
+            return Err(
+                format!("A file can only have {MAX_METADATA} metadata entries at most").into(),
+            );
+        }
+        let file = self.file_db.update_metadata(file_id, metadata).await?;
+        self.publish_updated(&file).await?;
+        Ok(file)
+    }
+
+    pub async fn delete_metadata_by_id(&self, file_id: &ObjectId, key: &str) -> Result<File> {
+        let file = self.get_file_by_id(file_id).await?;
+        if !file.metadata.contains_key(key) {
+            return Err(Error::NotFound(
+                "Cannot find the metadata with the provided key".into(),
+            ));
+        }
+        let file = self.file_db.delete_metadata(file_id, key).await?;
+        self.publish_updated(&file).await?;
+        Ok(file)
+    }
+
+    // Everything that is announced goes into the change log first, for the sync clients
+    async fn publish(&self, event: Event) -> Result<()> {
+        self.change_db
+            .append_changes(vec![Change::from_event(&event)])
+            .await?;
+        self.event_bus.publish(event);
+        Ok(())
+    }
+
+    async fn publish_updated(&self, file: &File) -> Result<()> {
+        self.publish(Event::from_file(EventKind::Updated, file))
+            .await
+    }
 }
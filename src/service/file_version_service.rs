@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use mongodb::bson::oid::ObjectId;
 
 use crate::{
     aws::S3,
     base::{file::File, file_version::FileVersion},
     db::file_version_db::FileVersionDB,
+    error::Error,
     Result,
 };
 
@@ -25,6 +28,23 @@ impl FileVersionService {
         self.file_version_db.get_versions_by_file_id(file_id).await
     }
 
+    // Returns the versions of every provided file, grouped by the file id
+    // Files without any version are simply missing from the map
+    pub async fn get_versions_map_by_file_ids(
+        &self,
+        file_ids: &[ObjectId],
+    ) -> Result<HashMap<ObjectId, Vec<FileVersion>>> {
+        let versions = self
+            .file_version_db
+            .get_versions_by_file_ids(file_ids)
+            .await?;
+        let mut map: HashMap<ObjectId, Vec<FileVersion>> = HashMap::new();
+        for version in versions {
+            map.entry(version.file).or_default().push(version);
+        }
+        Ok(map)
+    }
+
     pub async fn get_version_by_file_id_version(
         &self,
         file_id: &ObjectId,
@@ -41,11 +61,12 @@ impl FileVersionService {
             .exists_version_by_file_id_version(&file.id, version)
             .await?
         {
-            return Err("Cannot find the version with the provided information".into());
+            return Err(Error::NotFound(
+                "Cannot find the version with the provided information".into(),
+            ));
         }
         let file_id = file.id;
-        let internal_file_version_path =
-            &format!("{}/{}.{}", file.id, version, file.extension_to_str());
+        let internal_file_version_path = &file.internal_version_path(version);
         self.file_version_db
             .delete_version_by_file_id_version(&file_id, version)
             .await?;
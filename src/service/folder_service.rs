@@ -1,14 +1,26 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
 use futures::try_join;
-use mongodb::bson::{oid::ObjectId, Bson, Document};
+use mongodb::bson::{doc, oid::ObjectId, Bson, Document};
 
 use crate::{
     aws::S3,
-    base::folder::Folder,
-    db::{file_db::FileDB, folder_db::FolderDB},
-    helper::into_string,
-    validation::file::check_dir,
+    base::{
+        change::{Change, ChangeAction},
+        event::{Event, EventKind},
+        folder::{Folder, Visibility},
+        search_entry::SearchEntry,
+    },
+    db::{
+        acl_db::AclDB, change_db::ChangeDB, file_db::FileDB, file_request_db::FileRequestDB,
+        folder_db::FolderDB, search_db::SearchDB, share_link_db::ShareLinkDB,
+    },
+    error::Error,
+    helper::{event_bus::EventBus, into_string, position::normalize_path},
+    validation::file::{check_dir, MAX_METADATA, MAX_TAGS},
     Result,
 };
 
@@ -16,28 +28,62 @@ use crate::{
 pub struct FolderService {
     file_db: FileDB,
     folder_db: FolderDB,
+    search_db: SearchDB,
+    acl_db: AclDB,
+    share_link_db: ShareLinkDB,
+    file_request_db: FileRequestDB,
     storage: S3,
+    change_db: ChangeDB,
+    event_bus: EventBus,
 }
 
 impl FolderService {
-    pub fn init(file_db: &FileDB, folder_db: &FolderDB, storage: &S3) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        file_db: &FileDB,
+        folder_db: &FolderDB,
+        search_db: &SearchDB,
+        acl_db: &AclDB,
+        share_link_db: &ShareLinkDB,
+        file_request_db: &FileRequestDB,
+        storage: &S3,
+        change_db: &ChangeDB,
+        event_bus: &EventBus,
+    ) -> Self {
         Self {
             file_db: file_db.clone(),
             folder_db: folder_db.clone(),
+            search_db: search_db.clone(),
+            acl_db: acl_db.clone(),
+            share_link_db: share_link_db.clone(),
+            file_request_db: file_request_db.clone(),
             storage: storage.clone(),
+            change_db: change_db.clone(),
+            event_bus: event_bus.clone(),
         }
     }
 
     pub async fn get_folders_by_map(&self, map: &HashMap<String, String>) -> Result<Vec<Folder>> {
         let mut document = HashMap::new();
         for i in map {
-            if *i.0 == "owner" {
-                document.insert(
-                    "owner".to_string(),
-                    Bson::ObjectId(ObjectId::from_str(i.1)?),
-                );
+            if *i.0 == "owner" || *i.0 == "team" {
+                document.insert(i.0.to_string(), Bson::ObjectId(ObjectId::from_str(i.1)?));
+            } else if *i.0 == "tags" {
+                // tags=invoice,final matches the folders having all of the listed tags
+                let tags =
+                    i.1.split(',')
+                        .map(|t| t.trim().to_lowercase())
+                        .filter(|t| !t.is_empty())
+                        .collect::<Vec<_>>();
+                document.insert("tags".to_string(), Bson::Document(doc! {"$all": tags}));
             } else {
-                document.insert(i.0.to_string(), Bson::String(i.1.to_string()));
+                // Names and paths are stored in NFC, so the filter has to be as well
+                // This also covers metadata filters, like metadata.project=abc
+                let value = match i.0.as_str() {
+                    "folderName" | "position" | "fullpath" => normalize_path(i.1),
+                    _ => i.1.to_string(),
+                };
+                document.insert(i.0.to_string(), Bson::String(value));
             }
         }
         let doc = Document::from_iter(document);
@@ -52,8 +98,27 @@ impl FolderService {
     //     self.folder_db.get_public_folders().await
     // }
 
+    // The public ones, including the ones that inherit a public visibility
     pub async fn get_public_folders_by_owner(&self, owner: &ObjectId) -> Result<Vec<Folder>> {
-        self.folder_db.get_public_folders_by_owner(owner).await
+        let folders = self
+            .folder_db
+            .get_folders_by(doc! {"owner": owner, "visibility": {"$ne": "private"}})
+            .await?;
+        self.filter_public_folders(folders).await
+    }
+
+    // Keeps the folders that are public, either by themselves or through the folders above them
+    pub async fn filter_public_folders(&self, folders: Vec<Folder>) -> Result<Vec<Folder>> {
+        let dirs = folders
+            .iter()
+            .filter(|f| f.visibility == Visibility::Inherit)
+            .map(|f| f.position.as_str())
+            .collect::<Vec<_>>();
+        let dir_visibilities = self.folder_db.get_dir_visibilities(&dirs).await?;
+        Ok(folders
+            .into_iter()
+            .filter(|f| f.is_public(&dir_visibilities))
+            .collect())
     }
 
     pub async fn get_folder_by_id(&self, folder_id: &ObjectId) -> Result<Folder> {
@@ -71,7 +136,13 @@ impl FolderService {
     // }
 
     pub async fn get_public_folder_by_id(&self, folder_id: &ObjectId) -> Result<Folder> {
-        self.folder_db.get_public_folder_by_id(folder_id).await
+        let folder = self.folder_db.get_folder_by_id(folder_id).await?;
+        self.filter_public_folders(vec![folder])
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                Error::NotFound("Cannot find the folder with the provided information".into())
+            })
     }
 
     // pub async fn get_folder_by_fullpath(&self, fullpath: &str) -> Result<Folder> {
@@ -114,9 +185,9 @@ impl FolderService {
 
     pub async fn create_folder(&self, folder: Folder) -> Result<Folder> {
         if self.exists_folder_by_fullpath(&folder.fullpath).await? {
-            return Err(
+            return Err(Error::Conflict(
                 "The folder with this name already existed. Please try another folder name".into(),
-            );
+            ));
         }
 
         if !self.exists_folder_by_fullpath(&folder.position).await? {
@@ -125,6 +196,12 @@ impl FolderService {
 
         let folder = self.folder_db.create_folder(folder).await?;
 
+        self.search_db
+            .index_entry(SearchEntry::from_folder(&folder), false)
+            .await?;
+        self.publish(Event::from_folder(EventKind::Created, &folder))
+            .await?;
+
         Ok(folder)
     }
 
@@ -135,11 +212,16 @@ impl FolderService {
     ) -> Result<Folder> {
         let old_folder = self.folder_db.get_folder_by_id(folder_id).await?;
 
+        // Everything inside moves along, which the sync clients hear about one by one
+        let mut inner_changes = vec![];
+
         if old_folder.fullpath != folder.fullpath {
             let exists_folder = self.exists_folder_by_fullpath(&folder.fullpath).await?;
             let exists_folder_at_postion = self.exists_folder_by_fullpath(&folder.position).await?;
             if exists_folder {
-                return Err("This folder name already existed. Please use another name".into());
+                return Err(Error::Conflict(
+                    "This folder name already existed. Please use another name".into(),
+                ));
             }
             if !exists_folder_at_postion {
                 return Err("Cannot move folder to a virtual position".into());
@@ -147,6 +229,13 @@ impl FolderService {
             if old_folder.fullpath == folder.position {
                 return Err("Cannot move to self".into());
             }
+            let (old_inner_folders, old_inner_files) = try_join!(
+                self.folder_db
+                    .get_folders_by_prefix_position(&old_folder.fullpath),
+                self.file_db
+                    .get_files_by_prefix_fullpath(&old_folder.fullpath)
+            )?;
+
             if old_folder.fullpath.matches('/').count() == folder.fullpath.matches('/').count() {
                 // This means that the user is renaming a folder
                 try_join!(
@@ -176,9 +265,32 @@ impl FolderService {
                     )
                 )?;
             }
+
+            let (new_inner_folders, new_inner_files) = try_join!(
+                self.folder_db
+                    .get_folders_by_prefix_position(&folder.fullpath),
+                self.file_db.get_files_by_prefix_fullpath(&folder.fullpath)
+            )?;
+            inner_changes = Change::moved_tree(
+                &old_inner_folders,
+                &new_inner_folders,
+                &old_inner_files,
+                &new_inner_files,
+            );
         }
 
         let updated_folder = self.folder_db.update_folder(folder_id, folder).await?;
+
+        self.search_db
+            .index_entry(SearchEntry::from_folder(&updated_folder), false)
+            .await?;
+        // Only the folder itself is announced, the clients reload what is inside of it
+        self.publish(
+            Event::from_folder(EventKind::Updated, &updated_folder)
+                .moved_from(&old_folder.position, &old_folder.fullpath),
+        )
+        .await?;
+        self.change_db.append_changes(inner_changes).await?;
         Ok(updated_folder)
     }
 
@@ -188,11 +300,21 @@ impl FolderService {
         owner: &ObjectId,
     ) -> Result<()> {
         // Edge case: Cannot let the user delete the root folder
+        // The root folder is the only one whose position is the same as its fullpath
         let folder_to_delete = self.get_folder_by_id(folder_id).await?;
-        if folder_to_delete.folder_name == owner.to_string() {
+        if folder_to_delete.position == folder_to_delete.fullpath {
             return Err("You cannot delete the root folder".into());
         }
 
+        // The shares are deleted along with the folder, so the users they reached are kept for the event
+        let grantees = self
+            .acl_db
+            .get_entries_by_resource(folder_id)
+            .await?
+            .into_iter()
+            .map(|e| e.grantee)
+            .collect();
+
         let deleted_folder = self
             .folder_db
             .delete_folder_by_id_owner(folder_id, owner)
@@ -204,9 +326,30 @@ impl FolderService {
             .get_files_by_prefix_fullpath(&deleted_folder.fullpath)
             .await?;
 
+        // The inner folders are about to be deleted, keep their ids to clean up the search index
+        let inner_folders = self
+            .folder_db
+            .get_folders_by_prefix_position(&deleted_folder.fullpath)
+            .await?;
+
+        let mut deleted_resources = vec![deleted_folder.id];
+        deleted_resources.extend(inner_folders.iter().map(|f| f.id));
+        deleted_resources.extend(files.iter().map(|f| f.id));
+
+        // A tombstone for everything inside, not only for the folder
+        let inner_changes = inner_folders
+            .iter()
+            .map(|f| Change::from_folder(ChangeAction::Deleted, f))
+            .chain(
+                files
+                    .iter()
+                    .map(|f| Change::from_file(ChangeAction::Deleted, f)),
+            )
+            .collect::<Vec<_>>();
+
         for file in files {
-            let internal_full_filename = &format!("{}.{}", file.id, file.extension_to_str());
-            let internal_file_version_path = &format!("{}/", file.id);
+            let internal_full_filename = &file.internal_path();
+            let internal_file_version_path = &file.internal_version_folder();
 
             self.storage.delete_file(internal_full_filename).await?;
             self.storage
@@ -222,6 +365,149 @@ impl FolderService {
             .delete_files_by_prefix_fullpath(&deleted_folder.fullpath)
             .await?;
 
+        self.search_db
+            .delete_entries_by_resources(&deleted_resources)
+            .await?;
+        self.acl_db
+            .delete_entries_by_resources(&deleted_resources)
+            .await?;
+        self.share_link_db
+            .delete_links_by_folders(&deleted_resources)
+            .await?;
+        self.file_request_db
+            .delete_requests_by_folders(&deleted_resources)
+            .await?;
+
+        self.publish(
+            Event::from_folder(EventKind::Deleted, &deleted_folder).with_grantees(grantees),
+        )
+        .await?;
+        self.change_db.append_changes(inner_changes).await?;
+
+        Ok(())
+    }
+
+    // The visibility for a new file or folder at the position, when it does not ask for one
+    pub async fn get_child_visibility(&self, position: &str) -> Result<Visibility> {
+        let parent = self.folder_db.get_folder_by_fullpath(position).await?;
+        Ok(parent.child_visibility.unwrap_or(Visibility::Inherit))
+    }
+
+    // With recursive, every file and folder inside gets the same visibility as well
+    pub async fn update_visibility_by_id(
+        &self,
+        folder_id: &ObjectId,
+        visibility: Visibility,
+        recursive: bool,
+    ) -> Result<Folder> {
+        let folder = self.folder_db.get_folder_by_id(folder_id).await?;
+
+        if !recursive {
+            let updated_folder = self
+                .folder_db
+                .update_visibility(folder_id, visibility)
+                .await?;
+            self.search_db
+                .update_visibilities_by_resources(&[updated_folder.id], visibility.as_str())
+                .await?;
+            self.publish_updated(&updated_folder).await?;
+            return Ok(updated_folder);
+        }
+
+        try_join!(
+            self.folder_db
+                .update_visibilities_by_prefix_fullpath(&folder.fullpath, visibility),
+            self.file_db
+                .update_visibilities_by_prefix_fullpath(&folder.fullpath, visibility.into())
+        )?;
+
+        let (inner_folders, files) = try_join!(
+            self.folder_db
+                .get_folders_by_prefix_position(&folder.fullpath),
+            self.file_db.get_files_by_prefix_fullpath(&folder.fullpath)
+        )?;
+        let mut resources = vec![folder.id];
+        resources.extend(inner_folders.iter().map(|f| f.id));
+        resources.extend(files.iter().map(|f| f.id));
+        self.search_db
+            .update_visibilities_by_resources(&resources, visibility.as_str())
+            .await?;
+
+        let updated_folder = self.folder_db.get_folder_by_id(folder_id).await?;
+        self.publish_updated(&updated_folder).await?;
+        let inner_changes = inner_folders
+            .iter()
+            .map(|f| Change::from_folder(ChangeAction::Updated, f))
+            .chain(
+                files
+                    .iter()
+                    .map(|f| Change::from_file(ChangeAction::Updated, f)),
+            )
+            .collect();
+        self.change_db.append_changes(inner_changes).await?;
+        Ok(updated_folder)
+    }
+
+    pub async fn add_tags_by_id(&self, folder_id: &ObjectId, tags: &[String]) -> Result<Folder> {
+        let folder = self.folder_db.get_folder_by_id(folder_id).await?;
+        let new_tags = tags.iter().filter(|t| !folder.tags.contains(t)).count();
+        if folder.tags.len() + new_tags > MAX_TAGS {
+            return Err(format!("A folder can only have {MAX_TAGS} tags at most").into());
+        }
+        let folder = self.folder_db.add_tags(folder_id, tags).await?;
+        self.publish_updated(&folder).await?;
+        Ok(folder)
+    }
+
+    pub async fn remove_tags_by_id(&self, folder_id: &ObjectId, tags: &[String]) -> Result<Folder> {
+        let folder = self.folder_db.remove_tags(folder_id, tags).await?;
+        self.publish_updated(&folder).await?;
+        Ok(folder)
+    }
+
+    pub async fn update_metadata_by_id(
+        &self,
+        folder_id: &ObjectId,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<Folder> {
+        let folder = self.folder_db.get_folder_by_id(folder_id).await?;
+        let new_keys = metadata
+            .keys()
+            .filter(|k| !folder.metadata.contains_key(*k))
+            .count();
+        if folder.metadata.len() + new_keys > MAX_METADATA {
+            return Err(
+                format!("A folder can only have {MAX_METADATA} metadata entries at most").into(),
+            );
+        }
+        let folder = self.folder_db.update_metadata(folder_id, metadata).await?;
+        self.publish_updated(&folder).await?;
+        Ok(folder)
+    }
+
+    pub async fn delete_metadata_by_id(&self, folder_id: &ObjectId, key: &str) -> Result<Folder> {
+        let folder = self.folder_db.get_folder_by_id(folder_id).await?;
+        if !folder.metadata.contains_key(key) {
+            return Err(Error::NotFound(
+                "Cannot find the metadata with the provided key".into(),
+            ));
+        }
+        let folder = self.folder_db.delete_metadata(folder_id, key).await?;
+        self.publish_updated(&folder).await?;
+        Ok(folder)
+    }
+
+    // Everything that is announced goes into the change log first, for the sync clients
+    async fn publish(&self, event: Event) -> Result<()> {
+        self.change_db
+            .append_changes(vec![Change::from_event(&event)])
+            .await?;
+        self.event_bus.publish(event);
         Ok(())
     }
+
+    async fn publish_updated(&self, folder: &Folder) -> Result<()> {
+        self.publish(Event::from_folder(EventKind::Updated, folder))
+            .await
+    }
 }
@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use mongodb::bson::{doc, Bson};
+
+use crate::{
+    aws::S3,
+    base::{
+        file::File,
+        file_version::FileVersion,
+        migration::Migration,
+        reconciliation::Reconciliation,
+        search_entry::SearchEntry,
+        user::{Role, Status},
+    },
+    db::{
+        file_db::FileDB, file_version_db::FileVersionDB, folder_db::FolderDB,
+        migration_db::MigrationDB, search_db::SearchDB, user_db::UserDB,
+    },
+    service::file_service::FileService,
+    Result,
+};
+
+// The schema migrations, in the order they are applied
+// A migration is never changed once it has shipped, a new one is added instead
+pub const MIGRATIONS: &[&str] = &["fill-default-fields", "index-search", "index-search-teams"];
+
+// An upload writes to the storage before the database,
+// so what was written this recently might just not be in the database yet
+const ORPHAN_GRACE_PERIOD: i64 = 60 * 60 * 1000;
+
+// The tasks that are run by hand, outside of the requests
+#[derive(Debug, Clone)]
+pub struct MaintenanceService {
+    file_db: FileDB,
+    folder_db: FolderDB,
+    file_version_db: FileVersionDB,
+    user_db: UserDB,
+    search_db: SearchDB,
+    migration_db: MigrationDB,
+    storage: S3,
+}
+
+impl MaintenanceService {
+    pub fn init(
+        file_db: &FileDB,
+        folder_db: &FolderDB,
+        file_version_db: &FileVersionDB,
+        user_db: &UserDB,
+        search_db: &SearchDB,
+        migration_db: &MigrationDB,
+        storage: &S3,
+    ) -> Self {
+        Self {
+            file_db: file_db.clone(),
+            folder_db: folder_db.clone(),
+            file_version_db: file_version_db.clone(),
+            user_db: user_db.clone(),
+            search_db: search_db.clone(),
+            migration_db: migration_db.clone(),
+            storage: storage.clone(),
+        }
+    }
+
+    pub async fn get_pending_migrations(&self) -> Result<Vec<&'static str>> {
+        let applied = self.migration_db.get_applied_names().await?;
+        Ok(MIGRATIONS
+            .iter()
+            .filter(|m| !applied.contains(**m))
+            .copied()
+            .collect())
+    }
+
+    // Applies the pending migrations one by one, a failing one stops the ones after it
+    pub async fn run_migrations(&self) -> Result<Vec<Migration>> {
+        let mut applied = vec![];
+        for name in self.get_pending_migrations().await? {
+            let migration = Migration {
+                name: name.to_string(),
+                modified: self.apply_migration(name).await?,
+                applied_at: Utc::now().timestamp_millis(),
+            };
+            self.migration_db
+                .create_migration(migration.clone())
+                .await?;
+            applied.push(migration);
+        }
+        Ok(applied)
+    }
+
+    async fn apply_migration(&self, name: &str) -> Result<u64> {
+        match name {
+            // The documents written before the fields were added only get the defaults when read,
+            // the queries on those fields did not match them
+            "fill-default-fields" => {
+                let mut modified = self
+                    .migration_db
+                    .set_missing_fields(
+                        "User",
+                        vec![
+                            ("emailVerified", false.into()),
+                            ("role", Role::User.as_str().into()),
+                            ("status", Status::Active.as_str().into()),
+                            ("identities", Bson::Array(vec![])),
+                        ],
+                    )
+                    .await?;
+                for collection in ["File", "Folder"] {
+                    modified += self
+                        .migration_db
+                        .set_missing_fields(
+                            collection,
+                            vec![
+                                ("tags", Bson::Array(vec![])),
+                                ("metadata", Bson::Document(doc! {})),
+                                ("hidden", false.into()),
+                            ],
+                        )
+                        .await?;
+                }
+                Ok(modified)
+            }
+            // The search index only got the files and folders written after it was added
+            "index-search" => Ok(self.reindex_search().await? as u64),
+            // The entries written before did not have the team
+            "index-search-teams" => Ok(self.reindex_search().await? as u64),
+            _ => Err(format!("There is no migration named {name}").into()),
+        }
+    }
+
+    // Writes the search entry of every file and folder again, along with the content of the text files
+    // Returns how many entries were written
+    pub async fn reindex_search(&self) -> Result<usize> {
+        let folders = self.folder_db.get_folders_by(doc! {}).await?;
+        for folder in &folders {
+            self.search_db
+                .index_entry(SearchEntry::from_folder(folder), false)
+                .await?;
+        }
+
+        let files = self.file_db.get_files_by(doc! {}).await?;
+        for file in &files {
+            let content = self.get_searchable_content(file).await?;
+            self.search_db
+                .index_entry(SearchEntry::from_file(file, content), true)
+                .await?;
+        }
+        Ok(folders.len() + files.len())
+    }
+
+    async fn get_searchable_content(&self, file: &File) -> Result<String> {
+        if !file.has_extension("txt") {
+            return Ok(String::new());
+        }
+        // An empty upload has nothing on the storage, a missing content is left to the reconciliation
+        let data = match self.storage.get_data_by_key(&file.internal_path()).await {
+            Ok(data) => data.collect().await?.into_bytes(),
+            Err(_) => return Ok(String::new()),
+        };
+        Ok(FileService::searchable_content(file, &data))
+    }
+
+    // Compares every object on the storage with the files and the versions in the database
+    // With fix, the orphan objects and the broken versions are deleted,
+    // the files are only reported, since deleting them would lose what is left of them
+    pub async fn reconcile(&self, fix: bool) -> Result<Reconciliation> {
+        let files = self.file_db.get_files_by(doc! {}).await?;
+        let versions = self.file_version_db.get_versions_by(doc! {}).await?;
+        let users = self
+            .user_db
+            .get_users()
+            .await?
+            .into_iter()
+            .map(|u| u.id)
+            .collect::<HashSet<_>>();
+        let objects = self.storage.get_all_with_time("").await?;
+        let keys = objects
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<HashSet<_>>();
+
+        let files_by_id = files.iter().map(|f| (f.id, f)).collect::<HashMap<_, _>>();
+        let mut expected = HashSet::new();
+        let mut reconciliation = Reconciliation::default();
+
+        for file in &files {
+            expected.insert(file.internal_path());
+            expected.insert(file.internal_version_folder());
+            if !keys.contains(file.internal_path().as_str()) {
+                reconciliation.missing_contents.push(file.id);
+            }
+            if !users.contains(&file.owner) {
+                reconciliation.ownerless_files.push(file.id);
+            }
+        }
+
+        for version in &versions {
+            let Some(file) = files_by_id.get(&version.file) else {
+                reconciliation.broken_versions.push(version.id);
+                continue;
+            };
+            let path = file.internal_version_path(version.version_number);
+            if !keys.contains(path.as_str()) {
+                reconciliation.broken_versions.push(version.id);
+            }
+            expected.insert(path);
+        }
+
+        let written_before = Utc::now().timestamp_millis() - ORPHAN_GRACE_PERIOD;
+        reconciliation.orphan_objects = objects
+            .into_iter()
+            .filter(|(key, modified)| !expected.contains(key) && *modified < written_before)
+            .map(|(key, _)| key)
+            .collect();
+
+        if fix {
+            self.storage
+                .delete_keys(&reconciliation.orphan_objects)
+                .await?;
+            for version in &reconciliation.broken_versions {
+                self.file_version_db.delete_version_by_id(version).await?;
+            }
+        }
+        Ok(reconciliation)
+    }
+
+    // Deletes the versions of every file except for the newest ones to keep,
+    // only the versions made before the time are deleted, returns how many were deleted
+    pub async fn prune_versions(&self, keep: usize, before: Option<i64>) -> Result<usize> {
+        let files = self
+            .file_db
+            .get_files_by(doc! {})
+            .await?
+            .into_iter()
+            .map(|f| (f.id, f))
+            .collect::<HashMap<_, _>>();
+
+        let mut versions_by_file = HashMap::<_, Vec<FileVersion>>::new();
+        for version in self.file_version_db.get_versions_by(doc! {}).await? {
+            versions_by_file
+                .entry(version.file)
+                .or_default()
+                .push(version);
+        }
+
+        let mut pruned = 0;
+        for (file_id, mut versions) in versions_by_file {
+            // The versions of a file that does not exist anymore are left to the reconciliation
+            let Some(file) = files.get(&file_id) else {
+                continue;
+            };
+
+            versions.sort_by_key(|v| std::cmp::Reverse(v.version_number));
+            for version in versions.into_iter().skip(keep) {
+                if before.is_some_and(|before| version.created_at >= before) {
+                    continue;
+                }
+                self.delete_version(file, &version).await?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+
+    async fn delete_version(&self, file: &File, version: &FileVersion) -> Result<()> {
+        self.storage
+            .delete_file(&file.internal_version_path(version.version_number))
+            .await?;
+        self.file_version_db.delete_version_by_id(&version.id).await
+    }
+}
@@ -1,4 +1,14 @@
+pub mod account_service;
+pub mod acl_service;
+pub mod change_service;
+pub mod file_request_service;
 pub mod file_service;
 pub mod file_version_service;
 pub mod folder_service;
+pub mod maintenance_service;
+pub mod oidc_service;
+pub mod search_service;
+pub mod share_link_service;
+pub mod team_service;
 pub mod user_service;
+pub mod webhook_service;
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use crate::{
+    base::oidc_login::OidcLogin,
+    db::oidc_login_db::OidcLoginDB,
+    helper::oidc::{
+        authorization_url, discover, exchange_code, verify_id_token, IdClaims, OidcProvider,
+    },
+    Result,
+};
+
+#[derive(Debug, Clone)]
+pub struct OidcService {
+    oidc_login_db: OidcLoginDB,
+    providers: HashMap<String, OidcProvider>,
+}
+
+impl OidcService {
+    pub fn init(oidc_login_db: &OidcLoginDB, providers: &HashMap<String, OidcProvider>) -> Self {
+        Self {
+            oidc_login_db: oidc_login_db.clone(),
+            providers: providers.clone(),
+        }
+    }
+
+    pub fn get_provider_names(&self) -> Vec<String> {
+        let mut names = self.providers.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    fn get_provider(&self, name: &str) -> Result<&OidcProvider> {
+        self.providers
+            .get(name)
+            .ok_or_else(|| format!("There is no identity provider named {name}").into())
+    }
+
+    // Returns where to send the user to log in at the provider
+    pub async fn begin_login(&self, provider_name: &str) -> Result<String> {
+        let provider = self.get_provider(provider_name)?;
+        let discovery = discover(provider).await?;
+
+        let login = OidcLogin::new(&provider.name);
+        let url = authorization_url(
+            provider,
+            &discovery,
+            &login.state,
+            &login.nonce,
+            &login.code_verifier,
+        )?;
+        self.oidc_login_db.create_login(login).await?;
+        Ok(url)
+    }
+
+    // Called when the provider sends the user back, returns who the user is at the provider
+    pub async fn finish_login(
+        &self,
+        provider_name: &str,
+        code: &str,
+        state: &str,
+    ) -> Result<IdClaims> {
+        let provider = self.get_provider(provider_name)?;
+        let login = self
+            .oidc_login_db
+            .consume_login(state, &provider.name)
+            .await?;
+
+        let discovery = discover(provider).await?;
+        let id_token = exchange_code(provider, &discovery, code, &login.code_verifier).await?;
+        verify_id_token(provider, &discovery, &id_token, &login.nonce).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    use super::*;
+    use crate::{db::mongo::DB, helper::oidc::tests::MockIdp};
+
+    async fn setup(idp: &MockIdp) -> (DB, OidcService) {
+        let db = DB::init_test().await;
+        let oidc_login_db = OidcLoginDB::init(&db);
+        oidc_login_db.create_indexes().await.unwrap();
+        let providers = HashMap::from([("mock".to_string(), idp.provider())]);
+        (db, OidcService::init(&oidc_login_db, &providers))
+    }
+
+    // The state and the nonce that the provider gets in the redirect
+    fn read_login(url: &str) -> (String, String) {
+        let url = Url::parse(url).unwrap();
+        let param = |name| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+                .unwrap()
+        };
+        (param("state"), param("nonce"))
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn finishes_a_login_once() {
+        let idp = MockIdp::start().await;
+        let (db, oidc_service) = setup(&idp).await;
+
+        let url = oidc_service.begin_login("mock").await.unwrap();
+        assert!(url.starts_with(&format!("{}/authorize?", idp.issuer)));
+        let (state, nonce) = read_login(&url);
+        idp.issue(&idp.claims(&nonce));
+
+        let claims = oidc_service
+            .finish_login("mock", "code", &state)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "mock-user-1");
+
+        // The state is used up, a replayed callback is refused
+        assert!(oidc_service
+            .finish_login("mock", "code", &state)
+            .await
+            .is_err());
+        db.drop_test().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn refuses_a_token_with_the_nonce_of_another_login() {
+        let idp = MockIdp::start().await;
+        let (db, oidc_service) = setup(&idp).await;
+
+        let (first_state, first_nonce) =
+            read_login(&oidc_service.begin_login("mock").await.unwrap());
+        let (second_state, _) = read_login(&oidc_service.begin_login("mock").await.unwrap());
+
+        idp.issue(&idp.claims(&first_nonce));
+        assert!(oidc_service
+            .finish_login("mock", "code", &second_state)
+            .await
+            .is_err());
+        assert!(oidc_service
+            .finish_login("mock", "code", &first_state)
+            .await
+            .is_ok());
+        db.drop_test().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB at TEST_MONGODB_URI"]
+    async fn refuses_an_unknown_state() {
+        let idp = MockIdp::start().await;
+        let (db, oidc_service) = setup(&idp).await;
+
+        let (_, nonce) = read_login(&oidc_service.begin_login("mock").await.unwrap());
+        idp.issue(&idp.claims(&nonce));
+        assert!(oidc_service
+            .finish_login("mock", "code", "made-up-state")
+            .await
+            .is_err());
+        db.drop_test().await;
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::{HashMap, HashSet};
+
+use futures::try_join;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+
+use crate::{
+    base::{
+        file::File,
+        folder::Folder,
+        search_entry::{ResourceKind, SearchHit},
+    },
+    db::{
+        acl_db::AclDB, file_db::FileDB, folder_db::FolderDB, search_db::SearchDB, team_db::TeamDB,
+    },
+    Result,
+};
+
+#[derive(Debug, Clone)]
+pub struct SearchService {
+    search_db: SearchDB,
+    file_db: FileDB,
+    folder_db: FolderDB,
+    acl_db: AclDB,
+    team_db: TeamDB,
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchResource {
+    File(File),
+    Folder(Folder),
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub hit: SearchHit,
+    pub resource: SearchResource,
+}
+
+// How many pages of the index are read at most to fill one page of results,
+// the hits that the viewer cannot see are dropped along the way
+const MAX_SEARCH_ROUNDS: u64 = 5;
+
+// What the viewer can see besides the public files and folders
+#[derive(Debug, Default)]
+struct Reach {
+    viewer: Option<ObjectId>,
+    teams: Vec<ObjectId>,
+    shared: HashSet<ObjectId>,
+}
+
+impl Reach {
+    // The same rules for the index, where the id is the resource, and for the files and folders
+    fn filter(&self, id_field: &str) -> Document {
+        let mut access = vec![doc! {"visibility": {"$in": ["public", "inherit"]}}];
+        if let Some(viewer) = self.viewer {
+            access.push(doc! {"owner": viewer, "team": null});
+        }
+        if !self.teams.is_empty() {
+            access.push(doc! {"team": {"$in": &self.teams}});
+        }
+        if !self.shared.is_empty() {
+            let shared = self.shared.iter().collect::<Vec<_>>();
+            access.push(doc! {id_field: {"$in": shared}});
+        }
+        doc! {"$or": access}
+    }
+
+    // Whether the viewer sees it no matter its visibility
+    fn has(&self, id: &ObjectId, owner: &ObjectId, team: Option<&ObjectId>) -> bool {
+        let in_tree = match team {
+            Some(team) => self.teams.contains(team),
+            None => self.viewer.as_ref() == Some(owner),
+        };
+        in_tree || self.shared.contains(id)
+    }
+}
+
+impl SearchService {
+    pub fn init(
+        search_db: &SearchDB,
+        file_db: &FileDB,
+        folder_db: &FolderDB,
+        acl_db: &AclDB,
+        team_db: &TeamDB,
+    ) -> Self {
+        Self {
+            search_db: search_db.clone(),
+            file_db: file_db.clone(),
+            folder_db: folder_db.clone(),
+            acl_db: acl_db.clone(),
+            team_db: team_db.clone(),
+        }
+    }
+
+    // The teams of the viewer, and everything shared with them along with what is inside of the shared folders
+    async fn get_reach(&self, viewer: Option<&ObjectId>) -> Result<Reach> {
+        let Some(viewer) = viewer else {
+            return Ok(Reach::default());
+        };
+
+        let teams = self
+            .team_db
+            .get_teams_by_member(viewer)
+            .await?
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+
+        let entries = self.acl_db.get_entries_by_grantee(viewer).await?;
+        let mut shared = entries.iter().map(|e| e.resource).collect::<HashSet<_>>();
+        let shared_folder_ids = entries
+            .iter()
+            .filter(|e| e.kind == ResourceKind::Folder)
+            .map(|e| e.resource)
+            .collect::<Vec<_>>();
+        if !shared_folder_ids.is_empty() {
+            let shared_folders = self
+                .folder_db
+                .get_folders_by(doc! {"_id": {"$in": shared_folder_ids}})
+                .await?;
+            for folder in shared_folders {
+                let (inner_folders, inner_files) = try_join!(
+                    self.folder_db
+                        .get_folders_by_prefix_position(&folder.fullpath),
+                    self.file_db.get_files_by_prefix_fullpath(&folder.fullpath)
+                )?;
+                shared.extend(inner_folders.iter().map(|f| f.id));
+                shared.extend(inner_files.iter().map(|f| f.id));
+            }
+        }
+
+        Ok(Reach {
+            viewer: Some(*viewer),
+            teams,
+            shared,
+        })
+    }
+
+    pub async fn search(
+        &self,
+        query: &str,
+        viewer: Option<&ObjectId>,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>> {
+        let reach = self.get_reach(viewer).await?;
+        let access = reach.filter("resource");
+
+        // The inherit hits that turn out to be private are dropped after the search,
+        // so the index is read page after page until there are enough results
+        let mut results = Vec::new();
+        for round in 0..MAX_SEARCH_ROUNDS {
+            let hits = self
+                .search_db
+                .search(query, access.clone(), round * limit as u64, limit)
+                .await?;
+            let is_last = (hits.len() as i64) < limit;
+
+            results.extend(self.keep_visible(hits, &reach).await?);
+            if is_last || results.len() as i64 >= limit {
+                break;
+            }
+        }
+        results.truncate(limit as usize);
+        Ok(results)
+    }
+
+    async fn keep_visible(&self, hits: Vec<SearchHit>, reach: &Reach) -> Result<Vec<SearchResult>> {
+        let file_ids = hits
+            .iter()
+            .filter(|h| h.entry.kind == ResourceKind::File)
+            .map(|h| h.entry.resource)
+            .collect::<Vec<_>>();
+        let folder_ids = hits
+            .iter()
+            .filter(|h| h.entry.kind == ResourceKind::Folder)
+            .map(|h| h.entry.resource)
+            .collect::<Vec<_>>();
+
+        // The index might lag behind, so the actual files and folders are fetched
+        // with the same rules as the index
+        let access = reach.filter("_id");
+        let file_filter = doc! {"_id": {"$in": file_ids}, "$and": [access.clone()]};
+        let folder_filter = doc! {"_id": {"$in": folder_ids}, "$and": [access]};
+
+        let files = self.file_db.get_files_by(file_filter).await?;
+        let folders = self.folder_db.get_folders_by(folder_filter).await?;
+
+        // The inherit ones are only kept when the folders above them make them public
+        let dirs = files
+            .iter()
+            .map(|f| f.position.as_str())
+            .chain(folders.iter().map(|f| f.position.as_str()))
+            .collect::<Vec<_>>();
+        let dir_visibilities = self.folder_db.get_dir_visibilities(&dirs).await?;
+
+        let mut files = files
+            .into_iter()
+            .filter(|f| {
+                reach.has(&f.id, &f.owner, f.team.as_ref()) || f.is_public(&dir_visibilities)
+            })
+            .map(|f| (f.id, f))
+            .collect::<HashMap<_, _>>();
+        let mut folders = folders
+            .into_iter()
+            .filter(|f| {
+                reach.has(&f.id, &f.owner, f.team.as_ref()) || f.is_public(&dir_visibilities)
+            })
+            .map(|f| (f.id, f))
+            .collect::<HashMap<_, _>>();
+
+        // Keep the ranking of the search index
+        let results = hits
+            .into_iter()
+            .filter_map(|hit| {
+                let resource = match hit.entry.kind {
+                    ResourceKind::File => SearchResource::File(files.remove(&hit.entry.resource)?),
+                    ResourceKind::Folder => {
+                        SearchResource::Folder(folders.remove(&hit.entry.resource)?)
+                    }
+                };
+                Some(SearchResult { hit, resource })
+            })
+            .collect();
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sees_the_own_team_and_shared_resources() {
+        let viewer = ObjectId::new();
+        let other = ObjectId::new();
+        let team = ObjectId::new();
+        let shared = ObjectId::new();
+        let reach = Reach {
+            viewer: Some(viewer),
+            teams: vec![team],
+            shared: HashSet::from([shared]),
+        };
+
+        assert!(reach.has(&ObjectId::new(), &viewer, None));
+        assert!(reach.has(&ObjectId::new(), &other, Some(&team)));
+        assert!(reach.has(&shared, &other, None));
+        assert!(!reach.has(&ObjectId::new(), &other, None));
+        // What the viewer made in a team that they left is the team's
+        assert!(!reach.has(&ObjectId::new(), &viewer, Some(&ObjectId::new())));
+
+        let guest = Reach::default();
+        assert!(!guest.has(&shared, &viewer, None));
+        assert_eq!(
+            guest.filter("resource"),
+            doc! {"$or": [{"visibility": {"$in": ["public", "inherit"]}}]}
+        );
+    }
+}
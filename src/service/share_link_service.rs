@@ -0,0 +1,111 @@
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{
+    base::{file::File, folder::Folder, share_link::ShareLink},
+    db::{file_db::FileDB, folder_db::FolderDB, share_link_db::ShareLinkDB},
+    error::Error,
+    helper::{into_string, position::normalize_path},
+    validation::file::check_dir,
+    Result,
+};
+
+#[derive(Debug, Clone)]
+pub struct ShareLinkService {
+    share_link_db: ShareLinkDB,
+    file_db: FileDB,
+    folder_db: FolderDB,
+}
+
+impl ShareLinkService {
+    pub fn init(share_link_db: &ShareLinkDB, file_db: &FileDB, folder_db: &FolderDB) -> Self {
+        Self {
+            share_link_db: share_link_db.clone(),
+            file_db: file_db.clone(),
+            folder_db: folder_db.clone(),
+        }
+    }
+
+    pub async fn create_link(&self, link: ShareLink) -> Result<ShareLink> {
+        self.share_link_db.create_link(link).await
+    }
+
+    pub async fn get_links_by_folder(&self, folder: &ObjectId) -> Result<Vec<ShareLink>> {
+        self.share_link_db.get_links_by_folder(folder).await
+    }
+
+    pub async fn delete_link_by_id_folder(
+        &self,
+        link_id: &ObjectId,
+        folder: &ObjectId,
+    ) -> Result<ShareLink> {
+        self.share_link_db
+            .delete_link_by_id_folder(link_id, folder)
+            .await
+    }
+
+    // An expired link is treated the same as a link that does not exist
+    pub async fn get_link_by_token(&self, token: &str) -> Result<ShareLink> {
+        let link = self.share_link_db.get_link_by_token(token).await?;
+        if link.is_expired() {
+            return Err(Error::NotFound("This link has expired".into()));
+        }
+        Ok(link)
+    }
+
+    // The links stop working while the owner of the folder is locked
+    pub async fn get_link_folder(&self, link: &ShareLink) -> Result<Folder> {
+        let folder = self.folder_db.get_folder_by_id(&link.folder).await?;
+        if folder.hidden {
+            return Err(Error::NotFound(
+                "Cannot find the link with the provided information".into(),
+            ));
+        }
+        Ok(folder)
+    }
+
+    // The absolute position of a path inside the shared folder
+    // The path can only go down the tree, never above the shared folder
+    pub fn get_link_position(&self, folder: &Folder, path: &str) -> Result<String> {
+        let path = normalize_path(path);
+        check_dir(&path).map_err(into_string)?;
+        Ok(format!("{}{path}", folder.fullpath))
+    }
+
+    // The same position without the root folder in front, which is what File::new expects
+    pub fn get_link_relative_position(&self, folder: &Folder, path: &str) -> Result<String> {
+        let position = self.get_link_position(folder, path)?;
+        let (_, relative_position) = position
+            .split_once('/')
+            .ok_or("Cannot get the position inside the shared folder")?;
+        Ok(relative_position.to_string())
+    }
+
+    // Everything directly at the path inside the shared folder, regardless of the visibility
+    pub async fn get_link_content(
+        &self,
+        folder: &Folder,
+        path: &str,
+    ) -> Result<(Vec<Folder>, Vec<File>)> {
+        let position = self.get_link_position(folder, path)?;
+        // The root folder is at its own position, so it has to be left out
+        let folders = self
+            .folder_db
+            .get_folders_by(doc! {"position": &position, "fullpath": {"$ne": &position}})
+            .await?;
+        let files = self
+            .file_db
+            .get_files_by(doc! {"position": &position})
+            .await?;
+        Ok((folders, files))
+    }
+
+    pub async fn get_link_file(&self, folder: &Folder, file_id: &ObjectId) -> Result<File> {
+        let file = self.file_db.get_file_by_id(file_id).await?;
+        if !file.fullpath.starts_with(&folder.fullpath) {
+            return Err(Error::NotFound(
+                "Cannot find the file with the provided information".into(),
+            ));
+        }
+        Ok(file)
+    }
+}
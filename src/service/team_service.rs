@@ -0,0 +1,231 @@
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    aws::S3,
+    base::{
+        change::{Change, ChangeAction},
+        event::{Event, EventKind},
+        folder::Folder,
+        team::{Team, TeamMember, TeamRole},
+        user::User,
+    },
+    db::{
+        acl_db::AclDB, change_db::ChangeDB, file_db::FileDB, file_request_db::FileRequestDB,
+        file_version_db::FileVersionDB, folder_db::FolderDB, search_db::SearchDB,
+        share_link_db::ShareLinkDB, team_db::TeamDB, user_db::UserDB,
+    },
+    error::Error,
+    helper::event_bus::EventBus,
+    Result,
+};
+
+#[derive(Debug, Clone)]
+pub struct TeamService {
+    team_db: TeamDB,
+    user_db: UserDB,
+    file_db: FileDB,
+    folder_db: FolderDB,
+    file_version_db: FileVersionDB,
+    search_db: SearchDB,
+    acl_db: AclDB,
+    share_link_db: ShareLinkDB,
+    file_request_db: FileRequestDB,
+    change_db: ChangeDB,
+    storage: S3,
+    event_bus: EventBus,
+}
+
+impl TeamService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(
+        team_db: &TeamDB,
+        user_db: &UserDB,
+        file_db: &FileDB,
+        folder_db: &FolderDB,
+        file_version_db: &FileVersionDB,
+        search_db: &SearchDB,
+        acl_db: &AclDB,
+        share_link_db: &ShareLinkDB,
+        file_request_db: &FileRequestDB,
+        change_db: &ChangeDB,
+        storage: &S3,
+        event_bus: &EventBus,
+    ) -> Self {
+        Self {
+            team_db: team_db.clone(),
+            user_db: user_db.clone(),
+            file_db: file_db.clone(),
+            folder_db: folder_db.clone(),
+            file_version_db: file_version_db.clone(),
+            search_db: search_db.clone(),
+            acl_db: acl_db.clone(),
+            share_link_db: share_link_db.clone(),
+            file_request_db: file_request_db.clone(),
+            change_db: change_db.clone(),
+            storage: storage.clone(),
+            event_bus: event_bus.clone(),
+        }
+    }
+
+    pub async fn get_team_by_id(&self, team_id: &ObjectId) -> Result<Team> {
+        self.team_db.get_team_by_id(team_id).await
+    }
+
+    // Fails the same way as a missing team when the user is not a member,
+    // so that outsiders cannot tell which teams exist
+    pub async fn get_team_by_id_member(&self, team_id: &ObjectId, user: &ObjectId) -> Result<Team> {
+        let team = self.team_db.get_team_by_id(team_id).await?;
+        if team.role_of(user).is_none() {
+            return Err(Error::NotFound(
+                "Cannot find the team with the provided information".into(),
+            ));
+        }
+        Ok(team)
+    }
+
+    pub async fn get_teams_by_member(&self, user: &ObjectId) -> Result<Vec<Team>> {
+        self.team_db.get_teams_by_member(user).await
+    }
+
+    pub async fn get_teams_by_owner(&self, user: &ObjectId) -> Result<Vec<Team>> {
+        self.team_db.get_teams_by_owner(user).await
+    }
+
+    pub async fn create_team(&self, team: Team, owner: &User) -> Result<Team> {
+        if self.team_db.exists_team_by_name(&team.name).await? {
+            return Err(Error::Conflict(
+                "This team name is already taken. Please try another name".into(),
+            ));
+        }
+
+        let root_folder = Folder::new_team_root(owner, &team)?;
+        if self
+            .folder_db
+            .exists_folder_by_fullpath(&root_folder.fullpath)
+            .await?
+        {
+            return Err(Error::Conflict(
+                "This team name is already taken. Please try another name".into(),
+            ));
+        }
+
+        let new_team = self.team_db.create_team(team).await?;
+        self.folder_db.create_folder(root_folder).await?;
+
+        Ok(new_team)
+    }
+
+    pub async fn add_member(&self, team: &Team, username: &str, role: TeamRole) -> Result<Team> {
+        if role == TeamRole::Owner {
+            return Err("A team can only have one owner".into());
+        }
+        let user = self.user_db.get_user_by_username(username).await?;
+        self.team_db
+            .add_member(&team.id, TeamMember::new(user.id, role))
+            .await
+    }
+
+    pub async fn update_member_role(
+        &self,
+        team: &Team,
+        user: &ObjectId,
+        role: TeamRole,
+    ) -> Result<Team> {
+        if role == TeamRole::Owner || team.role_of(user) == Some(TeamRole::Owner) {
+            return Err("The owner of the team cannot be changed".into());
+        }
+        self.team_db.update_member_role(&team.id, user, role).await
+    }
+
+    pub async fn remove_member(&self, team: &Team, user: &ObjectId) -> Result<Team> {
+        if team.role_of(user) == Some(TeamRole::Owner) {
+            return Err("The owner cannot leave the team. Delete the team instead".into());
+        }
+        self.team_db.remove_member(&team.id, user).await
+    }
+
+    // Deletes the team along with all of its folders and files
+    pub async fn delete_team(&self, team_id: &ObjectId) -> Result<()> {
+        let deleted_team = self.team_db.delete_team(team_id).await?;
+
+        let files = self.file_db.get_files_by_team(&deleted_team.id).await?;
+        let folders = self.folder_db.get_folders_by_team(&deleted_team.id).await?;
+
+        let mut deleted_resources = folders.iter().map(|f| f.id).collect::<Vec<_>>();
+        deleted_resources.extend(files.iter().map(|f| f.id));
+
+        // The team is gone already, so its members and the users it shared with are kept for the event
+        let mut grantees = deleted_team
+            .members
+            .iter()
+            .map(|m| m.user)
+            .collect::<Vec<_>>();
+        for entry in self
+            .acl_db
+            .get_entries_by_resources(&deleted_resources)
+            .await?
+        {
+            if !grantees.contains(&entry.grantee) {
+                grantees.push(entry.grantee);
+            }
+        }
+
+        // The root folder is announced, and everything else gets a tombstone, like a deleted folder
+        // The root folder is the only one whose position is the same as its fullpath
+        let (root_folders, inner_folders) = folders
+            .iter()
+            .partition::<Vec<_>, _>(|f| f.position == f.fullpath);
+        let inner_changes = inner_folders
+            .into_iter()
+            .map(|f| Change::from_folder(ChangeAction::Deleted, f))
+            .chain(
+                files
+                    .iter()
+                    .map(|f| Change::from_file(ChangeAction::Deleted, f)),
+            )
+            .collect::<Vec<_>>();
+
+        for file in files {
+            let internal_full_filename = &file.internal_path();
+            let internal_file_version_path = &file.internal_version_folder();
+
+            self.file_version_db
+                .delete_versions_by_file_id(&file.id)
+                .await?;
+
+            self.storage.delete_file(internal_full_filename).await?;
+            self.storage
+                .delete_folder(internal_file_version_path)
+                .await?;
+        }
+
+        self.file_db.delete_files_by_team(&deleted_team.id).await?;
+        self.folder_db
+            .delete_folders_by_team(&deleted_team.id)
+            .await?;
+        self.search_db
+            .delete_entries_by_resources(&deleted_resources)
+            .await?;
+        self.acl_db
+            .delete_entries_by_resources(&deleted_resources)
+            .await?;
+        self.share_link_db
+            .delete_links_by_folders(&deleted_resources)
+            .await?;
+        self.file_request_db
+            .delete_requests_by_folders(&deleted_resources)
+            .await?;
+
+        for root_folder in root_folders {
+            let event =
+                Event::from_folder(EventKind::Deleted, root_folder).with_grantees(grantees.clone());
+            self.change_db
+                .append_changes(vec![Change::from_event(&event)])
+                .await?;
+            self.event_bus.publish(event);
+        }
+        self.change_db.append_changes(inner_changes).await?;
+
+        Ok(())
+    }
+}
@@ -1,35 +1,94 @@
-use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
+
+use chrono::Utc;
+use futures::{stream, try_join, StreamExt, TryStreamExt};
+use mongodb::bson::{doc, oid::ObjectId};
 
 use crate::{
     aws::S3,
-    base::{folder::Folder, user::User},
-    db::{file_db::FileDB, file_version_db::FileVersionDB, folder_db::FolderDB, user_db::UserDB},
+    base::{
+        change::Change,
+        event::{Event, EventKind},
+        folder::Folder,
+        user::{Identity, Status, TwoFactor, Usage, User},
+    },
+    db::{
+        acl_db::AclDB, change_db::ChangeDB, file_db::FileDB, file_request_db::FileRequestDB,
+        file_version_db::FileVersionDB, folder_db::FolderDB, search_db::SearchDB,
+        share_link_db::ShareLinkDB, team_db::TeamDB, user_db::UserDB, user_token_db::UserTokenDB,
+        webhook_db::WebhookDB, webhook_delivery_db::WebhookDeliveryDB,
+    },
+    error::Error,
+    helper::{
+        event_bus::EventBus,
+        hash::{hash_token, random_token},
+        oidc::IdClaims,
+        totp::{generate_recovery_codes, generate_secret, verify_code},
+    },
     Result,
 };
 
+// How many files have their size asked from the storage at the same time
+const SIZE_REQUESTS_AT_ONCE: usize = 16;
+
 #[derive(Debug, Clone)]
 pub struct UserService {
     user_db: UserDB,
     file_db: FileDB,
     folder_db: FolderDB,
     file_version_db: FileVersionDB,
+    search_db: SearchDB,
+    acl_db: AclDB,
+    team_db: TeamDB,
+    share_link_db: ShareLinkDB,
+    file_request_db: FileRequestDB,
+    user_token_db: UserTokenDB,
+    webhook_db: WebhookDB,
+    webhook_delivery_db: WebhookDeliveryDB,
+    change_db: ChangeDB,
     storage: S3,
+    event_bus: EventBus,
+    // How long a deleted account waits before it is purged, in days
+    deletion_grace_days: i64,
 }
 
 impl UserService {
+    #[allow(clippy::too_many_arguments)]
     pub fn init(
         user_db: &UserDB,
         file_db: &FileDB,
         folder_db: &FolderDB,
         file_verion_db: &FileVersionDB,
+        search_db: &SearchDB,
+        acl_db: &AclDB,
+        team_db: &TeamDB,
+        share_link_db: &ShareLinkDB,
+        file_request_db: &FileRequestDB,
+        user_token_db: &UserTokenDB,
+        webhook_db: &WebhookDB,
+        webhook_delivery_db: &WebhookDeliveryDB,
+        change_db: &ChangeDB,
         storage: &S3,
+        event_bus: &EventBus,
+        deletion_grace_days: i64,
     ) -> Self {
         Self {
             user_db: user_db.clone(),
             file_db: file_db.clone(),
             folder_db: folder_db.clone(),
             file_version_db: file_verion_db.clone(),
+            search_db: search_db.clone(),
+            acl_db: acl_db.clone(),
+            team_db: team_db.clone(),
+            share_link_db: share_link_db.clone(),
+            file_request_db: file_request_db.clone(),
+            user_token_db: user_token_db.clone(),
+            webhook_db: webhook_db.clone(),
+            webhook_delivery_db: webhook_delivery_db.clone(),
+            change_db: change_db.clone(),
             storage: storage.clone(),
+            event_bus: event_bus.clone(),
+            deletion_grace_days,
         }
     }
 
@@ -37,14 +96,43 @@ impl UserService {
         self.user_db.get_users().await
     }
 
+    pub async fn search_users(&self, query: &str) -> Result<Vec<User>> {
+        self.user_db.search_users(query).await
+    }
+
     pub async fn get_user_by_id(&self, user_id: &ObjectId) -> Result<User> {
         self.user_db.get_user_by_id(user_id).await
     }
 
+    pub async fn get_user_by_username(&self, username: &str) -> Result<User> {
+        self.user_db.get_user_by_username(username).await
+    }
+
+    // Returns the users with the provided ids, keyed by their id
+    // Duplicated ids are fine, each user is only fetched once
+    pub async fn get_users_map_by_ids(
+        &self,
+        user_ids: &[ObjectId],
+    ) -> Result<HashMap<ObjectId, User>> {
+        let mut ids = user_ids.to_vec();
+        ids.sort();
+        ids.dedup();
+        let users = self.user_db.get_users_by_ids(&ids).await?;
+        Ok(users.into_iter().map(|u| (u.id, u)).collect())
+    }
+
+    // The same error for a wrong username and a wrong password, so the usernames cannot be guessed
     pub async fn get_user_by_login_info(&self, username: &str, password: &str) -> Result<User> {
-        self.user_db
+        match self
+            .user_db
             .get_user_by_login_info(username, password)
             .await
+        {
+            Err(Error::NotFound(_)) => Err(Error::Unauthorized(
+                "The username or the password is incorrect".into(),
+            )),
+            result => result,
+        }
     }
 
     // pub async fn exists_user_by_id(&self, user_id: &ObjectId) -> Result<bool> {
@@ -67,16 +155,16 @@ impl UserService {
 
     pub async fn create_user(&self, user: User) -> Result<User> {
         if self.exists_user_by_email(&user.email).await? {
-            return Err(
+            return Err(Error::Conflict(
                 "There is a user associated with this email. Please use another email".into(),
-            );
+            ));
         }
 
         if self.exists_user_by_username(&user.username).await? {
-            return Err(
+            return Err(Error::Conflict(
                 "There is a user associated with this username. Please pick another username"
                     .into(),
-            );
+            ));
         }
 
         let new_user = self.user_db.create_user(user).await?;
@@ -87,22 +175,104 @@ impl UserService {
         Ok(new_user)
     }
 
+    // Finds the user who logged in with this identity before,
+    // or links the account with the same email, or creates a new account with its root folder
+    pub async fn get_or_create_sso_user(&self, provider: &str, claims: &IdClaims) -> Result<User> {
+        let identity = Identity {
+            provider: provider.to_string(),
+            subject: claims.sub.clone(),
+        };
+        if let Some(user) = self.user_db.find_user_by_identity(&identity).await? {
+            return Ok(user);
+        }
+
+        let email = claims
+            .email
+            .as_deref()
+            .ok_or("The identity provider did not share an email")?;
+
+        if self.exists_user_by_email(email).await? {
+            // Linking gives the identity the whole account, so only a verified email is trusted
+            if !claims.email_verified {
+                return Err(Error::Conflict(
+                    "There is a user associated with this email, but the identity provider has not verified it"
+                        .into(),
+                ));
+            }
+            let user = self.user_db.get_user_by_email(email).await?;
+            return self.user_db.add_identity(&user.id, identity, true).await;
+        }
+
+        // These users log in through the provider, the password only has to satisfy the rules
+        let password = format!("{}aA1!", random_token(32));
+        let username = self.get_available_username(claims).await?;
+        let user = User::new(ObjectId::new(), &username, email, &password, "", None)?;
+
+        let new_user = self.create_user(user).await?;
+        self.user_db
+            .add_identity(&new_user.id, identity, claims.email_verified)
+            .await
+    }
+
+    // Based on the username at the provider, or the email, with a random suffix if it is taken
+    async fn get_available_username(&self, claims: &IdClaims) -> Result<String> {
+        let base = claims
+            .preferred_username
+            .as_deref()
+            .or_else(|| claims.email.as_deref()?.split('@').next())
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect::<String>();
+        let base = match base.len() {
+            0..=1 => "user".to_string(),
+            _ => base,
+        };
+
+        if !self.exists_user_by_username(&base).await? {
+            return Ok(base);
+        }
+        for _ in 0..5 {
+            let username = format!("{base}-{}", random_token(4).to_lowercase());
+            if !self.exists_user_by_username(&username).await? {
+                return Ok(username);
+            }
+        }
+        Err("Cannot find an available username, please try again".into())
+    }
+
     pub async fn update_user(&self, user: User) -> Result<User> {
         let old_user = self.get_user_by_id(&user.id).await?;
         if old_user.username != user.username
             && self.exists_user_by_username(&user.username).await?
         {
-            return Err("This username is already taken. Please try another username".into());
+            return Err(Error::Conflict(
+                "This username is already taken. Please try another username".into(),
+            ));
         }
 
         if old_user.email != user.email && self.exists_user_by_email(&user.email).await? {
-            return Err("This email is already taken. Please try another email".into());
+            return Err(Error::Conflict(
+                "This email is already taken. Please try another email".into(),
+            ));
         }
 
-        let updated_user = self.user_db.update_user(&user.id.clone(), user).await?;
+        let mut updated_user = self.user_db.update_user(&user.id.clone(), user).await?;
 
-        let user_files = self.file_db.get_files_by_owner(&updated_user.id).await?;
-        for file in user_files {
+        // The new email has to be verified again
+        if old_user.email != updated_user.email {
+            updated_user = self
+                .user_db
+                .update_email_verified(&updated_user.id, &updated_user.email, false)
+                .await?;
+        }
+
+        // Only the personal tree is renamed, the trees of the teams are named after the team
+        let user_files = self
+            .file_db
+            .get_files_by(doc! {"owner": updated_user.id, "team": null})
+            .await?;
+        for file in &user_files {
             let (_, rest) = file
                 .position
                 .split_once('/')
@@ -116,9 +286,9 @@ impl UserService {
 
         let user_folders = self
             .folder_db
-            .get_folders_by_owner(&updated_user.id)
+            .get_folders_by(doc! {"owner": updated_user.id, "team": null})
             .await?;
-        for folder in user_folders {
+        for folder in &user_folders {
             let (_, rest) = folder
                 .position
                 .split_once('/')
@@ -140,9 +310,143 @@ impl UserService {
                 .await?;
         }
 
+        // The personal root folder is renamed along with the user, and everything inside moves with it
+        if old_user.username != updated_user.username {
+            let old_root = format!("{}/", old_user.username);
+            let root_folder = self
+                .folder_db
+                .get_folder_by_fullpath(&format!("{}/", updated_user.username))
+                .await?;
+
+            // For the sync clients, every file and folder of the tree has moved
+            let (new_files, new_folders) = try_join!(
+                self.file_db
+                    .get_files_by(doc! {"owner": updated_user.id, "team": null}),
+                self.folder_db
+                    .get_folders_by(doc! {"owner": updated_user.id, "team": null})
+            )?;
+            self.change_db
+                .append_changes(Change::moved_tree(
+                    &user_folders,
+                    &new_folders,
+                    &user_files,
+                    &new_files,
+                ))
+                .await?;
+
+            self.event_bus.publish(
+                Event::from_folder(EventKind::Updated, &root_folder)
+                    .moved_from(&old_root, &old_root),
+            );
+        }
+
         Ok(updated_user)
     }
 
+    // Starts over with a new secret, the old one stops working if it was not verified yet
+    pub async fn enroll_two_factor(&self, user_id: &ObjectId) -> Result<String> {
+        let user = self.get_user_by_id(user_id).await?;
+        if user.has_two_factor() {
+            return Err(Error::Conflict(
+                "Two-factor authentication is already enabled".into(),
+            ));
+        }
+
+        let secret = generate_secret();
+        self.user_db
+            .update_two_factor(
+                user_id,
+                Some(TwoFactor {
+                    secret: secret.clone(),
+                    enabled: false,
+                    recovery_codes: vec![],
+                    last_step: 0,
+                }),
+            )
+            .await?;
+        Ok(secret)
+    }
+
+    // The first code proves that the authenticator app is set up correctly
+    // Returns the recovery codes, which are only shown this once
+    pub async fn enable_two_factor(&self, user_id: &ObjectId, code: &str) -> Result<Vec<String>> {
+        let user = self.get_user_by_id(user_id).await?;
+        let two_factor = match user.two_factor {
+            Some(two_factor) if !two_factor.enabled => two_factor,
+            Some(_) => {
+                return Err(Error::Conflict(
+                    "Two-factor authentication is already enabled".into(),
+                ))
+            }
+            None => return Err("Enroll in two-factor authentication first".into()),
+        };
+
+        let step = verify_code(
+            &two_factor.secret,
+            code,
+            Utc::now().timestamp(),
+            two_factor.last_step,
+        )?
+        .ok_or("The code is not correct")?;
+
+        let recovery_codes = generate_recovery_codes();
+        self.user_db
+            .update_two_factor(
+                user_id,
+                Some(TwoFactor {
+                    enabled: true,
+                    recovery_codes: recovery_codes.iter().map(|c| hash_token(c)).collect(),
+                    last_step: step,
+                    ..two_factor
+                }),
+            )
+            .await?;
+        Ok(recovery_codes)
+    }
+
+    // Accepts a code from the app, or one of the recovery codes, which is used up
+    // The database only takes the code if nobody used it in the meantime,
+    // so the same code cannot get through two requests at once
+    pub async fn verify_two_factor(&self, user_id: &ObjectId, code: &str) -> Result<User> {
+        let user = self.get_user_by_id(user_id).await?;
+        let two_factor = user
+            .two_factor
+            .filter(|t| t.enabled)
+            .ok_or("Two-factor authentication is not enabled")?;
+
+        let step = verify_code(
+            &two_factor.secret,
+            code,
+            Utc::now().timestamp(),
+            two_factor.last_step,
+        )?;
+
+        let user = match step {
+            Some(step) => self.user_db.use_two_factor_step(user_id, step).await?,
+            None => {
+                self.user_db
+                    .use_recovery_code(user_id, &hash_token(code))
+                    .await?
+            }
+        };
+        user.ok_or_else(|| Error::Unauthorized("The code is not correct".into()))
+    }
+
+    pub async fn disable_two_factor(
+        &self,
+        user_id: &ObjectId,
+        password: &str,
+        code: &str,
+    ) -> Result<User> {
+        let user = self.get_user_by_id(user_id).await?;
+        if user.password != password {
+            return Err("The provided password does not match with the current password".into());
+        }
+
+        self.verify_two_factor(user_id, code).await?;
+        self.user_db.update_two_factor(user_id, None).await
+    }
+
     pub async fn update_refresh_token(
         &self,
         user_id: &ObjectId,
@@ -153,14 +457,185 @@ impl UserService {
             .await
     }
 
+    // The public content of the user is hidden for as long as the account is locked
+    async fn update_status_by_id(
+        &self,
+        user_id: &ObjectId,
+        status: Status,
+        deletion_scheduled_at: Option<i64>,
+    ) -> Result<User> {
+        let user = self
+            .user_db
+            .update_status(user_id, status, deletion_scheduled_at)
+            .await?;
+        let hidden = user.is_locked();
+        try_join!(
+            self.file_db.update_hidden_by_owner(&user.id, hidden),
+            self.folder_db.update_hidden_by_owner(&user.id, hidden)
+        )?;
+        Ok(user)
+    }
+
+    pub async fn suspend_user_by_id(&self, user_id: &ObjectId, suspended: bool) -> Result<User> {
+        let user = self.get_user_by_id(user_id).await?;
+
+        // A suspension does not replace a pending deletion, nor does lifting it cancel one
+        let status = match (suspended, user.status) {
+            (true, Status::Active) => Status::Suspended,
+            (false, Status::Suspended) => Status::Active,
+            (true, _) => return Err(Error::Conflict("This account is already locked".into())),
+            (false, _) => return Err("This account is not suspended".into()),
+        };
+
+        self.update_status_by_id(user_id, status, None).await
+    }
+
+    // Locks the account, which is purged once the grace period is over
+    pub async fn schedule_deletion_by_id(&self, user_id: &ObjectId) -> Result<User> {
+        // A team cannot be left without an owner
+        if !self.team_db.get_teams_by_owner(user_id).await?.is_empty() {
+            return Err("Delete the teams that you own before deleting your account".into());
+        }
+
+        let user = self.get_user_by_id(user_id).await?;
+        user.check_active()?;
+
+        let deletion_scheduled_at =
+            Utc::now().timestamp_millis() + self.deletion_grace_days * 24 * 60 * 60 * 1000;
+
+        self.update_status_by_id(
+            user_id,
+            Status::PendingDeletion,
+            Some(deletion_scheduled_at),
+        )
+        .await
+    }
+
+    // The account is locked, so the user proves who they are with the login info instead
+    // The second factor is asked for like on the login, the password alone is not enough
+    pub async fn cancel_deletion(
+        &self,
+        username: &str,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<User> {
+        let user = self.get_user_by_login_info(username, password).await?;
+
+        if user.status != Status::PendingDeletion {
+            return Err("This account is not scheduled for deletion".into());
+        }
+
+        if user.has_two_factor() {
+            let code = code.ok_or_else(|| {
+                Error::Unauthorized("Two-factor authentication is required, send the code".into())
+            })?;
+            self.verify_two_factor(&user.id, code).await?;
+        }
+
+        self.update_status_by_id(&user.id, Status::Active, None)
+            .await
+    }
+
+    // Deletes every account whose grace period is over, and returns how many were deleted
+    // One account failing does not stop the others, it is tried again on the next run
+    pub async fn purge_due_users(&self) -> Result<usize> {
+        let users = self
+            .user_db
+            .get_users_due_for_deletion(Utc::now().timestamp_millis())
+            .await?;
+
+        let mut purged = 0;
+        for user in users {
+            match self.delete_user_by_id(&user.id).await {
+                Ok(_) => purged += 1,
+                Err(e) => tracing::error!("Cannot purge the user {}: {e}", user.id),
+            }
+        }
+        Ok(purged)
+    }
+
+    pub async fn reset_password_by_id(&self, user_id: &ObjectId, password: &str) -> Result<User> {
+        self.user_db.update_password(user_id, password).await
+    }
+
+    // The files in the teams count toward the user who created them
+    pub async fn get_usage_by_id(&self, user_id: &ObjectId) -> Result<Usage> {
+        let user = self.get_user_by_id(user_id).await?;
+
+        let (files, folders) = try_join!(
+            self.file_db.get_files_by_owner(&user.id),
+            self.folder_db.get_folders_by_owner(&user.id)
+        )?;
+
+        let file_ids = files.iter().map(|f| f.id).collect::<Vec<_>>();
+        let versions = self
+            .file_version_db
+            .get_versions_by_file_ids(&file_ids)
+            .await?;
+
+        // The current content and the versions of a file are all stored under its id
+        let prefixes = file_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let bytes = stream::iter(prefixes)
+            .map(|prefix| async move { self.storage.get_size_by_prefix(&prefix).await })
+            .buffer_unordered(SIZE_REQUESTS_AT_ONCE)
+            .try_fold(0, |total, size| async move { Ok(total + size) })
+            .await?;
+
+        Ok(Usage {
+            user: user.id,
+            files: files.len() as u64,
+            folders: folders.len() as u64,
+            versions: versions.len() as u64,
+            bytes,
+        })
+    }
+
     pub async fn delete_user_by_id(&self, user_id: &ObjectId) -> Result<()> {
+        // A team cannot be left without an owner
+        if !self.team_db.get_teams_by_owner(user_id).await?.is_empty() {
+            return Err("Delete the teams that you own before deleting your account".into());
+        }
+
         let deleted_user = self.user_db.delete_user(user_id).await?;
 
+        // The files and folders that the user created in a team stay with the team,
+        // and are handed over to the owner of the team
+        for team in self.team_db.get_teams_by_member(&deleted_user.id).await? {
+            let team_owner = team.owner()?;
+            self.file_db
+                .transfer_team_files(&team.id, &deleted_user.id, team_owner)
+                .await?;
+            self.folder_db
+                .transfer_team_folders(&team.id, &deleted_user.id, team_owner)
+                .await?;
+            self.search_db
+                .transfer_team_entries(&team.id, &deleted_user.id, team_owner)
+                .await?;
+        }
+        self.team_db
+            .remove_member_from_teams(&deleted_user.id)
+            .await?;
+
+        // The personal tree goes away with its root folder, which is announced to everyone
+        // that the user shared something with, since their shares go away as well
+        let root_folder = self
+            .folder_db
+            .get_folder_by_fullpath(&format!("{}/", deleted_user.username))
+            .await
+            .ok();
+        let grantees = self
+            .acl_db
+            .get_entries_by_owner(&deleted_user.id)
+            .await?
+            .into_iter()
+            .map(|e| e.grantee)
+            .collect();
+
         let files = self.file_db.get_files_by_owner(&deleted_user.id).await?;
 
         for file in files {
-            let internal_full_filename = &format!("{}.{}", file.id, file.extension_to_str());
-            let internal_file_version_path = &format!("{}/", file.id);
+            let internal_full_filename = &file.internal_path();
+            let internal_file_version_path = &file.internal_version_folder();
 
             self.file_version_db
                 .delete_versions_by_file_id(&file.id)
@@ -176,7 +651,84 @@ impl UserService {
         self.folder_db
             .delete_folders_by_owner(&deleted_user.id)
             .await?;
+        self.search_db
+            .delete_entries_by_owner(&deleted_user.id)
+            .await?;
+        self.acl_db.delete_entries_by_user(&deleted_user.id).await?;
+        self.share_link_db
+            .delete_links_by_creator(&deleted_user.id)
+            .await?;
+        self.file_request_db
+            .delete_requests_by_creator(&deleted_user.id)
+            .await?;
+        self.user_token_db
+            .delete_tokens_by_user(&deleted_user.id)
+            .await?;
+        let webhooks = self
+            .webhook_db
+            .delete_webhooks_by_owner(&deleted_user.id)
+            .await?;
+        self.webhook_delivery_db
+            .delete_deliveries_by_webhooks(&webhooks)
+            .await?;
+
+        if let Some(root_folder) = root_folder {
+            self.event_bus.publish(
+                Event::from_folder(EventKind::Deleted, &root_folder).with_grantees(grantees),
+            );
+        }
 
         Ok(())
     }
+
+    // Counts how many files and folders of the user use each tag
+    // Returns the counts of the files first, then the counts of the folders
+    pub async fn get_tag_counts(
+        &self,
+        owner: &ObjectId,
+        public_only: bool,
+    ) -> Result<(Vec<(String, i64)>, Vec<(String, i64)>)> {
+        if !public_only {
+            let filter = doc! {"owner": owner};
+            return try_join!(
+                self.file_db.count_tags_by(filter.clone()),
+                self.folder_db.count_tags_by(filter)
+            );
+        }
+
+        // The inherit ones only count when the folders above them make them public
+        let inherit = doc! {"owner": owner, "visibility": "inherit"};
+        let (files, folders) = try_join!(
+            self.file_db.get_files_by(inherit.clone()),
+            self.folder_db.get_folders_by(inherit)
+        )?;
+        let dirs = files
+            .iter()
+            .map(|f| f.position.as_str())
+            .chain(folders.iter().map(|f| f.position.as_str()))
+            .collect::<Vec<_>>();
+        let dir_visibilities = self.folder_db.get_dir_visibilities(&dirs).await?;
+
+        let public_file_ids = files
+            .iter()
+            .filter(|f| f.is_public(&dir_visibilities))
+            .map(|f| f.id)
+            .collect::<Vec<_>>();
+        let public_folder_ids = folders
+            .iter()
+            .filter(|f| f.is_public(&dir_visibilities))
+            .map(|f| f.id)
+            .collect::<Vec<_>>();
+
+        try_join!(
+            self.file_db.count_tags_by(doc! {
+                "owner": owner,
+                "$or": [{"visibility": "public"}, {"_id": {"$in": public_file_ids}}]
+            }),
+            self.folder_db.count_tags_by(doc! {
+                "owner": owner,
+                "$or": [{"visibility": "public"}, {"_id": {"$in": public_folder_ids}}]
+            })
+        )
+    }
 }
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{
+    base::{
+        event::Event,
+        webhook::Webhook,
+        webhook_delivery::{DeliveryAttempt, DeliveryStatus, WebhookDelivery},
+    },
+    config::WebhooksConfig,
+    db::{webhook_db::WebhookDB, webhook_delivery_db::WebhookDeliveryDB},
+    error::Error,
+    helper::{
+        into_string,
+        webhook::{check_webhook_host, send_webhook, webhook_client},
+    },
+    response::webhook::WebhookPayload,
+    Result,
+};
+
+pub const MAX_WEBHOOKS_PER_USER: u64 = 20;
+
+// Only the latest deliveries are listed, the older ones are still kept
+pub const MAX_LISTED_DELIVERIES: i64 = 100;
+
+#[derive(Debug, Clone)]
+pub struct WebhookService {
+    webhook_db: WebhookDB,
+    webhook_delivery_db: WebhookDeliveryDB,
+    timeout: Duration,
+    allow_private_hosts: bool,
+    max_attempts: usize,
+}
+
+impl WebhookService {
+    pub fn init(
+        webhook_db: &WebhookDB,
+        webhook_delivery_db: &WebhookDeliveryDB,
+        config: &WebhooksConfig,
+    ) -> Self {
+        Self {
+            webhook_db: webhook_db.clone(),
+            webhook_delivery_db: webhook_delivery_db.clone(),
+            timeout: Duration::from_secs(config.timeout),
+            allow_private_hosts: config.allow_private_hosts,
+            max_attempts: config.max_attempts,
+        }
+    }
+
+    pub async fn create_webhook(&self, webhook: Webhook) -> Result<Webhook> {
+        if self
+            .webhook_db
+            .count_webhooks_by_owner(&webhook.owner)
+            .await?
+            >= MAX_WEBHOOKS_PER_USER
+        {
+            return Err(Error::QuotaExceeded(
+                "There can only be 20 webhooks per user".into(),
+            ));
+        }
+        check_webhook_host(&webhook.url, self.allow_private_hosts).await?;
+        self.webhook_db.create_webhook(webhook).await
+    }
+
+    pub async fn get_webhooks_by_owner(&self, owner: &ObjectId) -> Result<Vec<Webhook>> {
+        self.webhook_db.get_webhooks_by_owner(owner).await
+    }
+
+    pub async fn get_webhook_by_id_owner(
+        &self,
+        webhook_id: &ObjectId,
+        owner: &ObjectId,
+    ) -> Result<Webhook> {
+        self.webhook_db
+            .get_webhook_by_id_owner(webhook_id, owner)
+            .await
+    }
+
+    pub async fn update_webhook(&self, webhook: Webhook) -> Result<Webhook> {
+        check_webhook_host(&webhook.url, self.allow_private_hosts).await?;
+        self.webhook_db
+            .update_webhook(
+                &webhook.id,
+                doc! {
+                    "url": webhook.url,
+                    "events": webhook.events,
+                    "active": webhook.active,
+                    "updatedAt": webhook.updated_at,
+                },
+            )
+            .await
+    }
+
+    // The deliveries go along with the webhook
+    pub async fn delete_webhook(&self, webhook_id: &ObjectId) -> Result<Webhook> {
+        let webhook = self.webhook_db.delete_webhook_by_id(webhook_id).await?;
+        self.webhook_delivery_db
+            .delete_deliveries_by_webhooks(&[webhook.id])
+            .await?;
+        Ok(webhook)
+    }
+
+    pub async fn get_deliveries(&self, webhook_id: &ObjectId) -> Result<Vec<WebhookDelivery>> {
+        self.webhook_delivery_db
+            .get_deliveries_by_webhook(webhook_id, MAX_LISTED_DELIVERIES)
+            .await
+    }
+
+    pub async fn get_delivery_by_id_webhook(
+        &self,
+        delivery_id: &ObjectId,
+        webhook_id: &ObjectId,
+    ) -> Result<WebhookDelivery> {
+        self.webhook_delivery_db
+            .get_delivery_by_id_webhook(delivery_id, webhook_id)
+            .await
+    }
+
+    // Who can see the event is left to the caller, since it depends on the owner of each webhook
+    pub async fn get_webhooks_for_event(&self, event: &Event) -> Result<Vec<Webhook>> {
+        self.webhook_db.get_webhooks_by_event(&event.name()).await
+    }
+
+    // The event id is shared by every webhook that gets the same event
+    pub async fn queue_delivery(
+        &self,
+        webhook: &Webhook,
+        event_id: &ObjectId,
+        event: &Event,
+    ) -> Result<WebhookDelivery> {
+        let name = event.name();
+        let payload = serde_json::to_string(&WebhookPayload {
+            id: event_id.to_string(),
+            event: name.clone(),
+            created_at: event.created_at,
+            data: event.clone().into_response(),
+        })
+        .map_err(into_string)?;
+
+        self.webhook_delivery_db
+            .create_delivery(WebhookDelivery::new(webhook.id, *event_id, &name, payload))
+            .await
+    }
+
+    // Sends the same payload again as a new delivery, and waits for the first try
+    pub async fn redeliver(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery> {
+        let new_delivery = self
+            .webhook_delivery_db
+            .create_delivery(delivery.redeliver())
+            .await?;
+        self.deliver(&new_delivery.id).await?;
+        self.webhook_delivery_db
+            .get_delivery_by_id_webhook(&new_delivery.id, &new_delivery.webhook)
+            .await
+    }
+
+    // Nothing happens when someone else is already sending it
+    pub async fn deliver(&self, delivery_id: &ObjectId) -> Result<()> {
+        if let Some(delivery) = self
+            .webhook_delivery_db
+            .claim_delivery_by_id(delivery_id)
+            .await?
+        {
+            self.attempt(delivery).await?;
+        }
+        Ok(())
+    }
+
+    // Tries every delivery that is waiting for a retry, returns how many were tried
+    pub async fn deliver_due(&self) -> Result<usize> {
+        let mut count = 0;
+        while let Some(delivery) = self.webhook_delivery_db.claim_due_delivery().await? {
+            self.attempt(delivery).await?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    async fn attempt(&self, mut delivery: WebhookDelivery) -> Result<()> {
+        let webhook = match self.webhook_db.get_webhook_by_id(&delivery.webhook).await {
+            Ok(webhook) => Some(webhook),
+            Err(Error::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        let attempt = match webhook {
+            Some(webhook) if webhook.active => {
+                // The client is made for each delivery, to only reach the addresses that were checked
+                let client = check_webhook_host(&webhook.url, self.allow_private_hosts)
+                    .await
+                    .and_then(|addresses| webhook_client(&webhook.url, &addresses, self.timeout));
+                match client {
+                    Ok(client) => {
+                        send_webhook(
+                            &client,
+                            &webhook.url,
+                            &webhook.secret,
+                            &delivery.event,
+                            &delivery.id.to_string(),
+                            &delivery.payload,
+                        )
+                        .await
+                    }
+                    Err(e) => DeliveryAttempt::failed(&e.to_string()),
+                }
+            }
+            // There is no point in trying again
+            Some(_) => {
+                return self
+                    .give_up(delivery, "The webhook has been disabled")
+                    .await
+            }
+            None => return self.give_up(delivery, "The webhook has been deleted").await,
+        };
+
+        delivery.attempts.push(attempt.clone());
+        let (status, next_attempt_at) = delivery.after_attempt(self.max_attempts);
+        self.webhook_delivery_db
+            .record_attempt(&delivery.id, attempt, status, next_attempt_at)
+            .await
+    }
+
+    async fn give_up(&self, delivery: WebhookDelivery, reason: &str) -> Result<()> {
+        self.webhook_delivery_db
+            .record_attempt(
+                &delivery.id,
+                DeliveryAttempt::failed(reason),
+                DeliveryStatus::Failed,
+                None,
+            )
+            .await
+    }
+}
@@ -1,58 +1,127 @@
+use std::collections::BTreeMap;
+
 use validator::ValidationError;
 
+use crate::helper::make_error::validation_message;
+
 use super::check_with;
 
+pub const MAX_TAGS: usize = 32;
+pub const MAX_METADATA: usize = 32;
+pub const MAX_METADATA_VALUE: usize = 1024;
+pub const MAX_NAME_LENGTH: usize = 255;
+
+const FORBIDDEN_NAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
 pub fn check_filename(filename: &str) -> Result<(), ValidationError> {
-    // This regex matches hello,
-    // Rejects hello.txt and .txt
-    // This can also match hello_world or hello-world
-    check_with(
-        filename,
-        r#"^[a-zA-Z0-9-_]{3,}$"#,
-        "The name can only contain a-z A-Z 0-9 and within 3 to 20 characters in length",
-    )
+    // Names like hello, Q1 report, .env or Báo cáo tháng 1 are all fine
+    check_name(filename)
 }
 
 pub fn check_extension(extension: &str) -> Result<(), ValidationError> {
+    // Any extension is accepted here, which ones are allowed is decided by the extension policy
+    // It can be empty for files without an extension
     check_with(
         extension,
-        r#"^(txt|png|jpg|jpeg|mp3)$"#,
-        "Supported extensions are txt, png, jpg, jpeg, mp3",
+        r#"^([a-zA-Z0-9]{1,16})?$"#,
+        "The extension can only contain a-z A-Z 0-9 and at most 16 characters in length",
     )
 }
 
 pub fn check_visibility(visibility: &str) -> Result<(), ValidationError> {
     check_with(
         visibility,
-        r#"^(public|private)$"#,
-        "Visibility can only be public or private",
+        r#"^(public|private|inherit)$"#,
+        "Visibility can only be public, private or inherit",
+    )
+}
+
+pub fn check_role(role: &str) -> Result<(), ValidationError> {
+    check_with(
+        role,
+        r#"^(viewer|editor)$"#,
+        "Role can only be viewer or editor",
     )
 }
 
+pub fn check_link_password(password: &str) -> Result<(), ValidationError> {
+    check_with(
+        password,
+        r#"^.{8,}$"#,
+        "Link password must be at least 8 characters",
+    )
+}
+
+pub fn check_uploader_name(name: &str) -> Result<(), ValidationError> {
+    let length = name.trim().chars().count();
+    if length == 0 || length > 100 {
+        return Err(validation_message(
+            "The uploader name must be between 1 and 100 characters",
+        ));
+    }
+    Ok(())
+}
+
 pub fn check_full_filename(full_filename: &str) -> Result<(), ValidationError> {
-    // This regex will match cases like hello.txt, hello or .txt or just txt will fail
-    check_with(full_filename, r#"^[a-zA-Z0-9-_]{3,}\.(txt|mp3|png|jpg|jpeg)$"#, "The full filename must met filename requirements and can only have txt, mp3, png, jpeg or jpg extension")
+    // The extension is a part of the name, so the whole name goes through the same policy
+    // This will match cases like hello.txt, archive.tar.gz, Q1 report.png or hello without an extension
+    check_name(full_filename)
 }
 
 pub fn check_dir(position: &str) -> Result<(), ValidationError> {
-    // This regex will match for cases like user/, user/hello/, hello-world/user/, hello_world/something/
+    // This will match for cases like user/, user/hello/, hello world/user/, or an empty position
     // It will reject cases like user, /user, /user/, or user/hello
-    // Basically it requires a slash must exists at the end
-    check_with(
-        position,
-        r#"^([a-zA-Z0-9-_]{3,}[/])*$"#,
-        "The dir input is in wrong format",
-    )
+    // Basically it requires a slash must exists at the end, and every folder name must be valid
+    if position.is_empty() {
+        return Ok(());
+    }
+    let dirs = position
+        .strip_suffix('/')
+        .ok_or_else(|| validation_message("The dir input is in wrong format"))?;
+    dirs.split('/').try_for_each(check_name)
 }
 
 pub fn check_fullpath(fullpath: &str) -> Result<(), ValidationError> {
-    // This regex will match cases like user/hello.txt, hello.txt, or nested/something-deep/hello.txt
-    // This will reject cases like hello/.txt, hello/world, or even hello/
-    check_with(
-        fullpath,
-        r#"^(([a-zA-Z0-9-_]{3,}[/])*)[a-zA-Z0-9-_]{3,}\.(png|txt|jpg|jpeg|mp3)$"#,
-        "The fullpath is incorrect",
-    )
+    // This will match cases like user/hello.txt, hello.txt, user/archive.tar.gz or nested/some thing/hello
+    // This will reject cases like hello/, /hello.txt or hello//world.txt
+    match fullpath.rsplit_once('/') {
+        Some((dir, name)) => {
+            check_dir(&format!("{dir}/"))?;
+            check_name(name)
+        }
+        None => check_name(fullpath),
+    }
+}
+
+// The policy that every file and folder name has to follow
+// Characters that have a special meaning in paths or that Windows cannot store are not allowed,
+// neither are control characters and spaces around the name
+pub fn check_name(name: &str) -> Result<(), ValidationError> {
+    if name.is_empty() {
+        return Err(validation_message("The name cannot be empty"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(validation_message(
+            "The name can only be 255 characters in length",
+        ));
+    }
+    if name == "." || name == ".." {
+        return Err(validation_message("The name cannot be . or .."));
+    }
+    if name.starts_with(char::is_whitespace) || name.ends_with(char::is_whitespace) {
+        return Err(validation_message(
+            "The name cannot start or end with a space",
+        ));
+    }
+    if name
+        .chars()
+        .any(|c| c.is_control() || FORBIDDEN_NAME_CHARS.contains(&c))
+    {
+        return Err(validation_message(
+            r#"The name cannot contain control characters or any of / \ : * ? " < > |"#,
+        ));
+    }
+    Ok(())
 }
 
 // pub fn check_version_path(version_path: &str) -> Result<(), ValidationError> {
@@ -78,6 +147,94 @@ pub fn check_fullpath(fullpath: &str) -> Result<(), ValidationError> {
 //     )
 // }
 
+pub fn check_tag(tag: &str) -> Result<(), ValidationError> {
+    // Tags can be written in any language, but cannot contain spaces or symbols
+    check_with(
+        tag,
+        r#"^[\p{L}\p{N}_-]{1,32}$"#,
+        "A tag can only contain letters, digits, - and _, and at most 32 characters in length",
+    )
+}
+
+pub fn check_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_TAGS {
+        return Err(validation_message("There can only be 32 tags at most"));
+    }
+    tags.iter().try_for_each(|t| check_tag(t))
+}
+
+pub fn check_metadata_key(key: &str) -> Result<(), ValidationError> {
+    // The key becomes a MongoDB field name, so dots and dollar signs are not allowed
+    check_with(
+        key,
+        r#"^[a-zA-Z0-9-_]{1,64}$"#,
+        "A metadata key can only contain a-z A-Z 0-9 - _ and at most 64 characters in length",
+    )
+}
+
+pub fn check_metadata(metadata: &BTreeMap<String, String>) -> Result<(), ValidationError> {
+    if metadata.len() > MAX_METADATA {
+        return Err(validation_message(
+            "There can only be 32 metadata entries at most",
+        ));
+    }
+    for (key, value) in metadata {
+        check_metadata_key(key)?;
+        if value.chars().count() > MAX_METADATA_VALUE {
+            return Err(validation_message(
+                "A metadata value can only be 1024 characters in length",
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn check_folder_name(folder_name: &str) -> Result<(), ValidationError> {
-    check_filename(folder_name)
+    check_name(folder_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_ordinary_names() {
+        for name in [
+            "report.pdf",
+            "Q1 (draft).txt",
+            "résumé.docx",
+            "日本語",
+            ".env",
+            "a+b[1]{2}$^",
+        ] {
+            assert!(check_name(name).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn refuses_names_that_could_escape_their_place() {
+        let names = [
+            "",
+            ".",
+            "..",
+            " padded",
+            "padded ",
+            "a/b",
+            r"a\b",
+            "a\"b",
+            "line\r\nbreak",
+            "tab\there",
+            "nul\0",
+            "a:b",
+            "a*b",
+            "a?b",
+            "<a>",
+            "a|b",
+        ];
+        for name in names {
+            assert!(check_name(name).is_err(), "{name:?}");
+        }
+        assert!(check_name(&"a".repeat(256)).is_err());
+        assert!(check_name(&"é".repeat(255)).is_ok());
+    }
 }
@@ -4,7 +4,9 @@ use validator::ValidationError;
 use crate::helper::make_error::validation_message;
 
 pub mod file;
+pub mod team;
 pub mod user;
+pub mod webhook;
 
 pub fn check_with(
     test_str: &str,
@@ -0,0 +1,20 @@
+use validator::ValidationError;
+
+use super::check_with;
+
+pub fn check_team_name(name: &str) -> Result<(), ValidationError> {
+    check_with(
+        name,
+        r#"^[a-zA-Z0-9-_]{2,}$"#,
+        "Team name must be a-z, A-Z, 0-9, -_ and at least 2 characters",
+    )
+}
+
+pub fn check_team_role(role: &str) -> Result<(), ValidationError> {
+    // The owner is not assigned, there is only one, which is the user creating the team
+    check_with(
+        role,
+        r#"^(admin|member)$"#,
+        "Team role can only be admin or member",
+    )
+}
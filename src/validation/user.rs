@@ -17,3 +17,12 @@ pub fn check_password(password: &str) -> Result<(), ValidationError> {
          "Password must contains at least one lowercase, one uppercase, one digit, one special character, and at least 8 characters in length"
     )
 }
+
+// Either a code from the authenticator app, or a recovery code like k3j9x-0qp2m
+pub fn check_two_factor_code(code: &str) -> Result<(), ValidationError> {
+    check_with(
+        code,
+        r#"^([0-9]{6}|[a-z0-9]{5}-[a-z0-9]{5})$"#,
+        "The code must be 6 digits, or a recovery code",
+    )
+}
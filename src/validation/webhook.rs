@@ -0,0 +1,35 @@
+use reqwest::Url;
+use validator::ValidationError;
+
+use crate::{base::webhook::WEBHOOK_EVENTS, helper::make_error::validation_message};
+
+pub const MAX_URL_LENGTH: usize = 2048;
+
+// Whether the host is allowed is only checked when the URL is resolved, see helper::webhook
+pub fn check_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if url.len() > MAX_URL_LENGTH {
+        return Err(validation_message(
+            "The webhook URL can be at most 2048 characters in length",
+        ));
+    }
+    match Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => Ok(()),
+        _ => Err(validation_message(
+            "The webhook URL must be an http or https URL",
+        )),
+    }
+}
+
+pub fn check_webhook_events(events: &[String]) -> Result<(), ValidationError> {
+    if events.is_empty() {
+        return Err(validation_message(
+            "The webhook must be subscribed to at least one event",
+        ));
+    }
+    if events.iter().any(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+        return Err(validation_message(
+            "The events can only be file.created, file.updated, file.moved, file.deleted, version.restored, folder.created, folder.updated, folder.moved or folder.deleted",
+        ));
+    }
+    Ok(())
+}
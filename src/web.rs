@@ -1,21 +1,33 @@
 use salvo::{prelude::StatusCode, writer::Json, Piece, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use utoipa::ToSchema;
 
 use crate::WebResult;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+// Every response has this shape, the code is the http status, like 404
+// The errors also have a stable error code that the clients can match on, like "not_found",
+// and the validation errors list the problems of each field in the details
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct Web {
-    code: String,
+    code: u16,
     message: String,
+    #[schema(value_type = Object)]
     data: Value,
     error: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    error_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[schema(value_type = Option<Object>)]
+    details: Option<Value>,
 }
 
 impl Piece for Web {
     fn render(self, res: &mut Response) {
+        let status = StatusCode::from_u16(self.code).unwrap_or(StatusCode::OK);
         res.render(Json(self));
-        res.set_status_code(StatusCode::OK)
+        res.set_status_code(status)
     }
 }
 
@@ -33,45 +45,39 @@ impl Web {
         error: impl ToString,
     ) -> Self {
         Self {
-            code: code.to_string(),
+            code: code.as_u16(),
             message: message.to_string(),
             data: json!(&data),
             error: error.to_string(),
+            error_code: None,
+            details: None,
         }
     }
 
     pub fn ok<T: for<'a> Deserialize<'a> + Serialize>(message: impl ToString, data: T) -> Web {
         Self {
-            code: StatusCode::OK.to_string(),
+            code: StatusCode::OK.as_u16(),
             message: message.to_string(),
             data: json!(&data),
             error: "".to_string(),
+            error_code: None,
+            details: None,
         }
     }
 
-    pub fn bad_request(error: impl ToString) -> Web {
+    pub fn error(code: StatusCode, error_code: &str, error: impl ToString) -> Web {
         Self {
-            code: StatusCode::BAD_REQUEST.to_string(),
+            code: code.as_u16(),
             message: String::default(),
             data: json!(&()),
             error: error.to_string(),
+            error_code: Some(error_code.to_string()),
+            details: None,
         }
     }
 
-    pub fn internal_error(error: impl ToString) -> Web {
-        Self {
-            code: StatusCode::INTERNAL_SERVER_ERROR.to_string(),
-            message: String::default(),
-            data: json!(&()),
-            error: error.to_string(),
-        }
-    }
-    pub fn forbidden(error: impl ToString) -> Web {
-        Self {
-            code: StatusCode::FORBIDDEN.to_string(),
-            message: String::default(),
-            data: json!(&()),
-            error: error.to_string(),
-        }
+    pub fn with_details(mut self, details: Value) -> Web {
+        self.details = Some(details);
+        self
     }
 }